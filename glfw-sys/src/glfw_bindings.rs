@@ -3,6 +3,7 @@ use std::ffi::{c_char, c_int, c_uchar, c_void};
 pub static GLFW_CONTEXT_VERSION_MAJOR: c_int = 0x0002_2002;
 pub static GLFW_CONTEXT_VERSION_MINOR: c_int = 0x0002_2003;
 pub static GLFW_OPENGL_PROFILE: c_int = 0x0002_2008;
+pub static GLFW_SRGB_CAPABLE: c_int = 0x0002_100E;
 pub static GLFW_OPENGL_CORE_PROFILE: c_int = 0x0003_2001;
 pub static GLFW_CURSOR_DISABLED: c_int = 0x0003_4003;
 pub static GLFW_CURSOR: c_int = 0x0003_3001;
@@ -15,11 +16,14 @@ pub static GLFW_RELEASE: c_int = 0;
 pub static GLFW_PRESS: c_int = 1;
 pub static GLFW_REPEAT: c_int = 2;
 
+pub static GLFW_MOUSE_BUTTON_LEFT: c_int = 0;
+
 pub type GLFWglproc = *const c_void;
 pub type GLFWframebuffersizefun = extern "C" fn(*mut GLFWwindow, c_int, c_int);
 pub type GLFWkeyfun = extern "C" fn(*mut GLFWwindow, c_int, c_int, c_int, c_int);
 pub type GLFWcursorposfun = extern "C" fn(*mut GLFWwindow, f64, f64);
 pub type GLFWscrollfun = extern "C" fn(*mut GLFWwindow, f64, f64);
+pub type GLFWmousebuttonfun = extern "C" fn(*mut GLFWwindow, c_int, c_int, c_int);
 
 extern "C" {
     pub fn glfwInit() -> c_int;
@@ -44,6 +48,7 @@ extern "C" {
     pub fn glfwGetProcAddress(procname: *const c_uchar) -> GLFWglproc;
     pub fn glfwGetTime() -> f64;
     pub fn glfwGetInputMode(window: *mut GLFWwindow, mode: c_int) -> c_int;
+    pub fn glfwGetCursorPos(window: *mut GLFWwindow, xpos: *mut f64, ypos: *mut f64);
 
     //callbacks
     pub fn glfwSetInputMode(window: *mut GLFWwindow, mode: c_int, value: c_int);
@@ -62,6 +67,10 @@ extern "C" {
         window: *mut GLFWwindow,
         callback: Option<GLFWscrollfun>,
     ) -> Option<GLFWscrollfun>;
+    pub fn glfwSetMouseButtonCallback(
+        window: *mut GLFWwindow,
+        callback: Option<GLFWmousebuttonfun>,
+    ) -> Option<GLFWmousebuttonfun>;
 }
 
 pub enum GLFWmonitor {}
@@ -4,7 +4,7 @@ use phoenix::{
     components::{
         color::{Color, RGBA},
         geometry::plane::Triangle,
-        texture::{load, Config, Filtering, MinFiltering, Texture, Wrapping},
+        texture::{load, ColorSpace, Config, Filtering, MinFiltering, Texture, Wrapping},
         Component,
     },
     entities::entity::Entity,
@@ -80,6 +80,7 @@ pub fn test_2d_texture_brick_wall_triangle(window: Rc<Window>, render: Box<dyn R
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Filtering(Filtering::Linear),
         max_filtering: Filtering::Linear,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "brickwall.jpg";
@@ -119,6 +120,7 @@ pub fn test_2d_texture_two_brick_wall_triangle(window: Rc<Window>, render: Box<d
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Filtering(Filtering::Linear),
         max_filtering: Filtering::Linear,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "brickwall.jpg";
@@ -160,6 +162,7 @@ pub fn test_2d_texture_brick_wall_uniform_red_triangle(
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Filtering(Filtering::Nearest),
         max_filtering: Filtering::Nearest,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "brickwall.jpg";
@@ -199,6 +202,7 @@ pub fn test_2d_texture_brick_wall_disco_triangle(window: Rc<Window>, render: Box
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Filtering(Filtering::Nearest),
         max_filtering: Filtering::Nearest,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "brickwall.jpg";
@@ -232,6 +236,7 @@ pub fn test_2d_texture_happy_face_linear_texture(window: Rc<Window>, render: Box
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Filtering(Filtering::Linear),
         max_filtering: Filtering::Linear,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "awesomeface.png";
@@ -264,6 +269,7 @@ pub fn test_2d_texture_two_triangle_two_textures(window: Rc<Window>, render: Box
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Filtering(Filtering::Linear),
         max_filtering: Filtering::Linear,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "brickwall.jpg";
@@ -313,6 +319,7 @@ pub fn test_2d_texture_one_triangle_two_textures(window: Rc<Window>, render: Box
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Filtering(Filtering::Linear),
         max_filtering: Filtering::Linear,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "background.png";
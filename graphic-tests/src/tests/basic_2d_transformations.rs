@@ -5,7 +5,7 @@ use phoenix::{
     components::{
         color::{Color, RGBA},
         geometry::plane::Triangle,
-        texture::{load, Config, Filtering, MinFiltering, Mipmaps, Texture, Wrapping},
+        texture::{load, ColorSpace, Config, Filtering, MinFiltering, Mipmaps, Texture, Wrapping},
         transformer::Builder,
         Component,
     },
@@ -199,6 +199,7 @@ pub fn test_2d_transformation_triangle_rotation_scale_perspective(
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Mipmap(Mipmaps::LinearMipmapLinear),
         max_filtering: Filtering::Linear,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "brickwall.jpg";
@@ -9,8 +9,8 @@ use phoenix::{
             Point,
         },
         texture::{
-            self, generate_default_vertices_for_cube, load, Filtering, MinFiltering, Mipmaps,
-            Texture, Wrapping,
+            self, generate_default_vertices_for_cube, load, ColorSpace, Filtering, MinFiltering,
+            Mipmaps, Texture, Wrapping,
         },
         transformer::Builder,
         Component, FillMode,
@@ -57,6 +57,7 @@ pub fn test_3d_basic_gold_cube_on_green_background(window: Rc<Window>, render: B
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Mipmap(Mipmaps::LinearMipmapLinear),
         max_filtering: Filtering::Nearest,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "brickwall.jpg";
@@ -9,8 +9,8 @@ use phoenix::{
         light::Light,
         material::Material,
         texture::{
-            generate_default_vertices_for_cube, load, Config, Filtering, MinFiltering, Texture,
-            Wrapping,
+            generate_default_vertices_for_cube, load, ColorSpace, Config, Filtering, MinFiltering,
+            Texture, Wrapping,
         },
         transformer::Builder,
         Component,
@@ -172,6 +172,7 @@ pub fn test_3d_light_container_diffuse_specular_maps(window: Rc<Window>, render:
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Filtering(Filtering::Linear),
         max_filtering: Filtering::Linear,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = TEST_TEXTURE_DIR.to_owned() + "wodden_container.png";
@@ -184,9 +185,13 @@ pub fn test_3d_light_container_diffuse_specular_maps(window: Rc<Window>, render:
 
     let second_path = TEST_TEXTURE_DIR.to_owned() + "steel_frame.png";
     let second_texture_data = load(Path::new(&second_path)).unwrap();
+    //the second texture is the specular map, its values are data, not colors
     let steel_frame = Texture::new_with_vertices(
         second_texture_data,
-        texture_config,
+        Config {
+            color_space: ColorSpace::Linear,
+            ..texture_config
+        },
         generate_default_vertices_for_cube(),
     );
 
@@ -1,6 +1,8 @@
+use std::collections::HashMap;
+
 use cgmath::{InnerSpace, Vector3, Zero};
 
-use crate::components::Shape;
+use crate::components::{FillMode, NormalMode, Shape};
 
 pub type ID = u32;
 
@@ -11,23 +13,175 @@ pub struct IdGarbageCollector {
 }
 
 pub fn calculate_normal_vec_for_shape(shape: &dyn Shape) -> Vec<f32> {
-    let mut result = Vec::with_capacity(shape.get_vertices().len());
+    calculate_normals(
+        shape.get_vertices(),
+        shape.get_indices(),
+        shape.get_fill_mode(),
+        shape.get_normal_mode(),
+    )
+}
+
+/// Per-vertex normals of the triangles described by the vertices, the indices and the fill mode.
+/// Vertices shared by several faces get the sum of their normals, weighted by the face area.
+pub fn calculate_normals(
+    vertices: &[f32],
+    indices: Option<&[u32]>,
+    fill_mode: FillMode,
+    mode: NormalMode,
+) -> Vec<f32> {
+    let vertices_count = vertices.len() / 3;
+    let position = |index: u32| {
+        let start = index as usize * 3;
+        Vector3::new(vertices[start], vertices[start + 1], vertices[start + 2])
+    };
+
+    let triangles = collect_triangles(vertices_count, indices, fill_mode);
+    let centroid = calculate_centroid(vertices);
+    let face_normals: Vec<Vector3<f32>> = triangles
+        .iter()
+        .map(|[a, b, c]| {
+            let (a, b, c) = (position(*a), position(*b), position(*c));
+            let normal = calculate_normal_vec(&a, &b, &c);
+            if mode == NormalMode::Flat {
+                check_normal_direction(a, b, c, centroid, normal)
+            } else {
+                normal
+            }
+        })
+        .collect();
+
+    let mut normals = vec![Vector3::zero(); vertices_count];
+    if let NormalMode::Smooth { crease_angle } = mode {
+        smooth_normals(
+            vertices,
+            &triangles,
+            &face_normals,
+            crease_angle,
+            &mut normals,
+        );
+    } else {
+        for (triangle, normal) in triangles.iter().zip(&face_normals) {
+            for index in triangle {
+                normals[*index as usize] += *normal;
+            }
+        }
+    }
 
-    let centroid = calculate_centroid(shape.get_vertices());
+    normals
+        .into_iter()
+        .flat_map(|normal| {
+            let slice: [f32; 3] = if normal.is_zero() {
+                normal.into()
+            } else {
+                normal.normalize().into()
+            };
+            slice
+        })
+        .collect()
+}
+
+/// Copies the attribute values (`size` floats per vertex) in the order of the indices,
+/// so no vertex is shared between faces. Returns `None` when an index is out of range.
+pub fn unindex_attribute(values: &[f32], indices: &[u32], size: usize) -> Option<Vec<f32>> {
+    let mut result = Vec::with_capacity(indices.len() * size);
+    for index in indices {
+        let start = *index as usize * size;
+        result.extend_from_slice(values.get(start..start + size)?);
+    }
+    Some(result)
+}
 
-    for triangle in shape.get_vertices().chunks(9) {
-        let vector_one = Vector3::new(triangle[0], triangle[1], triangle[2]);
-        let vector_two = Vector3::new(triangle[3], triangle[4], triangle[5]);
-        let vector_three = Vector3::new(triangle[6], triangle[7], triangle[8]);
+/// Triangles drawn by the fill mode, as indices into the vertices.
+/// Points and lines do not form any.
+pub fn collect_triangles(
+    vertices_count: usize,
+    indices: Option<&[u32]>,
+    fill_mode: FillMode,
+) -> Vec<[u32; 3]> {
+    let order: Vec<u32> = match indices {
+        Some(indices) => indices.to_vec(),
+        None => (0..u32::try_from(vertices_count).unwrap_or(u32::MAX)).collect(),
+    };
 
-        let normal = calculate_normal_vec(&vector_one, &vector_two, &vector_three);
-        let slice: [f32; 3] =
-            check_normal_direction(vector_one, vector_two, vector_three, centroid, normal).into();
-        result.extend_from_slice(&slice);
-        result.extend_from_slice(&slice);
-        result.extend_from_slice(&slice);
+    match fill_mode {
+        FillMode::Solid => order
+            .chunks_exact(3)
+            .map(|triangle| [triangle[0], triangle[1], triangle[2]])
+            .collect(),
+        //every second triangle of a strip is reversed to keep the winding of the first one
+        FillMode::TriangleStrip => order
+            .windows(3)
+            .enumerate()
+            .map(|(position, triangle)| {
+                if position.is_multiple_of(2) {
+                    [triangle[0], triangle[1], triangle[2]]
+                } else {
+                    [triangle[1], triangle[0], triangle[2]]
+                }
+            })
+            .collect(),
+        FillMode::Fan => order
+            .windows(2)
+            .skip(1)
+            .map(|edge| [order[0], edge[0], edge[1]])
+            .collect(),
+        FillMode::Points { .. } | FillMode::Lines | FillMode::LineStrip | FillMode::LineLoop => {
+            Vec::new()
+        }
+    }
+}
+
+/// Faces meeting at one position are averaged when the angle between them
+/// is not bigger than the crease angle, so sharp edges stay sharp.
+fn smooth_normals(
+    vertices: &[f32],
+    triangles: &[[u32; 3]],
+    face_normals: &[Vector3<f32>],
+    crease_angle: f32,
+    normals: &mut [Vector3<f32>],
+) {
+    let key = |index: u32| {
+        let start = index as usize * 3;
+        [
+            vertices[start].to_bits(),
+            vertices[start + 1].to_bits(),
+            vertices[start + 2].to_bits(),
+        ]
+    };
+
+    let mut faces_at_position: HashMap<[u32; 3], Vec<usize>> = HashMap::new();
+    let mut faces_at_vertex: Vec<Vec<usize>> = vec![Vec::new(); normals.len()];
+    for (face, triangle) in triangles.iter().enumerate() {
+        for index in triangle {
+            faces_at_position.entry(key(*index)).or_default().push(face);
+            faces_at_vertex[*index as usize].push(face);
+        }
+    }
+
+    let unit = |face: usize| {
+        let normal = face_normals[face];
+        if normal.is_zero() {
+            normal
+        } else {
+            normal.normalize()
+        }
+    };
+    let min_cos = f32::to_radians(crease_angle).cos() - 1e-6;
+
+    for (vertex, own_faces) in (0_u32..).zip(&faces_at_vertex) {
+        if own_faces.is_empty() {
+            continue;
+        }
+
+        for face in &faces_at_position[&key(vertex)] {
+            if own_faces
+                .iter()
+                .any(|own| unit(*own).dot(unit(*face)) >= min_cos)
+            {
+                normals[vertex as usize] += face_normals[*face];
+            }
+        }
     }
-    result
 }
 
 fn calculate_normal_vec(a: &Vector3<f32>, b: &Vector3<f32>, c: &Vector3<f32>) -> Vector3<f32> {
@@ -121,6 +275,134 @@ mod tests {
         assert_eq!(id_gc.get_renewable_ids_num(), 0);
     }
 
+    #[test]
+    fn test_calculate_normal_vec_for_indexed_shape() {
+        let vertices = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0];
+        let indices = vec![0, 1, 2, 2, 3, 0];
+        let normals =
+            calculate_normals(&vertices, Some(&indices), FillMode::Solid, NormalMode::Flat);
+
+        assert_eq!(normals.len(), vertices.len());
+        for normal in normals.chunks(3) {
+            assert_eq!(normal[2].abs(), 1.0);
+        }
+    }
+
+    #[test]
+    fn test_calculate_normal_vec_for_indexed_shape_unused_vertex() {
+        let vertices = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 5.0, 5.0, 5.0];
+        let indices = vec![0, 1, 2];
+        let normals =
+            calculate_normals(&vertices, Some(&indices), FillMode::Solid, NormalMode::Flat);
+
+        assert_eq!(&normals[9..], &[0.0, 0.0, 0.0]);
+    }
+
+    // Two faces of a roof meeting at 90 degrees, the shared edge is duplicated
+    const ROOF: [f32; 18] = [
+        0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 2.0, 0.0, 0.0, 1.0, 1.0, 1.0,
+    ];
+
+    #[test]
+    fn test_calculate_normals_winding_ignores_centroid() {
+        let winding = calculate_normals(&ROOF, None, FillMode::Solid, NormalMode::Winding);
+        let expected_first = Vector3::new(1.0, -1.0, 1.0).normalize();
+        let expected_second = Vector3::new(-1.0, -1.0, 0.0).normalize();
+        assert!(
+            (Vector3::new(winding[0], winding[1], winding[2]) - expected_first).magnitude() < 1e-6
+        );
+        assert!(
+            (Vector3::new(winding[9], winding[10], winding[11]) - expected_second).magnitude()
+                < 1e-6
+        );
+
+        // The centroid test turns the first face of this open shape around
+        let flat = calculate_normals(&ROOF, None, FillMode::Solid, NormalMode::Flat);
+        assert!((Vector3::new(flat[0], flat[1], flat[2]) + expected_first).magnitude() < 1e-6);
+    }
+
+    #[test]
+    fn test_calculate_normals_smooth_crease_angle() {
+        let normal_at = |normals: &[f32], vertex: usize| {
+            Vector3::new(
+                normals[vertex * 3],
+                normals[vertex * 3 + 1],
+                normals[vertex * 3 + 2],
+            )
+        };
+
+        // Faces at 90 degrees are averaged above the crease angle
+        let smooth = calculate_normals(
+            &ROOF,
+            None,
+            FillMode::Solid,
+            NormalMode::Smooth {
+                crease_angle: 180.0,
+            },
+        );
+        assert!((normal_at(&smooth, 1) - normal_at(&smooth, 3)).magnitude() < 1e-6);
+        assert!((normal_at(&smooth, 0) - normal_at(&smooth, 1)).magnitude() > 0.1);
+
+        // and stay flat below it
+        let creased = calculate_normals(
+            &ROOF,
+            None,
+            FillMode::Solid,
+            NormalMode::Smooth { crease_angle: 30.0 },
+        );
+        let flat = calculate_normals(&ROOF, None, FillMode::Solid, NormalMode::Winding);
+        for (a, b) in creased.iter().zip(&flat) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_calculate_normals_fan() {
+        let vertices = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 0.0];
+        let normals = calculate_normals(&vertices, None, FillMode::Fan, NormalMode::Winding);
+        for normal in normals.chunks(3) {
+            assert_eq!(normal, [0.0, 0.0, 1.0]);
+        }
+    }
+
+    #[test]
+    fn test_calculate_normals_triangle_strip() {
+        //zigzag quad strip, the second triangle is clockwise as listed
+        let vertices = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0];
+        let normals = calculate_normals(
+            &vertices,
+            None,
+            FillMode::TriangleStrip,
+            NormalMode::Winding,
+        );
+        for normal in normals.chunks(3) {
+            assert_eq!(normal, [0.0, 0.0, 1.0]);
+        }
+    }
+
+    #[test]
+    fn test_calculate_normals_without_triangles() {
+        let vertices = [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0];
+        for fill_mode in [
+            FillMode::Points { size: 4.0 },
+            FillMode::LineStrip,
+            FillMode::LineLoop,
+        ] {
+            let normals = calculate_normals(&vertices, None, fill_mode, NormalMode::Winding);
+            assert_eq!(normals.len(), vertices.len());
+        }
+    }
+
+    #[test]
+    fn test_unindex_attribute() {
+        let colors = [0.0, 0.1, 1.0, 1.1, 2.0, 2.1];
+        assert_eq!(
+            unindex_attribute(&colors, &[2, 0, 2], 2).unwrap(),
+            vec![2.0, 2.1, 0.0, 0.1, 2.0, 2.1]
+        );
+        assert!(unindex_attribute(&colors, &[3], 2).is_none());
+    }
+
     #[test]
     fn test_calculate_centroid() {
         let vertices = vec![
@@ -1,17 +1,31 @@
 use color::Color;
+use instances::Instances;
 use light::Light;
+use lod::Lod;
 use material::Material;
+use particles::Emitter;
+use sprite::Sprite;
+use text::Text;
 use texture::Texture;
+use tilemap::Tilemap;
 use transformer::Transformer;
 
+use self::geometry::bounds::Bounds;
 use self::shaders::ShaderSource;
 
 pub mod color;
 pub mod geometry;
+pub mod instances;
 pub mod light;
+pub mod lod;
 pub mod material;
+pub mod model;
+pub mod particles;
 pub mod shaders;
+pub mod sprite;
+pub mod text;
 pub mod texture;
+pub mod tilemap;
 pub mod transformer;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -24,6 +38,30 @@ pub enum Error {
     ReadFileError(#[from] std::io::Error),
     #[error("Error when loading texture data")]
     ImageError(#[from] image::ImageError),
+    #[error("Invalid geometry: {0}")]
+    GeometryError(String),
+    #[error("Parse error in {path} at line {line}: {message}")]
+    ParseError {
+        path: String,
+        line: usize,
+        message: String,
+    },
+    #[error("Error when loading glTF model: {0}")]
+    GltfError(#[from] gltf::Error),
+    #[error("Unsupported model feature: {0}")]
+    UnsupportedModelError(String),
+    #[error("Invalid instances: {0}")]
+    InstancesError(String),
+    #[error("Invalid particle emitter: {0}")]
+    ParticlesError(String),
+    #[error("Invalid font: {0}")]
+    FontError(String),
+    #[error("Invalid sprite: {0}")]
+    SpriteError(String),
+    #[error("Invalid tilemap: {0}")]
+    TilemapError(String),
+    #[error("Invalid color: {0}")]
+    ColorError(String),
 }
 
 pub enum Component {
@@ -34,29 +72,114 @@ pub enum Component {
     Transformer(Transformer),
     Light(Light),
     Material(Material),
+    Lod(Lod),
+    Instances(Instances),
+    Particles(Box<Emitter>),
+    Text(Box<Text>),
+    Sprite(Box<Sprite>),
+    Tilemap(Box<Tilemap>),
 }
 
+/// Primitive topology used to draw the vertices (or indices) of a shape.
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum FillMode {
+    /// Every vertex is a square point, `size` is in pixels.
+    Points { size: f32 },
+    /// Every pair of vertices is a separate line.
     Lines,
+    /// Connected lines, open at the last vertex.
+    LineStrip,
+    /// Connected lines, the last vertex is joined with the first one.
+    LineLoop,
+    /// Every three vertices are a separate triangle.
     Solid,
+    /// Every vertex after the first two forms a triangle with the two previous ones.
+    TriangleStrip,
+    /// Every vertex after the first two forms a triangle with the previous one and the first one.
     Fan,
 }
 
+/// How a transparent entity is mixed with what is already drawn behind it.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Blending {
+    /// Mixed by the alpha, fits smoke and text.
+    #[default]
+    Alpha,
+    /// Added to the colors behind, overlapping fragments get brighter. Fits sparks and fire.
+    Additive,
+}
+
+/// How the renderer generates normals for shapes which do not provide them.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum NormalMode {
+    /// One normal per face, turned away from the shape centroid. Fits convex shapes of any winding.
+    #[default]
+    Flat,
+    /// One normal per face, taken from the counter-clockwise winding order.
+    /// Fits concave and open shapes.
+    Winding,
+    /// Normals averaged over the faces sharing a position, oriented by the winding order.
+    /// Faces meeting at an angle (in degrees) bigger than `crease_angle` keep a hard edge.
+    Smooth { crease_angle: f32 },
+}
+
 pub trait Shape {
     fn get_vertices(&self) -> &[f32];
+    /// Indices into `get_vertices` (counted in vertices, not floats).
+    /// Shapes without an index list are drawn vertex by vertex.
+    fn get_indices(&self) -> Option<&[u32]> {
+        None
+    }
+    /// Per-vertex normals, the renderer calculates them when `None`.
+    fn get_normals(&self) -> Option<&[f32]> {
+        None
+    }
+    /// Per-vertex texture coordinates, overridden by the texture component vertices.
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        None
+    }
+    /// Per-vertex RGBA colors, overridden by the color component.
+    fn get_colors(&self) -> Option<&[f32]> {
+        None
+    }
     fn get_type(&self) -> ShapeType;
     fn get_fill_mode(&self) -> FillMode {
         FillMode::Solid
     }
+    fn get_normal_mode(&self) -> NormalMode {
+        NormalMode::default()
+    }
+    /// Bounding volumes in the shape's own space, `None` for a shape without vertices.
+    fn get_bounds(&self) -> Option<Bounds> {
+        Bounds::from_vertices(self.get_vertices())
+    }
 }
 
 #[derive(PartialEq, Debug, Clone)]
 pub enum ShapeType {
     Triangle,
     Circle,
+    Rectangle,
+    RoundedRectangle,
+    RegularPolygon,
+    Ring,
+    Arc,
+    Sector,
+    Polygon,
+    Polyline,
     Cube,
     Sphere,
+    Cylinder,
+    Cone,
+    Torus,
+    Capsule,
+    Plane,
+    Terrain,
+    Mesh,
+    Particles,
+    Text,
+    Sprite,
+    Tilemap,
 }
 
 impl Default for FillMode {
@@ -67,7 +190,7 @@ impl Default for FillMode {
 
 #[cfg(test)]
 mod tests {
-    use crate::components::{Component, FillMode};
+    use crate::components::{Component, FillMode, NormalMode};
 
     use std::mem;
     const MEMORY_USAGE_FOR_COMPONENTS_ENUM: usize = 72;
@@ -84,4 +207,9 @@ mod tests {
     fn test_fill_mode_default() {
         assert_eq!(FillMode::Solid, FillMode::default());
     }
+
+    #[test]
+    fn test_normal_mode_default() {
+        assert_eq!(NormalMode::Flat, NormalMode::default());
+    }
 }
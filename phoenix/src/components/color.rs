@@ -1,6 +1,11 @@
+use std::str::FromStr;
+
 use cgmath::Vector3;
 
 use super::light::Light;
+use super::Error;
+
+pub mod gradient;
 
 #[derive(PartialEq, Debug)]
 pub struct Color {
@@ -13,6 +18,7 @@ enum Type {
     Vertices(Vec<f32>),
 }
 
+/// Channels are stored sRGB encoded, as colors are usually picked. The alpha is linear.
 #[derive(Clone, Debug, PartialEq)]
 pub struct RGBA {
     r: u8,
@@ -142,6 +148,83 @@ impl RGBA {
         }
     }
 
+    /// Hue in degrees, saturation and value from 0 to 1.
+    #[must_use]
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let value = value.clamp(0.0, 1.0);
+        let chroma = value * saturation;
+        let [red, green, blue] = RGBA::hue_to_rgb(hue, chroma);
+        let offset = value - chroma;
+        RGBA::from_normalized(red + offset, green + offset, blue + offset, alpha)
+    }
+
+    /// Hue in degrees, saturation and lightness from 0 to 1.
+    #[must_use]
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Self {
+        let saturation = saturation.clamp(0.0, 1.0);
+        let lightness = lightness.clamp(0.0, 1.0);
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        let [red, green, blue] = RGBA::hue_to_rgb(hue, chroma);
+        let offset = lightness - chroma / 2.0;
+        RGBA::from_normalized(red + offset, green + offset, blue + offset, alpha)
+    }
+
+    /// Hue in degrees, saturation and value from 0 to 1. Grays have zero hue.
+    #[must_use]
+    pub fn to_hsv(&self) -> [f32; 3] {
+        let (hue, max, min) = self.hue_max_min();
+        let saturation = if max > 0.0 { (max - min) / max } else { 0.0 };
+        [hue, saturation, max]
+    }
+
+    /// Hue in degrees, saturation and lightness from 0 to 1. Grays have zero hue.
+    #[must_use]
+    pub fn to_hsl(&self) -> [f32; 3] {
+        let (hue, max, min) = self.hue_max_min();
+        let lightness = f32::midpoint(max, min);
+        let chroma = max - min;
+        let saturation = if chroma > 0.0 {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        } else {
+            0.0
+        };
+        [hue, saturation, lightness]
+    }
+
+    /// Channels from 0 to 1, still sRGB encoded.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn from_normalized(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        let to_u8 = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+        RGBA::new(to_u8(red), to_u8(green), to_u8(blue), alpha)
+    }
+
+    /// Channels in linear light from 0 to 1, encoded to sRGB for storing.
+    #[must_use]
+    pub fn from_linear(red: f32, green: f32, blue: f32, alpha: f32) -> Self {
+        RGBA::from_normalized(
+            linear_to_srgb(red),
+            linear_to_srgb(green),
+            linear_to_srgb(blue),
+            alpha,
+        )
+    }
+
+    /// Channels decoded from sRGB to linear light, the space where the light and blending math is right.
+    #[must_use]
+    pub fn get_as_linear_f32(&self) -> [f32; 4] {
+        let [red, green, blue, alpha] = self.get_as_normalized_f32();
+        [
+            srgb_to_linear(red),
+            srgb_to_linear(green),
+            srgb_to_linear(blue),
+            alpha,
+        ]
+    }
+
+    /// Channels as they are stored, without decoding the sRGB.
     #[must_use]
     pub fn get_as_normalized_f32(&self) -> [f32; 4] {
         let red = RGBA::convert_from_u8_to_normalized_f32(self.r);
@@ -156,6 +239,37 @@ impl RGBA {
         (self.r, self.g, self.b, self.a)
     }
 
+    /// Red, green and blue of a fully saturated hue with the given chroma.
+    fn hue_to_rgb(hue: f32, chroma: f32) -> [f32; 3] {
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let second = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+        match sector {
+            sector if sector < 1.0 => [chroma, second, 0.0],
+            sector if sector < 2.0 => [second, chroma, 0.0],
+            sector if sector < 3.0 => [0.0, chroma, second],
+            sector if sector < 4.0 => [0.0, second, chroma],
+            sector if sector < 5.0 => [second, 0.0, chroma],
+            _ => [chroma, 0.0, second],
+        }
+    }
+
+    fn hue_max_min(&self) -> (f32, f32, f32) {
+        let [red, green, blue, _] = self.get_as_normalized_f32();
+        let max = red.max(green).max(blue);
+        let min = red.min(green).min(blue);
+        let chroma = max - min;
+        let hue = if chroma <= 0.0 {
+            0.0
+        } else if (max - red).abs() < f32::EPSILON {
+            60.0 * ((green - blue) / chroma).rem_euclid(6.0)
+        } else if (max - green).abs() < f32::EPSILON {
+            60.0 * ((blue - red) / chroma + 2.0)
+        } else {
+            60.0 * ((red - green) / chroma + 4.0)
+        };
+        (hue, max, min)
+    }
+
     #[must_use]
     fn convert_from_u8_to_normalized_f32(number: u8) -> f32 {
         (1_f32 / f32::from(u8::MAX)) * f32::from(number)
@@ -175,6 +289,146 @@ impl RGBA {
     }
 }
 
+/// sRGB transfer function, from the encoded value to linear light.
+#[must_use]
+pub fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.040_45 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Inverse sRGB transfer function, from linear light to the encoded value.
+#[must_use]
+pub fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Parses CSS colors: `#rgb`, `#rgba`, `#rrggbb`, `#rrggbbaa`, `rgb()`, `rgba()`, `hsl()`, `hsla()`
+/// and the basic named colors.
+impl FromStr for RGBA {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let text = text.trim().to_ascii_lowercase();
+        let error = || Error::ColorError(format!("cannot parse \"{text}\""));
+
+        if let Some(hex) = text.strip_prefix('#') {
+            return parse_hex(hex).ok_or_else(error);
+        }
+        if let Some((function, arguments)) =
+            text.strip_suffix(')').and_then(|text| text.split_once('('))
+        {
+            return parse_function(function.trim(), arguments).ok_or_else(error);
+        }
+        named_color(&text).ok_or_else(error)
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<RGBA> {
+    if !hex.chars().all(|character| character.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digits: Vec<u8> = match hex.len() {
+        //short forms repeat every digit
+        3 | 4 => hex
+            .chars()
+            .map(|digit| u8::from_str_radix(&format!("{digit}{digit}"), 16).ok())
+            .collect::<Option<_>>()?,
+        6 | 8 => (0..hex.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&hex[index..index + 2], 16).ok())
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+    let alpha = digits
+        .get(3)
+        .map_or(1.0, |alpha| RGBA::convert_from_u8_to_normalized_f32(*alpha));
+    Some(RGBA::new(digits[0], digits[1], digits[2], alpha))
+}
+
+fn parse_function(function: &str, arguments: &str) -> Option<RGBA> {
+    let arguments: Vec<&str> = arguments
+        .split([',', '/', ' '])
+        .filter(|argument| !argument.is_empty())
+        .collect();
+    let alpha = match arguments.len() {
+        3 => 1.0,
+        4 => parse_fraction(arguments[3])?,
+        _ => return None,
+    };
+
+    match function {
+        "rgb" | "rgba" => {
+            let channel = |argument: &str| match argument.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().ok().map(|value| value / 100.0),
+                None => argument.parse::<f32>().ok().map(|value| value / 255.0),
+            };
+            Some(RGBA::from_normalized(
+                channel(arguments[0])?,
+                channel(arguments[1])?,
+                channel(arguments[2])?,
+                alpha,
+            ))
+        }
+        "hsl" | "hsla" => {
+            let hue = arguments[0].strip_suffix("deg").unwrap_or(arguments[0]);
+            let percent = |argument: &str| {
+                argument
+                    .strip_suffix('%')?
+                    .parse::<f32>()
+                    .ok()
+                    .map(|value| value / 100.0)
+            };
+            Some(RGBA::from_hsl(
+                hue.parse().ok()?,
+                percent(arguments[1])?,
+                percent(arguments[2])?,
+                alpha,
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// A number from 0 to 1 or a percentage.
+fn parse_fraction(argument: &str) -> Option<f32> {
+    match argument.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|value| value / 100.0),
+        None => argument.parse().ok(),
+    }
+}
+
+fn named_color(name: &str) -> Option<RGBA> {
+    let hex = match name {
+        "black" => 0x00_00_00_FF,
+        "silver" => 0xC0_C0_C0_FF,
+        "gray" | "grey" => 0x80_80_80_FF,
+        "white" => 0xFF_FF_FF_FF,
+        "maroon" => 0x80_00_00_FF,
+        "red" => 0xFF_00_00_FF,
+        "purple" => 0x80_00_80_FF,
+        "fuchsia" | "magenta" => 0xFF_00_FF_FF,
+        "green" => 0x00_80_00_FF,
+        "lime" => 0x00_FF_00_FF,
+        "olive" => 0x80_80_00_FF,
+        "yellow" => 0xFF_FF_00_FF,
+        "navy" => 0x00_00_80_FF,
+        "blue" => 0x00_00_FF_FF,
+        "teal" => 0x00_80_80_FF,
+        "aqua" | "cyan" => 0x00_FF_FF_FF,
+        "orange" => 0xFF_A5_00_FF,
+        "transparent" => 0x00_00_00_00,
+        _ => return None,
+    };
+    Some(RGBA::from_hex(hex))
+}
+
 impl From<&Light> for RGBA {
     fn from(light: &Light) -> Self {
         light.diffuse.into()
@@ -291,6 +545,99 @@ mod tests {
         assert!(!color_from_vertices.is_uniform());
     }
 
+    #[test]
+    fn test_rgba_hsv() {
+        assert_eq!(
+            RGBA::from_hsv(0.0, 1.0, 1.0, 1.0),
+            RGBA::new(255, 0, 0, 1.0)
+        );
+        assert_eq!(
+            RGBA::from_hsv(240.0, 0.5, 0.5, 1.0),
+            RGBA::new(64, 64, 128, 1.0)
+        );
+        //the hue wraps around
+        assert_eq!(
+            RGBA::from_hsv(-240.0, 1.0, 1.0, 1.0),
+            RGBA::from_hsv(120.0, 1.0, 1.0, 1.0)
+        );
+
+        let [hue, saturation, value] = RGBA::from_hex(0xFF_80_00_FF).to_hsv();
+        assert!((hue - 30.1).abs() < 0.1);
+        assert!((saturation - 1.0).abs() < 1e-6);
+        assert!((value - 1.0).abs() < 1e-6);
+        assert_eq!(RGBA::new(128, 128, 128, 1.0).to_hsv()[..2], [0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_rgba_hsl() {
+        assert_eq!(
+            RGBA::from_hsl(120.0, 1.0, 0.25, 0.5),
+            RGBA::new(0, 128, 0, 0.5)
+        );
+        assert_eq!(RGBA::from_hsl(0.0, 0.0, 1.0, 1.0), RGBA::new_white());
+
+        let [hue, saturation, lightness] = RGBA::new(0, 128, 0, 1.0).to_hsl();
+        assert!((hue - 120.0).abs() < 1e-3);
+        assert!((saturation - 1.0).abs() < 1e-6);
+        assert!((lightness - 0.251).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_rgba_srgb_linear() {
+        assert!((srgb_to_linear(0.5) - 0.214).abs() < 1e-3);
+        assert!((linear_to_srgb(0.214) - 0.5).abs() < 1e-3);
+        assert!((srgb_to_linear(0.02) - 0.02 / 12.92).abs() < 1e-6);
+
+        let gray = RGBA::new(128, 128, 128, 0.5);
+        let linear = gray.get_as_linear_f32();
+        assert!((linear[0] - 0.216).abs() < 1e-3);
+        assert_eq!(linear[3..], [0.5]);
+        assert_eq!(
+            RGBA::from_linear(linear[0], linear[1], linear[2], linear[3]),
+            gray
+        );
+    }
+
+    #[test]
+    fn test_rgba_from_css() {
+        assert_eq!("#f0a".parse::<RGBA>().unwrap(), RGBA::new(255, 0, 170, 1.0));
+        assert_eq!(
+            "#FF000080".parse::<RGBA>().unwrap(),
+            RGBA::from_hex(0xFF_00_00_80)
+        );
+        assert_eq!(
+            "rgb(255, 128, 0)".parse::<RGBA>().unwrap(),
+            RGBA::new(255, 128, 0, 1.0)
+        );
+        assert_eq!(
+            "rgba(100%, 0%, 0%, 0.25)".parse::<RGBA>().unwrap(),
+            RGBA::new(255, 0, 0, 0.25)
+        );
+        assert_eq!(
+            "rgb(0 0 255 / 50%)".parse::<RGBA>().unwrap(),
+            RGBA::new(0, 0, 255, 0.5)
+        );
+        assert_eq!(
+            "hsl(120deg, 100%, 25%)".parse::<RGBA>().unwrap(),
+            RGBA::new(0, 128, 0, 1.0)
+        );
+        assert_eq!(
+            " Orange ".parse::<RGBA>().unwrap(),
+            RGBA::from_hex(0xFF_A5_00_FF)
+        );
+
+        for invalid in [
+            "#12",
+            "#ggg",
+            "rgb(1, 2)",
+            "cmyk(1, 2, 3)",
+            "hsl(1, 2, 3)",
+            "reddish",
+        ] {
+            assert!(invalid.parse::<RGBA>().is_err(), "{invalid}");
+        }
+    }
+
     #[test]
     fn test_rgba_from_vector() {
         let vec3 = Vector3::new(0.8, 0.8, 0.8);
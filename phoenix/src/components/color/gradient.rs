@@ -0,0 +1,208 @@
+use cgmath::{InnerSpace, MetricSpace, Vector3};
+
+use super::{linear_to_srgb, Color, RGBA};
+use crate::components::{Error, Result, Shape};
+
+/// How the position of a vertex picks its place between the gradient stops.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Kind {
+    /// Along the line from `start` (offset 0) to `end` (offset 1), constant across it.
+    Linear {
+        start: Vector3<f32>,
+        end: Vector3<f32>,
+    },
+    /// By the distance from `center`, offset 1 at `radius`.
+    Radial { center: Vector3<f32>, radius: f32 },
+}
+
+/// Colors at offsets from 0 to 1, mixed in linear light to per-vertex colors of a shape.
+/// Vertices in the shape's own space are colored, before the transformer moves them.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Gradient {
+    kind: Kind,
+    /// Sorted by the offset, the colors are in linear light.
+    stops: Vec<(f32, [f32; 4])>,
+}
+
+impl Gradient {
+    /// # Errors
+    ///
+    /// Will return `Err` if there is no stop or an offset is not a number.
+    pub fn linear(start: Vector3<f32>, end: Vector3<f32>, stops: &[(f32, RGBA)]) -> Result<Self> {
+        Self::new(Kind::Linear { start, end }, stops)
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if there is no stop, an offset is not a number or the radius is not positive.
+    pub fn radial(center: Vector3<f32>, radius: f32, stops: &[(f32, RGBA)]) -> Result<Self> {
+        if radius <= 0.0 {
+            return Err(Error::ColorError(format!(
+                "radial gradient radius {radius} is not positive"
+            )));
+        }
+        Self::new(Kind::Radial { center, radius }, stops)
+    }
+
+    fn new(kind: Kind, stops: &[(f32, RGBA)]) -> Result<Self> {
+        if stops.is_empty() {
+            return Err(Error::ColorError("gradient has no stops".to_string()));
+        }
+        if stops.iter().any(|(offset, _)| !offset.is_finite()) {
+            return Err(Error::ColorError(
+                "gradient stop offset is not a number".to_string(),
+            ));
+        }
+
+        let mut stops: Vec<(f32, [f32; 4])> = stops
+            .iter()
+            .map(|(offset, color)| (*offset, color.get_as_linear_f32()))
+            .collect();
+        stops.sort_by(|first, second| first.0.total_cmp(&second.0));
+        Ok(Self { kind, stops })
+    }
+
+    #[must_use]
+    pub fn get_kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Offset of a point, clamped from 0 to 1.
+    #[must_use]
+    pub fn get_offset(&self, point: Vector3<f32>) -> f32 {
+        let offset = match self.kind {
+            Kind::Linear { start, end } => {
+                let direction = end - start;
+                let length = direction.magnitude2();
+                if length > 0.0 {
+                    (point - start).dot(direction) / length
+                } else {
+                    0.0
+                }
+            }
+            Kind::Radial { center, radius } => point.distance(center) / radius,
+        };
+        offset.clamp(0.0, 1.0)
+    }
+
+    /// Color at the offset, sRGB encoded like the stored colors.
+    #[must_use]
+    pub fn sample(&self, offset: f32) -> RGBA {
+        let [red, green, blue, alpha] = self.sample_encoded(offset);
+        RGBA::from_normalized(red, green, blue, alpha)
+    }
+
+    /// RGBA of every vertex, 3 floats per vertex in and 4 out.
+    #[must_use]
+    pub fn for_vertices(&self, vertices: &[f32]) -> Vec<f32> {
+        vertices
+            .chunks_exact(3)
+            .flat_map(|vertex| {
+                self.sample_encoded(self.get_offset(Vector3::new(vertex[0], vertex[1], vertex[2])))
+            })
+            .collect()
+    }
+
+    /// Per-vertex color component for the shape.
+    #[must_use]
+    pub fn for_shape(&self, shape: &dyn Shape) -> Color {
+        Color::from_vertices(self.for_vertices(shape.get_vertices()))
+    }
+
+    fn sample_encoded(&self, offset: f32) -> [f32; 4] {
+        let next = self
+            .stops
+            .iter()
+            .position(|(stop, _)| *stop > offset)
+            .unwrap_or(self.stops.len());
+        let [red, green, blue, alpha] = match (next.checked_sub(1), self.stops.get(next)) {
+            (Some(previous), Some((end, end_color))) => {
+                let (start, start_color) = self.stops[previous];
+                let weight = (offset - start) / (end - start);
+                std::array::from_fn(|channel| {
+                    start_color[channel] + (end_color[channel] - start_color[channel]) * weight
+                })
+            }
+            (Some(previous), None) => self.stops[previous].1,
+            (None, _) => self.stops[0].1,
+        };
+        [
+            linear_to_srgb(red),
+            linear_to_srgb(green),
+            linear_to_srgb(blue),
+            alpha,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::geometry::plane::Triangle;
+
+    fn black_to_white() -> [(f32, RGBA); 2] {
+        [(0.0, RGBA::new(0, 0, 0, 1.0)), (1.0, RGBA::new_white())]
+    }
+
+    #[test]
+    fn test_gradient_sample_mixes_in_linear_light() {
+        let gradient = Gradient::linear(
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            &black_to_white(),
+        )
+        .unwrap();
+
+        assert_eq!(gradient.sample(-1.0), RGBA::new(0, 0, 0, 1.0));
+        assert_eq!(gradient.sample(2.0), RGBA::new_white());
+        //half of the light is brighter than the half of the encoded value
+        assert_eq!(gradient.sample(0.5), RGBA::new(188, 188, 188, 1.0));
+    }
+
+    #[test]
+    fn test_gradient_linear_for_shape() {
+        let stops = [
+            (1.0, RGBA::new(0, 0, 255, 1.0)),
+            (0.0, RGBA::new(255, 0, 0, 0.0)),
+        ];
+        let gradient = Gradient::linear(
+            Vector3::new(-0.5, 0.0, 0.0),
+            Vector3::new(0.5, 0.0, 0.0),
+            &stops,
+        )
+        .unwrap();
+        let triangle = Triangle::new([-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0]);
+
+        let color = gradient.for_shape(&triangle);
+        let colors = color.as_ref_vertices().unwrap();
+        assert_eq!(colors.len(), 12);
+        let expected = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0];
+        assert!(colors[..8]
+            .iter()
+            .zip(expected)
+            .all(|(color, expected)| (color - expected).abs() < 1e-5));
+        assert!((colors[11] - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_gradient_radial() {
+        let gradient =
+            Gradient::radial(Vector3::new(1.0, 1.0, 0.0), 2.0, &black_to_white()).unwrap();
+
+        assert!(gradient.get_offset(Vector3::new(1.0, 1.0, 0.0)).abs() < 1e-6);
+        assert!((gradient.get_offset(Vector3::new(2.0, 1.0, 0.0)) - 0.5).abs() < 1e-6);
+        assert!((gradient.get_offset(Vector3::new(9.0, 1.0, 0.0)) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_gradient_invalid() {
+        let origin = Vector3::new(0.0, 0.0, 0.0);
+
+        assert!(Gradient::linear(origin, origin, &[]).is_err());
+        assert!(Gradient::radial(origin, 0.0, &black_to_white()).is_err());
+        assert!(Gradient::linear(origin, origin, &[(f32::NAN, RGBA::new_white())]).is_err());
+        //a single stop colors everything
+        let single = Gradient::linear(origin, origin, &[(0.3, RGBA::new_white())]).unwrap();
+        assert_eq!(single.sample(0.0), RGBA::new_white());
+    }
+}
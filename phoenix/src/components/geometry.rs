@@ -1,5 +1,11 @@
+pub mod bounds;
+pub mod mesh;
+pub mod path;
 pub mod plane;
+pub mod polyline;
 pub mod solid;
+pub mod terrain;
+pub mod triangulation;
 
 pub struct Radius {
     pub width: f32,
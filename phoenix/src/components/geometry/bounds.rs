@@ -0,0 +1,416 @@
+use cgmath::{InnerSpace, Matrix4, Transform, Vector3};
+
+/// Axis-aligned box, the sides are parallel to the axes of its space.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Aabb {
+    pub min: Vector3<f32>,
+    pub max: Vector3<f32>,
+}
+
+/// Box rotated by `axes`, each of them is a unit vector paired with a half of the box size along it.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Obb {
+    pub center: Vector3<f32>,
+    pub axes: [Vector3<f32>; 3],
+    pub half_extents: Vector3<f32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BoundingSphere {
+    pub center: Vector3<f32>,
+    pub radius: f32,
+}
+
+/// All bounding volumes of a shape, expressed in the same space.
+/// The oriented box is the tightest one after rotation, the axis-aligned box
+/// and the sphere are the cheapest to test against.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Bounds {
+    pub aabb: Aabb,
+    pub oriented: Obb,
+    pub sphere: BoundingSphere,
+}
+
+impl Aabb {
+    /// Box enclosing all positions of the flat `[x, y, z, x, y, z, ...]` list,
+    /// `None` when there is no complete position.
+    #[must_use]
+    pub fn from_vertices(vertices: &[f32]) -> Option<Self> {
+        let mut points = vertices
+            .chunks_exact(3)
+            .map(|point| Vector3::new(point[0], point[1], point[2]));
+        let first = points.next()?;
+        Some(points.fold(
+            Self {
+                min: first,
+                max: first,
+            },
+            |aabb, point| Self {
+                min: Vector3::new(
+                    aabb.min.x.min(point.x),
+                    aabb.min.y.min(point.y),
+                    aabb.min.z.min(point.z),
+                ),
+                max: Vector3::new(
+                    aabb.max.x.max(point.x),
+                    aabb.max.y.max(point.y),
+                    aabb.max.z.max(point.z),
+                ),
+            },
+        ))
+    }
+
+    #[must_use]
+    pub fn center(&self) -> Vector3<f32> {
+        (self.min + self.max) / 2.0
+    }
+
+    #[must_use]
+    pub fn half_extents(&self) -> Vector3<f32> {
+        (self.max - self.min) / 2.0
+    }
+
+    #[must_use]
+    pub fn corners(&self) -> [Vector3<f32>; 8] {
+        let (min, max) = (self.min, self.max);
+        [
+            Vector3::new(min.x, min.y, min.z),
+            Vector3::new(max.x, min.y, min.z),
+            Vector3::new(min.x, max.y, min.z),
+            Vector3::new(max.x, max.y, min.z),
+            Vector3::new(min.x, min.y, max.z),
+            Vector3::new(max.x, min.y, max.z),
+            Vector3::new(min.x, max.y, max.z),
+            Vector3::new(max.x, max.y, max.z),
+        ]
+    }
+
+    #[must_use]
+    pub fn contains(&self, point: Vector3<f32>) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x)
+            && (self.min.y..=self.max.y).contains(&point.y)
+            && (self.min.z..=self.max.z).contains(&point.z)
+    }
+
+    #[must_use]
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+            && self.min.z <= other.max.z
+            && other.min.z <= self.max.z
+    }
+
+    /// Smallest axis-aligned box enclosing this one after the transformation.
+    #[must_use]
+    pub fn transform(&self, matrix: &Matrix4<f32>) -> Self {
+        self.to_oriented().transform(matrix).to_aabb()
+    }
+
+    /// Smallest box enclosing both boxes.
+    #[must_use]
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: Vector3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            max: Vector3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        }
+    }
+
+    #[must_use]
+    pub fn to_oriented(&self) -> Obb {
+        Obb {
+            center: self.center(),
+            axes: [Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()],
+            half_extents: self.half_extents(),
+        }
+    }
+}
+
+impl Obb {
+    /// Applies an affine transformation, a scale along the axes changes the half extents.
+    #[must_use]
+    pub fn transform(&self, matrix: &Matrix4<f32>) -> Self {
+        let mut axes = self.axes;
+        let mut half_extents = self.half_extents;
+        for (index, axis) in axes.iter_mut().enumerate() {
+            let transformed = matrix.transform_vector(*axis);
+            let length = transformed.magnitude();
+            //a degenerated axis keeps its direction, its extent is zero anyway
+            if length > f32::EPSILON {
+                *axis = transformed / length;
+            }
+            half_extents[index] *= length;
+        }
+
+        Self {
+            center: transform_point(matrix, self.center),
+            axes,
+            half_extents,
+        }
+    }
+
+    #[must_use]
+    pub fn corners(&self) -> [Vector3<f32>; 8] {
+        let [x, y, z] = [0, 1, 2].map(|index| self.axes[index] * self.half_extents[index]);
+        [
+            self.center - x - y - z,
+            self.center + x - y - z,
+            self.center - x + y - z,
+            self.center + x + y - z,
+            self.center - x - y + z,
+            self.center + x - y + z,
+            self.center - x + y + z,
+            self.center + x + y + z,
+        ]
+    }
+
+    #[must_use]
+    pub fn to_aabb(&self) -> Aabb {
+        let extent = |component: fn(&Vector3<f32>) -> f32| {
+            (0..3)
+                .map(|index| component(&self.axes[index]).abs() * self.half_extents[index])
+                .sum::<f32>()
+        };
+        let half_extents = Vector3::new(extent(|v| v.x), extent(|v| v.y), extent(|v| v.z));
+        Aabb {
+            min: self.center - half_extents,
+            max: self.center + half_extents,
+        }
+    }
+}
+
+impl BoundingSphere {
+    /// Sphere centered in the middle of the positions, `None` when there is no complete position.
+    #[must_use]
+    pub fn from_vertices(vertices: &[f32]) -> Option<Self> {
+        let center = Aabb::from_vertices(vertices)?.center();
+        let radius = vertices
+            .chunks_exact(3)
+            .map(|point| (Vector3::new(point[0], point[1], point[2]) - center).magnitude())
+            .fold(0.0, f32::max);
+        Some(Self { center, radius })
+    }
+
+    #[must_use]
+    pub fn contains(&self, point: Vector3<f32>) -> bool {
+        (point - self.center).magnitude() <= self.radius
+    }
+
+    #[must_use]
+    pub fn intersects(&self, other: &BoundingSphere) -> bool {
+        let distance = self.radius + other.radius;
+        (other.center - self.center).magnitude2() <= distance * distance
+    }
+
+    /// Smallest sphere enclosing both spheres.
+    #[must_use]
+    pub fn union(&self, other: &BoundingSphere) -> Self {
+        let offset = other.center - self.center;
+        let distance = offset.magnitude();
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+
+        let radius = (distance + self.radius + other.radius) / 2.0;
+        Self {
+            center: self.center + offset * ((radius - self.radius) / distance),
+            radius,
+        }
+    }
+
+    /// Sphere enclosing this one after the transformation, a non-uniform scale
+    /// grows the radius by the biggest scale factor.
+    #[must_use]
+    pub fn transform(&self, matrix: &Matrix4<f32>) -> Self {
+        let scale = [matrix.x, matrix.y, matrix.z]
+            .iter()
+            .map(|column| column.truncate().magnitude())
+            .fold(0.0, f32::max);
+        Self {
+            center: transform_point(matrix, self.center),
+            radius: self.radius * scale,
+        }
+    }
+}
+
+impl Bounds {
+    #[must_use]
+    pub fn from_vertices(vertices: &[f32]) -> Option<Self> {
+        let aabb = Aabb::from_vertices(vertices)?;
+        Some(Self {
+            aabb,
+            oriented: aabb.to_oriented(),
+            sphere: BoundingSphere::from_vertices(vertices)?,
+        })
+    }
+
+    #[must_use]
+    pub fn transform(&self, matrix: &Matrix4<f32>) -> Self {
+        let oriented = self.oriented.transform(matrix);
+        Self {
+            aabb: oriented.to_aabb(),
+            oriented,
+            sphere: self.sphere.transform(matrix),
+        }
+    }
+
+    /// Bounds enclosing both, the oriented box loses its rotation and follows the axis-aligned one.
+    #[must_use]
+    pub fn union(&self, other: &Bounds) -> Self {
+        let aabb = self.aabb.union(&other.aabb);
+        Self {
+            aabb,
+            oriented: aabb.to_oriented(),
+            sphere: self.sphere.union(&other.sphere),
+        }
+    }
+}
+
+fn transform_point(matrix: &Matrix4<f32>, point: Vector3<f32>) -> Vector3<f32> {
+    (matrix * point.extend(1.0)).truncate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{assert_relative_eq, Deg, Matrix4};
+
+    const VERTICES: [f32; 12] = [
+        -1.0, 0.0, 0.0, 3.0, 2.0, 0.0, 1.0, -2.0, 1.0, 0.0, 0.0, -1.0,
+    ];
+
+    #[test]
+    fn test_local_bounds() {
+        let bounds = Bounds::from_vertices(&VERTICES).unwrap();
+
+        assert_eq!(bounds.aabb.min, Vector3::new(-1.0, -2.0, -1.0));
+        assert_eq!(bounds.aabb.max, Vector3::new(3.0, 2.0, 1.0));
+        assert_eq!(bounds.oriented.center, Vector3::new(1.0, 0.0, 0.0));
+        assert_eq!(bounds.oriented.half_extents, Vector3::new(2.0, 2.0, 1.0));
+        assert_eq!(bounds.sphere.center, Vector3::new(1.0, 0.0, 0.0));
+        assert_relative_eq!(bounds.sphere.radius, 8.0_f32.sqrt());
+        for point in VERTICES.chunks(3) {
+            let point = Vector3::new(point[0], point[1], point[2]);
+            assert!(bounds.aabb.contains(point));
+            assert!(bounds.sphere.contains(point));
+        }
+
+        assert!(Bounds::from_vertices(&[]).is_none());
+        assert!(Aabb::from_vertices(&[1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn test_transformed_bounds() {
+        let bounds = Bounds::from_vertices(&VERTICES).unwrap();
+        let matrix = Matrix4::from_translation(Vector3::new(10.0, 0.0, 0.0))
+            * Matrix4::from_angle_z(Deg(90.0))
+            * Matrix4::from_nonuniform_scale(2.0, 1.0, 3.0);
+        let world = bounds.transform(&matrix);
+
+        //x is scaled by 2 and turned into y, y turned into -x, z is scaled by 3
+        assert_relative_eq!(
+            world.aabb.min,
+            Vector3::new(8.0, -2.0, -3.0),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(world.aabb.max, Vector3::new(12.0, 6.0, 3.0), epsilon = 1e-5);
+        assert_relative_eq!(
+            world.oriented.center,
+            Vector3::new(10.0, 2.0, 0.0),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(world.oriented.axes[0], Vector3::unit_y(), epsilon = 1e-5);
+        assert_relative_eq!(
+            world.oriented.half_extents,
+            Vector3::new(4.0, 2.0, 3.0),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            world.sphere.center,
+            Vector3::new(10.0, 2.0, 0.0),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(world.sphere.radius, 3.0 * 8.0_f32.sqrt(), epsilon = 1e-5);
+
+        //every transformed vertex stays inside all world volumes, up to rounding errors
+        let margin = Vector3::new(1e-4, 1e-4, 1e-4);
+        let aabb = Aabb {
+            min: world.aabb.min - margin,
+            max: world.aabb.max + margin,
+        };
+        let sphere = BoundingSphere {
+            center: world.sphere.center,
+            radius: world.sphere.radius + 1e-4,
+        };
+        for point in VERTICES.chunks(3) {
+            let point = transform_point(&matrix, Vector3::new(point[0], point[1], point[2]));
+            assert!(aabb.contains(point));
+            assert!(sphere.contains(point));
+        }
+        for corner in world.oriented.corners() {
+            assert!(aabb.contains(corner));
+        }
+    }
+
+    #[test]
+    fn test_intersections() {
+        let first = Aabb {
+            min: Vector3::new(0.0, 0.0, 0.0),
+            max: Vector3::new(1.0, 1.0, 1.0),
+        };
+        let second = Aabb {
+            min: Vector3::new(0.5, 0.5, 0.5),
+            max: Vector3::new(2.0, 2.0, 2.0),
+        };
+        let far = Aabb {
+            min: Vector3::new(1.5, 0.0, 0.0),
+            max: Vector3::new(2.0, 1.0, 1.0),
+        };
+        assert!(first.intersects(&second));
+        assert!(!first.intersects(&far));
+
+        let sphere = BoundingSphere {
+            center: Vector3::new(0.0, 0.0, 0.0),
+            radius: 1.0,
+        };
+        let touching = BoundingSphere {
+            center: Vector3::new(2.0, 0.0, 0.0),
+            radius: 1.0,
+        };
+        assert!(sphere.intersects(&touching));
+        assert!(!sphere.intersects(&BoundingSphere {
+            center: Vector3::new(2.5, 0.0, 0.0),
+            radius: 1.0
+        }));
+    }
+
+    #[test]
+    fn test_union() {
+        let first = Bounds::from_vertices(&[0.0, 0.0, 0.0, 2.0, 0.0, 0.0]).unwrap();
+        let second = Bounds::from_vertices(&[4.0, -1.0, 0.0, 6.0, 1.0, 0.0]).unwrap();
+        let union = first.union(&second);
+
+        assert_eq!(union.aabb.min, Vector3::new(0.0, -1.0, 0.0));
+        assert_eq!(union.aabb.max, Vector3::new(6.0, 1.0, 0.0));
+        assert_eq!(union.oriented.center, Vector3::new(3.0, 0.0, 0.0));
+        //the spheres are centered at 1 and 5, the second one has a radius of the square root of 2
+        let radius = (4.0 + 1.0 + 2.0_f32.sqrt()) / 2.0;
+        assert_relative_eq!(union.sphere.radius, radius);
+        assert_relative_eq!(union.sphere.center, Vector3::new(radius, 0.0, 0.0));
+
+        //a sphere inside the other one is swallowed
+        assert_eq!(union.sphere.union(&first.sphere), union.sphere);
+    }
+}
@@ -0,0 +1,262 @@
+use crate::components::{Error, FillMode, NormalMode, Result, Shape, ShapeType};
+
+/// User-defined geometry. Every per-vertex attribute is optional,
+/// missing normals are calculated by the renderer.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mesh {
+    positions: Vec<f32>,
+    normals: Option<Vec<f32>>,
+    texture_coords: Option<Vec<f32>>,
+    colors: Option<Vec<f32>>,
+    indices: Option<Vec<u32>>,
+    mode: FillMode,
+    normal_mode: NormalMode,
+}
+
+pub struct Builder {
+    positions: Vec<f32>,
+    normals: Option<Vec<f32>>,
+    texture_coords: Option<Vec<f32>>,
+    colors: Option<Vec<f32>>,
+    indices: Option<Vec<u32>>,
+    mode: FillMode,
+    normal_mode: NormalMode,
+}
+
+impl Builder {
+    #[must_use]
+    pub fn new(positions: Vec<f32>) -> Self {
+        Self {
+            positions,
+            normals: None,
+            texture_coords: None,
+            colors: None,
+            indices: None,
+            mode: FillMode::Solid,
+            normal_mode: NormalMode::Flat,
+        }
+    }
+
+    #[must_use]
+    pub fn with_normals(mut self, normals: Vec<f32>) -> Self {
+        self.normals = Some(normals);
+        self
+    }
+
+    #[must_use]
+    pub fn with_texture_coords(mut self, texture_coords: Vec<f32>) -> Self {
+        self.texture_coords = Some(texture_coords);
+        self
+    }
+
+    #[must_use]
+    pub fn with_colors(mut self, colors: Vec<f32>) -> Self {
+        self.colors = Some(colors);
+        self
+    }
+
+    #[must_use]
+    pub fn with_indices(mut self, indices: Vec<u32>) -> Self {
+        self.indices = Some(indices);
+        self
+    }
+
+    #[must_use]
+    pub fn with_fill_mode(mut self, mode: FillMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Used only when the mesh has no normals of its own.
+    #[must_use]
+    pub fn with_normal_mode(mut self, normal_mode: NormalMode) -> Self {
+        self.normal_mode = normal_mode;
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` when the length of any attribute does not match the number of positions
+    /// or an index points outside the positions.
+    pub fn build(self) -> Result<Mesh> {
+        let vertices_count = self.positions.len() / 3;
+        if vertices_count == 0 || vertices_count * 3 != self.positions.len() {
+            return Err(Error::GeometryError(format!(
+                "Mesh positions length {} is not a non-zero multiple of 3",
+                self.positions.len()
+            )));
+        }
+
+        Builder::check_attribute_len("normals", self.normals.as_deref(), vertices_count * 3)?;
+        Builder::check_attribute_len(
+            "texture coordinates",
+            self.texture_coords.as_deref(),
+            vertices_count * 2,
+        )?;
+        Builder::check_attribute_len("colors", self.colors.as_deref(), vertices_count * 4)?;
+
+        if let Some(indices) = &self.indices {
+            if let Some(index) = indices
+                .iter()
+                .find(|index| **index as usize >= vertices_count)
+            {
+                return Err(Error::GeometryError(format!(
+                    "Mesh index {index} out of range, vertices count {vertices_count}"
+                )));
+            }
+        }
+
+        Ok(Mesh {
+            positions: self.positions,
+            normals: self.normals,
+            texture_coords: self.texture_coords,
+            colors: self.colors,
+            indices: self.indices,
+            mode: self.mode,
+            normal_mode: self.normal_mode,
+        })
+    }
+
+    fn check_attribute_len(name: &str, attribute: Option<&[f32]>, expected: usize) -> Result<()> {
+        match attribute {
+            Some(values) if values.len() != expected => Err(Error::GeometryError(format!(
+                "Mesh {name} length {} does not match expected length {expected}",
+                values.len()
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Mesh {
+    pub fn set_fill_mode(&mut self, mode: FillMode) {
+        self.mode = mode;
+    }
+
+    pub fn set_normal_mode(&mut self, normal_mode: NormalMode) {
+        self.normal_mode = normal_mode;
+    }
+}
+
+impl Shape for Mesh {
+    fn get_vertices(&self) -> &[f32] {
+        &self.positions
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        self.indices.as_deref()
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        self.normals.as_deref()
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        self.texture_coords.as_deref()
+    }
+
+    fn get_colors(&self) -> Option<&[f32]> {
+        self.colors.as_deref()
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Mesh
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        self.mode
+    }
+
+    fn get_normal_mode(&self) -> NormalMode {
+        self.normal_mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUAD: [f32; 12] = [
+        -0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.5, 0.5, 0.0, -0.5, 0.5, 0.0,
+    ];
+
+    #[test]
+    fn test_mesh_only_positions() {
+        let mesh = Builder::new(QUAD.to_vec()).build().unwrap();
+
+        assert_eq!(mesh.get_vertices(), &QUAD);
+        assert_eq!(mesh.get_type(), ShapeType::Mesh);
+        assert_eq!(mesh.get_fill_mode(), FillMode::Solid);
+        assert!(mesh.get_indices().is_none());
+        assert!(mesh.get_normals().is_none());
+        assert!(mesh.get_texture_coords().is_none());
+        assert!(mesh.get_colors().is_none());
+        assert_eq!(mesh.get_normal_mode(), NormalMode::Flat);
+    }
+
+    #[test]
+    fn test_mesh_all_attributes() {
+        let normals = [0.0, 0.0, 1.0].repeat(4);
+        let texture_coords = vec![0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+        let colors = [1.0, 0.0, 0.0, 1.0].repeat(4);
+        let indices = vec![0, 1, 2, 2, 3, 0];
+
+        let mut mesh = Builder::new(QUAD.to_vec())
+            .with_normals(normals.clone())
+            .with_texture_coords(texture_coords.clone())
+            .with_colors(colors.clone())
+            .with_indices(indices.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(mesh.get_normals().unwrap(), normals.as_slice());
+        assert_eq!(
+            mesh.get_texture_coords().unwrap(),
+            texture_coords.as_slice()
+        );
+        assert_eq!(mesh.get_colors().unwrap(), colors.as_slice());
+        assert_eq!(mesh.get_indices().unwrap(), indices.as_slice());
+
+        mesh.set_fill_mode(FillMode::Lines);
+        assert_eq!(mesh.get_fill_mode(), FillMode::Lines);
+
+        mesh.set_normal_mode(NormalMode::Winding);
+        assert_eq!(mesh.get_normal_mode(), NormalMode::Winding);
+    }
+
+    #[test]
+    fn test_mesh_invalid_positions_get_error() {
+        assert!(Builder::new(vec![]).build().is_err());
+        assert!(Builder::new(vec![0.0, 1.0]).build().is_err());
+    }
+
+    #[test]
+    fn test_mesh_attribute_len_mismatch_get_error() {
+        let result = Builder::new(QUAD.to_vec())
+            .with_normals(vec![0.0, 0.0, 1.0])
+            .build();
+        assert!(result.is_err());
+
+        let result = Builder::new(QUAD.to_vec())
+            .with_texture_coords(vec![0.0, 0.0])
+            .build();
+        assert!(result.is_err());
+
+        let result = Builder::new(QUAD.to_vec())
+            .with_colors(vec![1.0, 0.0, 0.0])
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_mesh_index_out_of_range_get_error() {
+        let result = Builder::new(QUAD.to_vec())
+            .with_indices(vec![0, 1, 4])
+            .build();
+
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("Mesh index 4 out of range"));
+    }
+}
@@ -0,0 +1,844 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use super::plane::Polygon;
+use super::polyline::{self, Polyline};
+use super::{triangulation, Point, Radius};
+use crate::components::{Error, Result};
+
+type Point2 = [f32; 2];
+
+const MIN_TOLERANCE: f32 = 1e-4;
+const MAX_CURVE_SEGMENTS: f32 = 1024.0;
+
+/// 2D vector path made of lines, Bezier curves and elliptical arcs, like the SVG paths.
+/// It is flattened into contours which can be filled as a `Polygon` or stroked as a `Polyline`.
+#[derive(Clone, Debug, Default)]
+pub struct Path {
+    subpaths: Vec<Subpath>,
+    current: Point2,
+}
+
+/// Flattened subpath.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    pub points: Vec<Point2>,
+    pub closed: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Subpath {
+    start: Point2,
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Segment {
+    Line {
+        end: Point2,
+    },
+    Quadratic {
+        control: Point2,
+        end: Point2,
+    },
+    Cubic {
+        first_control: Point2,
+        second_control: Point2,
+        end: Point2,
+    },
+    Arc {
+        radius: Point2,
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        end: Point2,
+    },
+}
+
+struct SvgParser<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl Path {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new subpath.
+    #[must_use]
+    pub fn move_to(mut self, x: f32, y: f32) -> Self {
+        self.current = [x, y];
+        self.subpaths.push(Subpath {
+            start: self.current,
+            segments: Vec::new(),
+            closed: false,
+        });
+        self
+    }
+
+    #[must_use]
+    pub fn line_to(self, x: f32, y: f32) -> Self {
+        self.push_segment(Segment::Line { end: [x, y] })
+    }
+
+    #[must_use]
+    pub fn quadratic_to(self, control_x: f32, control_y: f32, x: f32, y: f32) -> Self {
+        self.push_segment(Segment::Quadratic {
+            control: [control_x, control_y],
+            end: [x, y],
+        })
+    }
+
+    #[must_use]
+    pub fn cubic_to(
+        self,
+        first_control_x: f32,
+        first_control_y: f32,
+        second_control_x: f32,
+        second_control_y: f32,
+        x: f32,
+        y: f32,
+    ) -> Self {
+        self.push_segment(Segment::Cubic {
+            first_control: [first_control_x, first_control_y],
+            second_control: [second_control_x, second_control_y],
+            end: [x, y],
+        })
+    }
+
+    /// Elliptical arc to the given point, with the same parameters as the SVG arc command.
+    /// `rotation` of the ellipse x axis is in degrees.
+    #[must_use]
+    pub fn arc_to(
+        self,
+        radius: &Radius,
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        x: f32,
+        y: f32,
+    ) -> Self {
+        self.push_segment(Segment::Arc {
+            radius: [radius.width, radius.height],
+            rotation,
+            large_arc,
+            sweep,
+            end: [x, y],
+        })
+    }
+
+    /// Closes the current subpath with a line to its start point.
+    #[must_use]
+    pub fn close(mut self) -> Self {
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.closed = true;
+            self.current = subpath.start;
+        }
+        self
+    }
+
+    /// Parses SVG path data (the `d` attribute), all commands are supported:
+    /// M, L, H, V, Q, T, C, S, A, Z and their relative forms.
+    /// Coordinates are taken as they are, so the y axis points down like in SVG.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` when the data contains an unknown command or a malformed number.
+    pub fn from_svg(data: &str) -> Result<Self> {
+        SvgParser {
+            data: data.as_bytes(),
+            position: 0,
+        }
+        .parse()
+    }
+
+    /// Replaces curves by lines which are never further than `tolerance` from the curve.
+    /// Subpaths with a single point are skipped.
+    #[must_use]
+    pub fn flatten(&self, tolerance: f32) -> Vec<Contour> {
+        let tolerance = tolerance.max(MIN_TOLERANCE);
+        self.subpaths
+            .iter()
+            .filter_map(|subpath| {
+                let mut points = vec![subpath.start];
+                for segment in &subpath.segments {
+                    let start = points[points.len() - 1];
+                    segment.flatten(start, tolerance, &mut points);
+                }
+
+                points.dedup_by(|next, previous| is_same_point(*next, *previous));
+                if subpath.closed
+                    && points.len() > 1
+                    && is_same_point(points[0], points[points.len() - 1])
+                {
+                    points.pop();
+                }
+                (points.len() > 1).then_some(Contour {
+                    points,
+                    closed: subpath.closed,
+                })
+            })
+            .collect()
+    }
+
+    /// Fills the path with the even-odd rule, every contour is treated as closed.
+    /// Contours may be nested, but they must not intersect each other.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the path has no area or the triangulation of any contour fails.
+    pub fn fill(&self, tolerance: f32) -> Result<Polygon> {
+        let contours: Vec<Vec<Point2>> = self
+            .flatten(tolerance)
+            .into_iter()
+            .map(|contour| contour.points)
+            .filter(|points| points.len() > 2)
+            .collect();
+
+        //a contour inside an odd number of other contours is a hole
+        let depths: Vec<usize> = contours
+            .iter()
+            .enumerate()
+            .map(|(i, contour)| {
+                contours
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, other)| *j != i && is_inside(other, contour[0]))
+                    .count()
+            })
+            .collect();
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for (i, outer) in contours.iter().enumerate() {
+            if !depths[i].is_multiple_of(2) {
+                continue;
+            }
+
+            let holes: Vec<Vec<Point2>> = contours
+                .iter()
+                .enumerate()
+                .filter(|(j, hole)| depths[*j] == depths[i] + 1 && is_inside(outer, hole[0]))
+                .map(|(_, hole)| hole.clone())
+                .collect();
+
+            let offset = u32::try_from(vertices.len() / 3)
+                .map_err(|_| Error::GeometryError("Too many path points".to_string()))?;
+            let triangles = triangulation::triangulate(outer, &holes)?;
+            indices.extend(triangles.into_iter().map(|index| index + offset));
+            vertices.extend(
+                outer
+                    .iter()
+                    .chain(holes.iter().flatten())
+                    .flat_map(|point| [point[0], point[1], 0.0]),
+            );
+        }
+
+        if indices.is_empty() {
+            return Err(Error::GeometryError(
+                "Path has no closed contour with an area".to_string(),
+            ));
+        }
+        Ok(Polygon::new_triangulated(vertices, indices))
+    }
+
+    /// Strokes every contour as a polyline, `style` sets the line options of each of them.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the path is empty or building any of the polylines fails.
+    pub fn stroke(
+        &self,
+        tolerance: f32,
+        style: impl Fn(polyline::Builder) -> polyline::Builder,
+    ) -> Result<Polyline> {
+        let mut result: Option<Polyline> = None;
+        for contour in self.flatten(tolerance) {
+            let points: Vec<Point> = contour
+                .points
+                .iter()
+                .map(|point| Point {
+                    x: point[0],
+                    y: point[1],
+                    z: 0.0,
+                })
+                .collect();
+            let line =
+                style(polyline::Builder::new(&points).with_closed(contour.closed)).build()?;
+            match result.as_mut() {
+                Some(result) => result.append(line),
+                None => result = Some(line),
+            }
+        }
+
+        result.ok_or_else(|| Error::GeometryError("Path has nothing to stroke".to_string()))
+    }
+
+    fn push_segment(mut self, segment: Segment) -> Self {
+        if self.subpaths.last().is_none_or(|subpath| subpath.closed) {
+            //drawing after a close or without a move starts at the current point
+            let [x, y] = self.current;
+            self = self.move_to(x, y);
+        }
+
+        self.current = segment.end();
+        if let Some(subpath) = self.subpaths.last_mut() {
+            subpath.segments.push(segment);
+        }
+        self
+    }
+}
+
+impl Segment {
+    fn end(&self) -> Point2 {
+        match self {
+            Segment::Line { end }
+            | Segment::Quadratic { end, .. }
+            | Segment::Cubic { end, .. }
+            | Segment::Arc { end, .. } => *end,
+        }
+    }
+
+    /// Adds the points after `start`, the last one is always the segment end.
+    fn flatten(&self, start: Point2, tolerance: f32, points: &mut Vec<Point2>) {
+        match *self {
+            Segment::Line { end } => points.push(end),
+            Segment::Quadratic { control, end } => {
+                //the distance from the chord is at most a quarter of the second difference
+                let difference = length(second_difference(start, control, end));
+                let steps = curve_steps(difference / 4.0, tolerance);
+                for step in 1..=steps {
+                    let t = ratio(step, steps);
+                    let s = 1.0 - t;
+                    points.push(combine(&[
+                        (start, s * s),
+                        (control, 2.0 * s * t),
+                        (end, t * t),
+                    ]));
+                }
+            }
+            Segment::Cubic {
+                first_control,
+                second_control,
+                end,
+            } => {
+                let difference =
+                    length(second_difference(start, first_control, second_control)).max(length(
+                        second_difference(first_control, second_control, end),
+                    ));
+                let steps = curve_steps(difference * 3.0 / 4.0, tolerance);
+                for step in 1..=steps {
+                    let t = ratio(step, steps);
+                    let s = 1.0 - t;
+                    points.push(combine(&[
+                        (start, s * s * s),
+                        (first_control, 3.0 * s * s * t),
+                        (second_control, 3.0 * s * t * t),
+                        (end, t * t * t),
+                    ]));
+                }
+            }
+            Segment::Arc {
+                radius,
+                rotation,
+                large_arc,
+                sweep,
+                end,
+            } => flatten_arc(
+                start,
+                radius,
+                rotation,
+                (large_arc, sweep),
+                end,
+                tolerance,
+                points,
+            ),
+        }
+    }
+}
+
+impl SvgParser<'_> {
+    fn parse(&mut self) -> Result<Path> {
+        let mut path = Path::new();
+        let mut command = None;
+        //the last control point, reflected by the smooth curve commands
+        let mut last_cubic: Option<Point2> = None;
+        let mut last_quadratic: Option<Point2> = None;
+
+        loop {
+            self.skip_separators();
+            let Some(&next) = self.data.get(self.position) else {
+                break;
+            };
+            if next.is_ascii_alphabetic() {
+                self.position += 1;
+                command = Some(next);
+            } else if command.is_none() {
+                return Err(self.error("path data has to start with a command"));
+            }
+            let Some(current_command) = command else {
+                break;
+            };
+
+            let relative = current_command.is_ascii_lowercase();
+            let origin = if relative { path.current } else { [0.0, 0.0] };
+            let mut cubic = None;
+            let mut quadratic = None;
+
+            path = match current_command.to_ascii_uppercase() {
+                b'M' => {
+                    let point = self.point(origin)?;
+                    //coordinates after a move are lines
+                    command = Some(if relative { b'l' } else { b'L' });
+                    path.move_to(point[0], point[1])
+                }
+                b'L' => {
+                    let point = self.point(origin)?;
+                    path.line_to(point[0], point[1])
+                }
+                b'H' => {
+                    let x = self.number()? + origin[0];
+                    let y = path.current[1];
+                    path.line_to(x, y)
+                }
+                b'V' => {
+                    let x = path.current[0];
+                    let y = self.number()? + origin[1];
+                    path.line_to(x, y)
+                }
+                b'Q' | b'T' => {
+                    let control = if current_command.eq_ignore_ascii_case(&b'Q') {
+                        self.point(origin)?
+                    } else {
+                        reflect(last_quadratic, path.current)
+                    };
+                    let end = self.point(origin)?;
+                    quadratic = Some(control);
+                    path.quadratic_to(control[0], control[1], end[0], end[1])
+                }
+                b'C' | b'S' => {
+                    let first_control = if current_command.eq_ignore_ascii_case(&b'C') {
+                        self.point(origin)?
+                    } else {
+                        reflect(last_cubic, path.current)
+                    };
+                    let second_control = self.point(origin)?;
+                    let end = self.point(origin)?;
+                    cubic = Some(second_control);
+                    path.cubic_to(
+                        first_control[0],
+                        first_control[1],
+                        second_control[0],
+                        second_control[1],
+                        end[0],
+                        end[1],
+                    )
+                }
+                b'A' => {
+                    let radius = Radius {
+                        width: self.number()?,
+                        height: self.number()?,
+                    };
+                    let rotation = self.number()?;
+                    let large_arc = self.flag()?;
+                    let sweep = self.flag()?;
+                    let end = self.point(origin)?;
+                    path.arc_to(&radius, rotation, large_arc, sweep, end[0], end[1])
+                }
+                b'Z' => {
+                    command = None;
+                    path.close()
+                }
+                _ => {
+                    self.position -= 1;
+                    return Err(self.error("unknown command"));
+                }
+            };
+
+            last_cubic = cubic;
+            last_quadratic = quadratic;
+        }
+
+        Ok(path)
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::GeometryError(format!(
+            "Invalid SVG path data at position {}: {message}",
+            self.position
+        ))
+    }
+
+    fn skip_separators(&mut self) {
+        while self
+            .data
+            .get(self.position)
+            .is_some_and(|byte| byte.is_ascii_whitespace() || *byte == b',')
+        {
+            self.position += 1;
+        }
+    }
+
+    fn point(&mut self, origin: Point2) -> Result<Point2> {
+        Ok([self.number()? + origin[0], self.number()? + origin[1]])
+    }
+
+    fn number(&mut self) -> Result<f32> {
+        self.skip_separators();
+        let start = self.position;
+        let is_digit = |parser: &Self| {
+            parser
+                .data
+                .get(parser.position)
+                .is_some_and(u8::is_ascii_digit)
+        };
+        let skip = |parser: &mut Self, bytes: &[u8]| {
+            if parser
+                .data
+                .get(parser.position)
+                .is_some_and(|byte| bytes.contains(byte))
+            {
+                parser.position += 1;
+            }
+        };
+
+        skip(self, b"+-");
+        while is_digit(self) {
+            self.position += 1;
+        }
+        skip(self, b".");
+        while is_digit(self) {
+            self.position += 1;
+        }
+        if self
+            .data
+            .get(self.position)
+            .is_some_and(|byte| byte.eq_ignore_ascii_case(&b'e'))
+        {
+            self.position += 1;
+            skip(self, b"+-");
+            while is_digit(self) {
+                self.position += 1;
+            }
+        }
+
+        std::str::from_utf8(&self.data[start..self.position])
+            .ok()
+            .and_then(|text| text.parse().ok())
+            .ok_or_else(|| {
+                self.position = start;
+                self.error("expected a number")
+            })
+    }
+
+    /// Arc flags are single digits, which may be written without separators.
+    fn flag(&mut self) -> Result<bool> {
+        self.skip_separators();
+        let flag = match self.data.get(self.position) {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(self.error("expected an arc flag")),
+        };
+        self.position += 1;
+        Ok(flag)
+    }
+}
+
+/// Endpoint to center conversion from the SVG implementation notes.
+fn flatten_arc(
+    start: Point2,
+    radius: Point2,
+    rotation: f32,
+    (large_arc, sweep): (bool, bool),
+    end: Point2,
+    tolerance: f32,
+    points: &mut Vec<Point2>,
+) {
+    let [mut radius_x, mut radius_y] = radius.map(f32::abs);
+    if is_same_point(start, end) || radius_x < f32::EPSILON || radius_y < f32::EPSILON {
+        points.push(end);
+        return;
+    }
+
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let half_x = (start[0] - end[0]) / 2.0;
+    let half_y = (start[1] - end[1]) / 2.0;
+    let x = cos * half_x + sin * half_y;
+    let y = -sin * half_x + cos * half_y;
+
+    //too small radii are scaled up until the arc reaches the end point
+    let scale = (x * x) / (radius_x * radius_x) + (y * y) / (radius_y * radius_y);
+    if scale > 1.0 {
+        radius_x *= scale.sqrt();
+        radius_y *= scale.sqrt();
+    }
+
+    let (squared_x, squared_y) = (radius_x * radius_x, radius_y * radius_y);
+    let numerator = squared_x * squared_y - squared_x * y * y - squared_y * x * x;
+    let denominator = squared_x * y * y + squared_y * x * x;
+    let mut coefficient = (numerator.max(0.0) / denominator).sqrt();
+    if large_arc == sweep {
+        coefficient = -coefficient;
+    }
+    let center_x = coefficient * radius_x * y / radius_y;
+    let center_y = -coefficient * radius_y * x / radius_x;
+    let center = [
+        cos * center_x - sin * center_y + f32::midpoint(start[0], end[0]),
+        sin * center_x + cos * center_y + f32::midpoint(start[1], end[1]),
+    ];
+
+    let start_angle = f32::atan2((y - center_y) / radius_y, (x - center_x) / radius_x);
+    let end_angle = f32::atan2((-y - center_y) / radius_y, (-x - center_x) / radius_x);
+    let mut sweep_angle = end_angle - start_angle;
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += TAU;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= TAU;
+    }
+
+    //the sagitta of every part stays within the tolerance
+    let max_radius = radius_x.max(radius_y);
+    let step = if tolerance < max_radius {
+        2.0 * (1.0 - tolerance / max_radius).acos()
+    } else {
+        FRAC_PI_2
+    };
+    let steps = steps_count(sweep_angle.abs() / step);
+
+    for part in 1..steps {
+        let (angle_sin, angle_cos) = (start_angle + sweep_angle * ratio(part, steps)).sin_cos();
+        points.push([
+            center[0] + radius_x * angle_cos * cos - radius_y * angle_sin * sin,
+            center[1] + radius_x * angle_cos * sin + radius_y * angle_sin * cos,
+        ]);
+    }
+    points.push(end);
+}
+
+fn curve_steps(max_distance: f32, tolerance: f32) -> u16 {
+    //the error of uniform steps falls with the square of their count
+    steps_count((max_distance / tolerance).sqrt())
+}
+
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn steps_count(steps: f32) -> u16 {
+    steps.ceil().clamp(1.0, MAX_CURVE_SEGMENTS) as u16
+}
+
+fn ratio(step: u16, steps: u16) -> f32 {
+    f32::from(step) / f32::from(steps)
+}
+
+fn second_difference(first: Point2, second: Point2, third: Point2) -> Point2 {
+    [
+        first[0] - 2.0 * second[0] + third[0],
+        first[1] - 2.0 * second[1] + third[1],
+    ]
+}
+
+fn is_same_point(first: Point2, second: Point2) -> bool {
+    (first[0] - second[0]).abs() < f32::EPSILON && (first[1] - second[1]).abs() < f32::EPSILON
+}
+
+fn length(vector: Point2) -> f32 {
+    vector[0].hypot(vector[1])
+}
+
+fn combine(weighted: &[(Point2, f32)]) -> Point2 {
+    weighted.iter().fold([0.0, 0.0], |sum, (point, weight)| {
+        [sum[0] + point[0] * weight, sum[1] + point[1] * weight]
+    })
+}
+
+fn reflect(control: Option<Point2>, current: Point2) -> Point2 {
+    control.map_or(current, |control| {
+        [2.0 * current[0] - control[0], 2.0 * current[1] - control[1]]
+    })
+}
+
+fn is_inside(contour: &[Point2], point: Point2) -> bool {
+    let mut inside = false;
+    let mut previous = contour[contour.len() - 1];
+    for current in contour {
+        if (current[1] > point[1]) != (previous[1] > point[1])
+            && point[0]
+                < (previous[0] - current[0]) * (point[1] - current[1]) / (previous[1] - current[1])
+                    + current[0]
+        {
+            inside = !inside;
+        }
+        previous = *current;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::{Shape, ShapeType};
+
+    fn square(path: Path, min: f32, max: f32) -> Path {
+        path.move_to(min, min)
+            .line_to(max, min)
+            .line_to(max, max)
+            .line_to(min, max)
+            .close()
+    }
+
+    fn distance(first: Point2, second: Point2) -> f32 {
+        length([first[0] - second[0], first[1] - second[1]])
+    }
+
+    #[test]
+    fn test_path_flatten_lines() {
+        let contours = square(Path::new(), 0.0, 1.0)
+            .move_to(5.0, 5.0)
+            .line_to(6.0, 5.0)
+            .flatten(0.1);
+
+        assert_eq!(contours.len(), 2);
+        assert_eq!(
+            contours[0].points,
+            vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]
+        );
+        assert!(contours[0].closed);
+        assert_eq!(contours[1].points, vec![[5.0, 5.0], [6.0, 5.0]]);
+        assert!(!contours[1].closed);
+    }
+
+    /// Flattened points are taken at uniform steps, so the chord middles can be compared
+    /// with the curve points in the middle of each step.
+    fn check_tolerance(path: &Path, curve: impl Fn(f32) -> Point2, tolerance: f32) {
+        let points = path.flatten(tolerance).remove(0).points;
+        assert!(points.len() > 2);
+        let steps = u16::try_from(points.len() - 1).unwrap();
+        for (step, chord) in (0..steps).zip(points.windows(2)) {
+            let middle = [
+                f32::midpoint(chord[0][0], chord[1][0]),
+                f32::midpoint(chord[0][1], chord[1][1]),
+            ];
+            let on_curve = curve((f32::from(step) + 0.5) / f32::from(steps));
+            assert!(distance(middle, on_curve) <= tolerance);
+        }
+    }
+
+    #[test]
+    fn test_path_flatten_curves_within_tolerance() {
+        let quadratic = Path::new()
+            .move_to(0.0, 0.0)
+            .quadratic_to(1.0, 2.0, 2.0, 0.0);
+        let coarse = quadratic.flatten(0.1).remove(0).points;
+        let fine = quadratic.flatten(0.001).remove(0).points;
+        assert!(coarse.len() < fine.len());
+        assert_eq!(fine[fine.len() - 1], [2.0, 0.0]);
+        for tolerance in [0.1, 0.001] {
+            check_tolerance(&quadratic, |t| [2.0 * t, 4.0 * t * (1.0 - t)], tolerance);
+        }
+
+        let cubic = Path::new()
+            .move_to(0.0, 0.0)
+            .cubic_to(0.0, 1.0, 1.0, 1.0, 1.0, 0.0);
+        let curve = |t: f32| [3.0 * t * t - 2.0 * t * t * t, 3.0 * t * (1.0 - t)];
+        for tolerance in [0.1, 0.01] {
+            check_tolerance(&cubic, curve, tolerance);
+        }
+    }
+
+    #[test]
+    fn test_path_flatten_arc() {
+        let radius = Radius {
+            width: 1.0,
+            height: 1.0,
+        };
+        let half_circle = |sweep: bool| {
+            Path::new()
+                .move_to(-1.0, 0.0)
+                .arc_to(&radius, 0.0, false, sweep, 1.0, 0.0)
+                .flatten(0.001)
+                .remove(0)
+                .points
+        };
+
+        for sweep in [true, false] {
+            let points = half_circle(sweep);
+            assert!(points.len() > 10);
+            assert_eq!(points[points.len() - 1], [1.0, 0.0]);
+            assert!(points
+                .iter()
+                .all(|point| (length(*point) - 1.0).abs() < 1e-3));
+        }
+        //the sweep flag chooses the side of the arc, positive angles turn from x towards y
+        assert!(half_circle(true)[5][1] < 0.0);
+        assert!(half_circle(false)[5][1] > 0.0);
+    }
+
+    #[test]
+    fn test_path_fill_with_hole() {
+        let path = square(square(Path::new(), 0.0, 3.0), 1.0, 2.0);
+        let polygon = path.fill(0.1).unwrap();
+
+        assert_eq!(polygon.get_type(), ShapeType::Polygon);
+        assert_eq!(polygon.get_vertices().len(), 8 * 3);
+        //a square ring is made of eight triangles
+        assert_eq!(polygon.get_indices().unwrap().len(), 8 * 3);
+
+        //a contour inside the hole is filled again
+        let nested = square(path, 1.25, 1.75).fill(0.1).unwrap();
+        assert_eq!(nested.get_indices().unwrap().len(), 10 * 3);
+
+        assert!(Path::new()
+            .move_to(0.0, 0.0)
+            .line_to(1.0, 0.0)
+            .fill(0.1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_path_stroke() {
+        let path = square(Path::new(), 0.0, 1.0)
+            .move_to(5.0, 5.0)
+            .line_to(6.0, 5.0);
+        let polyline = path.stroke(0.1, |line| line.with_width(0.1)).unwrap();
+
+        //four quads of the closed square and one of the open line
+        assert_eq!(polyline.get_vertices().len(), 5 * 4 * 3);
+        assert_eq!(polyline.get_indices().unwrap().len(), 5 * 6);
+        assert_eq!(
+            *polyline.get_indices().unwrap().iter().max().unwrap(),
+            5 * 4 - 1
+        );
+
+        assert!(Path::new().stroke(0.1, |line| line).is_err());
+    }
+
+    #[test]
+    fn test_path_from_svg() {
+        let absolute = Path::from_svg("M0,0 L10,0 H10 V10 Q 10 20 0 20 T -10 20 C-10 10,-5 5 -5 0 S 0 0 0 -5 A5 5 0 0 1 0 5 Z").unwrap();
+        let relative =
+            Path::from_svg("m0 0 10 0h0v10q0 10-10 10t-10 0c0-10 5-15 5-20s5 0 5-5a5 5 0 010 10z")
+                .unwrap();
+
+        let absolute = absolute.flatten(0.01);
+        let relative = relative.flatten(0.01);
+        assert_eq!(absolute.len(), 1);
+        assert_eq!(absolute[0].points.len(), relative[0].points.len());
+        for (first, second) in absolute[0].points.iter().zip(&relative[0].points) {
+            assert!(distance(*first, *second) < 1e-4);
+        }
+        assert!(absolute[0].closed);
+
+        let numbers = Path::from_svg("M.5-.5l1e1.5").unwrap().flatten(0.1);
+        assert_eq!(numbers[0].points, vec![[0.5, -0.5], [10.5, 0.0]]);
+    }
+
+    #[test]
+    fn test_path_from_svg_invalid_get_error() {
+        assert!(Path::from_svg("10 10").is_err());
+        assert!(Path::from_svg("M 10").is_err());
+        assert!(Path::from_svg("M 0 0 X 10 10").is_err());
+        assert!(Path::from_svg("M 0 0 A 1 1 0 2 0 1 1").is_err());
+    }
+}
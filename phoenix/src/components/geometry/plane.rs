@@ -1,5 +1,6 @@
-use super::{Point, Radius};
-use crate::components::{FillMode, Shape, ShapeType};
+use super::{triangulation, Point, Radius};
+use crate::components::texture::projection::{self, Axis};
+use crate::components::{FillMode, Result, Shape, ShapeType};
 
 #[derive(Clone)]
 pub struct Triangle {
@@ -8,8 +9,60 @@ pub struct Triangle {
 
 pub struct Circle {
     vertices: Vec<f32>,
+    indices: Vec<u32>,
+    texture_coords: Vec<f32>,
 }
 
+/// `half_size` holds half of the width and height, so it can be built by `Scaler::radius`.
+pub struct Rectangle {
+    vertices: [f32; 12],
+}
+
+pub struct RoundedRectangle {
+    vertices: Vec<f32>,
+    indices: Vec<u32>,
+    texture_coords: Vec<f32>,
+}
+
+pub struct RegularPolygon {
+    vertices: Vec<f32>,
+    indices: Vec<u32>,
+    texture_coords: Vec<f32>,
+}
+
+pub struct Ring {
+    vertices: Vec<f32>,
+    indices: Vec<u32>,
+    texture_coords: Vec<f32>,
+}
+
+/// Part of a ring between two angles.
+pub struct Arc {
+    vertices: Vec<f32>,
+    indices: Vec<u32>,
+    texture_coords: Vec<f32>,
+}
+
+/// Part of a circle between two angles, like a pie slice.
+pub struct Sector {
+    vertices: Vec<f32>,
+    indices: Vec<u32>,
+    texture_coords: Vec<f32>,
+}
+
+/// Any simple polygon, concave ones and polygons with holes included.
+pub struct Polygon {
+    vertices: Vec<f32>,
+    indices: Vec<u32>,
+    texture_coords: Vec<f32>,
+}
+
+const RECTANGLE_INDICES: [u32; 6] = [0, 1, 2, 2, 3, 0];
+const RECTANGLE_TEXTURE_COORDS: [f32; 8] = [0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+//the texture base lies on the first edge and its top middle point on the last vertex
+const TRIANGLE_TEXTURE_COORDS: [f32; 6] = [0.0, 0.0, 1.0, 0.0, 0.5, 1.0];
+const FULL_ANGLE: f32 = 360.0;
+
 impl Triangle {
     #[must_use]
     pub fn new(vertices: [f32; 9]) -> Self {
@@ -22,6 +75,10 @@ impl Shape for Triangle {
         &self.vertices
     }
 
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&TRIANGLE_TEXTURE_COORDS)
+    }
+
     fn get_type(&self) -> ShapeType {
         ShapeType::Triangle
     }
@@ -32,6 +89,14 @@ impl Shape for Circle {
         &self.vertices
     }
 
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
     fn get_type(&self) -> ShapeType {
         ShapeType::Circle
     }
@@ -41,9 +106,135 @@ impl Shape for Circle {
     }
 }
 
+impl Shape for Rectangle {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&RECTANGLE_INDICES)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&RECTANGLE_TEXTURE_COORDS)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Rectangle
+    }
+}
+
+impl Shape for RoundedRectangle {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::RoundedRectangle
+    }
+}
+
+impl Shape for RegularPolygon {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::RegularPolygon
+    }
+}
+
+impl Shape for Ring {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Ring
+    }
+}
+
+impl Shape for Arc {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Arc
+    }
+}
+
+impl Shape for Sector {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Sector
+    }
+}
+
+impl Shape for Polygon {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Polygon
+    }
+}
+
 impl Circle {
     #[must_use]
-    pub fn new(center: &Point, radius: &Radius, mut segments: u8) -> Self {
+    pub fn new(center: &Point, radius: &Radius, mut segments: u16) -> Self {
         if segments < 5 {
             segments = 5;
         }
@@ -61,19 +252,303 @@ impl Circle {
             current_angle += angle;
         }
 
-        Self { vertices }
+        //The fan starts from the first point on the edge, the center is skipped
+        let indices = (1..=u32::from(segments)).collect();
+        Self {
+            texture_coords: projection::planar(&vertices, Axis::Z),
+            vertices,
+            indices,
+        }
     }
 }
 
+impl Rectangle {
+    #[must_use]
+    pub fn new(center: &Point, half_size: &Radius) -> Self {
+        let left = center.x - half_size.width;
+        let right = center.x + half_size.width;
+        let bottom = center.y - half_size.height;
+        let top = center.y + half_size.height;
+        let z = center.z;
+
+        Self {
+            vertices: [
+                left, bottom, z, right, bottom, z, right, top, z, left, top, z,
+            ],
+        }
+    }
+}
+
+impl RoundedRectangle {
+    /// The corner radius is limited to the half size, `segments` is the count per corner.
+    #[must_use]
+    pub fn new(center: &Point, half_size: &Radius, corner: &Radius, segments: u16) -> Self {
+        let corner = Radius {
+            width: corner.width.clamp(0.0, half_size.width),
+            height: corner.height.clamp(0.0, half_size.height),
+        };
+        let inner_width = half_size.width - corner.width;
+        let inner_height = half_size.height - corner.height;
+        let segments = segments.max(1);
+
+        let mut vertices: Vec<f32> = center.clone().into();
+        //corners counter-clockwise starting from the top right
+        for (x_sign, y_sign, start_angle) in [
+            (1.0, 1.0, 0.0),
+            (-1.0, 1.0, 90.0),
+            (-1.0, -1.0, 180.0),
+            (1.0, -1.0, 270.0),
+        ] {
+            let corner_center = Point {
+                x: center.x + x_sign * inner_width,
+                y: center.y + y_sign * inner_height,
+                z: center.z,
+            };
+            vertices.extend(outline(
+                &corner_center,
+                &corner,
+                start_angle,
+                90.0,
+                segments,
+                true,
+            ));
+        }
+
+        let outline_len = 4 * (u32::from(segments) + 1);
+        Self {
+            texture_coords: projection::planar(&vertices, Axis::Z),
+            vertices,
+            indices: fan_indices(outline_len, true),
+        }
+    }
+}
+
+impl RegularPolygon {
+    /// The first vertex points up.
+    #[must_use]
+    pub fn new(center: &Point, radius: &Radius, sides: u16) -> Self {
+        let sides = sides.max(3);
+        let mut vertices: Vec<f32> = center.clone().into();
+        vertices.extend(outline(center, radius, 90.0, FULL_ANGLE, sides, false));
+
+        Self {
+            texture_coords: projection::planar(&vertices, Axis::Z),
+            vertices,
+            indices: fan_indices(u32::from(sides), true),
+        }
+    }
+}
+
+impl Ring {
+    #[must_use]
+    pub fn new(center: &Point, outer: &Radius, inner: &Radius, segments: u16) -> Self {
+        let segments = segments.max(3);
+        let mut vertices = outline(center, outer, 0.0, FULL_ANGLE, segments, false);
+        vertices.extend(outline(center, inner, 0.0, FULL_ANGLE, segments, false));
+
+        Self {
+            texture_coords: projection::planar(&vertices, Axis::Z),
+            vertices,
+            indices: band_indices(u32::from(segments), true),
+        }
+    }
+}
+
+impl Arc {
+    /// Angles are in degrees, counted counter-clockwise from the positive x axis.
+    #[must_use]
+    pub fn new(
+        center: &Point,
+        outer: &Radius,
+        inner: &Radius,
+        start_angle: f32,
+        sweep_angle: f32,
+        segments: u16,
+    ) -> Self {
+        let segments = segments.max(1);
+        let sweep_angle = sweep_angle.clamp(0.0, FULL_ANGLE);
+        let mut vertices = outline(center, outer, start_angle, sweep_angle, segments, true);
+        vertices.extend(outline(
+            center,
+            inner,
+            start_angle,
+            sweep_angle,
+            segments,
+            true,
+        ));
+
+        Self {
+            texture_coords: projection::planar(&vertices, Axis::Z),
+            vertices,
+            indices: band_indices(u32::from(segments) + 1, false),
+        }
+    }
+}
+
+impl Sector {
+    /// Angles are in degrees, counted counter-clockwise from the positive x axis.
+    #[must_use]
+    pub fn new(
+        center: &Point,
+        radius: &Radius,
+        start_angle: f32,
+        sweep_angle: f32,
+        segments: u16,
+    ) -> Self {
+        let segments = segments.max(1);
+        let sweep_angle = sweep_angle.clamp(0.0, FULL_ANGLE);
+        let mut vertices: Vec<f32> = center.clone().into();
+        vertices.extend(outline(
+            center,
+            radius,
+            start_angle,
+            sweep_angle,
+            segments,
+            true,
+        ));
+
+        Self {
+            texture_coords: projection::planar(&vertices, Axis::Z),
+            vertices,
+            indices: fan_indices(u32::from(segments) + 1, false),
+        }
+    }
+}
+
+impl Polygon {
+    /// # Errors
+    ///
+    /// Will return `Err` if the contour is degenerate or intersects itself.
+    pub fn new(outer: &[Point]) -> Result<Self> {
+        Self::new_with_holes(outer, &[])
+    }
+
+    /// The polygon lies in the plane of the first outer point, other z values are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if any contour is degenerate, contours intersect
+    /// or a hole is not inside the outer contour.
+    pub fn new_with_holes(outer: &[Point], holes: &[Vec<Point>]) -> Result<Self> {
+        let to_2d = |points: &[Point]| -> Vec<[f32; 2]> {
+            points.iter().map(|point| [point.x, point.y]).collect()
+        };
+        let holes_2d: Vec<Vec<[f32; 2]>> = holes.iter().map(|hole| to_2d(hole)).collect();
+        let indices = triangulation::triangulate(&to_2d(outer), &holes_2d)?;
+
+        let z = outer.first().map_or(0.0, |point| point.z);
+        let vertices: Vec<f32> = outer
+            .iter()
+            .chain(holes.iter().flatten())
+            .flat_map(|point| [point.x, point.y, z])
+            .collect();
+
+        Ok(Self::new_triangulated(vertices, indices))
+    }
+
+    /// Polygon from already triangulated vertices, like a filled path.
+    pub(super) fn new_triangulated(vertices: Vec<f32>, indices: Vec<u32>) -> Self {
+        Self {
+            texture_coords: projection::planar(&vertices, Axis::Z),
+            vertices,
+            indices,
+        }
+    }
+}
+
+/// Points on the ellipse edge, `with_end` adds the closing point of the sweep.
+fn outline(
+    center: &Point,
+    radius: &Radius,
+    start_angle: f32,
+    sweep_angle: f32,
+    segments: u16,
+    with_end: bool,
+) -> Vec<f32> {
+    let points = if with_end { segments + 1 } else { segments };
+    (0..points)
+        .flat_map(|point| {
+            let angle =
+                f32::to_radians(start_angle + sweep_angle * f32::from(point) / f32::from(segments));
+            [
+                center.x + radius.width * angle.cos(),
+                center.y + radius.height * angle.sin(),
+                center.z,
+            ]
+        })
+        .collect()
+}
+
+/// Triangles between the center (index 0) and the outline points which follow it.
+fn fan_indices(points: u32, closed: bool) -> Vec<u32> {
+    let triangles = if closed { points } else { points - 1 };
+    (0..triangles)
+        .flat_map(|point| [0, point + 1, (point + 1) % points + 1])
+        .collect()
+}
+
+/// Quads between the outer outline (first `points`) and the inner one (next `points`).
+fn band_indices(points: u32, closed: bool) -> Vec<u32> {
+    let quads = if closed { points } else { points - 1 };
+    (0..quads)
+        .flat_map(|point| {
+            let next = (point + 1) % points;
+            [
+                point,
+                next,
+                points + next,
+                point,
+                points + next,
+                points + point,
+            ]
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::components::{
         geometry::{
-            plane::{Circle, Shape, ShapeType, Triangle},
+            plane::{
+                Arc, Circle, Polygon, Rectangle, RegularPolygon, Ring, RoundedRectangle, Sector,
+                Shape, ShapeType, Triangle,
+            },
             Point, Radius,
         },
         FillMode,
     }; // Import the Shape trait and ShapeType enum.
+    use crate::systems::scaler::Scaler;
+    use crate::window::Resolution;
+
+    fn center() -> Point {
+        Point {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    fn radius(value: f32) -> Radius {
+        Radius {
+            width: value,
+            height: value,
+        }
+    }
+
+    /// All triangles face the viewer, counter-clockwise winding in the XY plane.
+    fn check_triangles(shape: &dyn Shape) {
+        let vertices = shape.get_vertices();
+        let point = |index: u32| {
+            let start = index as usize * 3;
+            (vertices[start], vertices[start + 1])
+        };
+
+        for triangle in shape.get_indices().unwrap().chunks(3) {
+            let (a, b, c) = (point(triangle[0]), point(triangle[1]), point(triangle[2]));
+            let area = (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0);
+            assert!(area > 0.0);
+        }
+    }
 
     #[test]
     fn test_new_triangle() {
@@ -97,6 +572,7 @@ mod tests {
         };
         let circle = Circle::new(&center, &radius, 6);
         assert_eq!(circle.get_vertices().len(), 21);
+        assert_eq!(circle.get_indices().unwrap(), &[1, 2, 3, 4, 5, 6]);
         assert_eq!(circle.get_type(), ShapeType::Circle);
         assert_eq!(circle.get_fill_mode(), FillMode::Fan);
     }
@@ -117,4 +593,189 @@ mod tests {
         assert_eq!(circle.get_type(), ShapeType::Circle);
         assert_eq!(circle.get_fill_mode(), FillMode::Fan);
     }
+
+    #[test]
+    fn test_new_circle_more_than_255_segments() {
+        let circle = Circle::new(&center(), &radius(0.4), 1000);
+        assert_eq!(circle.get_vertices().len(), 1001 * 3);
+        assert_eq!(circle.get_indices().unwrap().len(), 1000);
+    }
+
+    #[test]
+    fn test_new_rectangle() {
+        let rectangle = Rectangle::new(
+            &center(),
+            &Radius {
+                width: 0.5,
+                height: 0.25,
+            },
+        );
+        assert_eq!(
+            rectangle.get_vertices(),
+            &[-0.5, -0.25, 0.0, 0.5, -0.25, 0.0, 0.5, 0.25, 0.0, -0.5, 0.25, 0.0]
+        );
+        assert_eq!(rectangle.get_type(), ShapeType::Rectangle);
+        assert_eq!(rectangle.get_fill_mode(), FillMode::Solid);
+        check_triangles(&rectangle);
+    }
+
+    #[test]
+    fn test_new_rounded_rectangle() {
+        let rectangle = RoundedRectangle::new(&center(), &radius(0.5), &radius(0.1), 4);
+
+        // Center and five points for each corner
+        assert_eq!(rectangle.get_vertices().len(), (1 + 4 * 5) * 3);
+        assert_eq!(rectangle.get_indices().unwrap().len(), 4 * 5 * 3);
+        assert_eq!(rectangle.get_type(), ShapeType::RoundedRectangle);
+        check_triangles(&rectangle);
+
+        let max_x = rectangle
+            .get_vertices()
+            .chunks(3)
+            .map(|point| point[0])
+            .fold(f32::MIN, f32::max);
+        assert!((max_x - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_new_rounded_rectangle_corner_limited_to_half_size() {
+        let rectangle = RoundedRectangle::new(&center(), &radius(0.5), &radius(2.0), 8);
+        assert!(rectangle
+            .get_vertices()
+            .chunks(3)
+            .all(|point| point[0].hypot(point[1]) <= 0.5 + 1e-6));
+    }
+
+    #[test]
+    fn test_new_regular_polygon() {
+        let hexagon = RegularPolygon::new(&center(), &radius(1.0), 6);
+        assert_eq!(hexagon.get_vertices().len(), 7 * 3);
+        assert_eq!(hexagon.get_indices().unwrap().len(), 6 * 3);
+        assert_eq!(hexagon.get_type(), ShapeType::RegularPolygon);
+        check_triangles(&hexagon);
+
+        // The first vertex after the center points up
+        assert!(hexagon.get_vertices()[3].abs() < 1e-6);
+        assert!((hexagon.get_vertices()[4] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_new_ring() {
+        let ring = Ring::new(&center(), &radius(1.0), &radius(0.5), 300);
+        assert_eq!(ring.get_vertices().len(), 600 * 3);
+        assert_eq!(ring.get_indices().unwrap().len(), 300 * 6);
+        assert_eq!(ring.get_type(), ShapeType::Ring);
+        check_triangles(&ring);
+    }
+
+    #[test]
+    fn test_new_arc_and_sector() {
+        let arc = Arc::new(&center(), &radius(1.0), &radius(0.8), 0.0, 90.0, 10);
+        assert_eq!(arc.get_vertices().len(), 2 * 11 * 3);
+        assert_eq!(arc.get_indices().unwrap().len(), 10 * 6);
+        assert_eq!(arc.get_type(), ShapeType::Arc);
+        check_triangles(&arc);
+
+        let sector = Sector::new(&center(), &radius(1.0), 45.0, 180.0, 12);
+        assert_eq!(sector.get_vertices().len(), 14 * 3);
+        assert_eq!(sector.get_indices().unwrap().len(), 12 * 3);
+        assert_eq!(sector.get_type(), ShapeType::Sector);
+        check_triangles(&sector);
+    }
+
+    #[test]
+    fn test_new_regular_polygon_with_scaler() {
+        let scaler = Scaler::new(Resolution {
+            width: 800,
+            height: 400,
+        });
+        let polygon = RegularPolygon::new(&center(), &scaler.radius(0.5), 4);
+
+        let max_x = polygon
+            .get_vertices()
+            .chunks(3)
+            .map(|point| point[0])
+            .fold(f32::MIN, f32::max);
+        assert!((max_x - 0.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_new_polygon_concave_with_hole() {
+        let point = |x: f32, y: f32| Point { x, y, z: 0.5 };
+        let outer = vec![
+            point(-1.0, -1.0),
+            point(1.0, -1.0),
+            point(1.0, 1.0),
+            point(0.0, 0.2),
+            point(-1.0, 1.0),
+        ];
+        let hole = vec![
+            point(-0.2, -0.6),
+            point(0.2, -0.6),
+            point(0.2, -0.2),
+            point(-0.2, -0.2),
+        ];
+        let polygon = Polygon::new_with_holes(&outer, &[hole]).unwrap();
+
+        assert_eq!(polygon.get_vertices().len(), 9 * 3);
+        assert!(polygon
+            .get_vertices()
+            .chunks(3)
+            .all(|point| point[2] == 0.5));
+        assert_eq!(polygon.get_type(), ShapeType::Polygon);
+        assert_eq!(polygon.get_fill_mode(), FillMode::Solid);
+        check_triangles(&polygon);
+    }
+
+    #[test]
+    fn test_new_polygon_self_intersecting_get_error() {
+        let point = |x: f32, y: f32| Point { x, y, z: 0.0 };
+        let bow_tie = [
+            point(0.0, 0.0),
+            point(1.0, 1.0),
+            point(1.0, 0.0),
+            point(0.0, 1.0),
+        ];
+        assert!(Polygon::new(&bow_tie).is_err());
+    }
+
+    #[test]
+    fn test_plane_shapes_texture_coords() {
+        let shapes: Vec<Box<dyn Shape>> = vec![
+            Box::new(Triangle::new([
+                -0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0,
+            ])),
+            Box::new(Circle::new(&center(), &radius(0.5), 8)),
+            Box::new(Rectangle::new(&center(), &radius(0.5))),
+            Box::new(RoundedRectangle::new(
+                &center(),
+                &radius(0.5),
+                &radius(0.1),
+                3,
+            )),
+            Box::new(RegularPolygon::new(&center(), &radius(0.5), 5)),
+            Box::new(Ring::new(&center(), &radius(0.5), &radius(0.2), 8)),
+            Box::new(Arc::new(
+                &center(),
+                &radius(0.5),
+                &radius(0.2),
+                0.0,
+                90.0,
+                4,
+            )),
+            Box::new(Sector::new(&center(), &radius(0.5), 0.0, 90.0, 4)),
+        ];
+
+        for shape in shapes {
+            let texture_coords = shape.get_texture_coords().unwrap();
+            assert_eq!(texture_coords.len(), shape.get_vertices().len() / 3 * 2);
+            assert!(texture_coords
+                .iter()
+                .all(|value| (0.0..=1.0).contains(value)));
+        }
+
+        //the circle center is skipped by the fan but still maps into the texture middle
+        let circle = Circle::new(&center(), &radius(0.5), 8);
+        assert_eq!(circle.get_texture_coords().unwrap()[..2], [0.5, 0.5]);
+    }
 }
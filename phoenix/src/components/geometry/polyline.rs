@@ -0,0 +1,891 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use cgmath::{InnerSpace, Vector3};
+
+use super::Point;
+use crate::components::shaders::ShaderSource;
+use crate::components::{Error, FillMode, Result, Shape, ShapeType};
+use crate::renderer::shaders::{BASIC_SHAPES_FRAG, POLYLINE_VERT};
+
+const MIN_LENGTH: f32 = 1e-6;
+
+/// Line of a given width expanded into triangles, so it is not limited to the
+/// 1-pixel lines of `FillMode::Lines`.
+pub struct Polyline {
+    vertices: Vec<f32>,
+    normals: Vec<f32>,
+    texture_coords: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+/// Shape of the corner between two segments.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Join {
+    /// Sharp corner, replaced by a bevel when the miter would be longer
+    /// than `limit` times the half width.
+    Miter {
+        limit: f32,
+    },
+    Round,
+    Bevel,
+}
+
+/// Shape of the open line ends.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Cap {
+    /// The line ends exactly at the end point.
+    #[default]
+    Butt,
+    /// The line is extended by half of its width.
+    Square,
+    Round,
+}
+
+/// Orientation of the line surface.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Facing {
+    /// The line lies across the given normal, `Vector3::unit_z()` gives a regular 2D line.
+    Plane(Vector3<f32>),
+    /// The line is turned towards the camera in every frame by the vertex shader,
+    /// the entity has to use the `screen_facing_shader`.
+    /// The shape keeps the line directions in the normals and the offsets from the line
+    /// in the texture coordinates, so such a line can not be textured.
+    Screen,
+}
+
+pub struct Builder {
+    points: Vec<Vector3<f32>>,
+    width: f32,
+    join: Join,
+    cap: Cap,
+    dashes: Vec<f32>,
+    dash_offset: f32,
+    closed: bool,
+    facing: Facing,
+    round_segments: u16,
+}
+
+impl Default for Join {
+    fn default() -> Self {
+        Self::Miter { limit: 4.0 }
+    }
+}
+
+/// Point of the expanded line, kept relative to the line until the facing is known.
+struct LineVertex {
+    center: Vector3<f32>,
+    direction: Vector3<f32>,
+    along: f32,
+    across: f32,
+    distance: f32,
+}
+
+/// Continuous part of the line, the whole line or a single dash.
+struct Piece {
+    points: Vec<Vector3<f32>>,
+    start_distance: f32,
+    closed: bool,
+}
+
+struct SegmentEnd {
+    direction: Vector3<f32>,
+    along: f32,
+    across: f32,
+}
+
+#[derive(Default)]
+struct Expansion {
+    vertices: Vec<LineVertex>,
+    indices: Vec<u32>,
+}
+
+/// Shader program for the `Facing::Screen` polylines.
+#[must_use]
+pub fn screen_facing_shader() -> ShaderSource {
+    ShaderSource::new(POLYLINE_VERT, BASIC_SHAPES_FRAG)
+}
+
+impl Builder {
+    #[must_use]
+    pub fn new(points: &[Point]) -> Self {
+        Self {
+            points: points
+                .iter()
+                .map(|point| Vector3::new(point.x, point.y, point.z))
+                .collect(),
+            width: 1.0,
+            join: Join::default(),
+            cap: Cap::default(),
+            dashes: Vec::new(),
+            dash_offset: 0.0,
+            closed: false,
+            facing: Facing::Plane(Vector3::unit_z()),
+            round_segments: 8,
+        }
+    }
+
+    /// Width in the units of the points.
+    #[must_use]
+    pub fn with_width(mut self, width: f32) -> Self {
+        self.width = width;
+        self
+    }
+
+    #[must_use]
+    pub fn with_join(mut self, join: Join) -> Self {
+        self.join = join;
+        self
+    }
+
+    #[must_use]
+    pub fn with_cap(mut self, cap: Cap) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Alternating dash and gap lengths, a list of odd length is repeated like in SVG.
+    /// `offset` moves the start of the pattern along the line. Every dash gets the line caps.
+    #[must_use]
+    pub fn with_dashes(mut self, pattern: Vec<f32>, offset: f32) -> Self {
+        self.dashes = pattern;
+        self.dash_offset = offset;
+        self
+    }
+
+    /// Joins the last point with the first one, such a line has no caps.
+    #[must_use]
+    pub fn with_closed(mut self, closed: bool) -> Self {
+        self.closed = closed;
+        self
+    }
+
+    #[must_use]
+    pub fn with_facing(mut self, facing: Facing) -> Self {
+        self.facing = facing;
+        self
+    }
+
+    /// Segments of a half circle, used by round joins and caps.
+    #[must_use]
+    pub fn with_round_segments(mut self, segments: u16) -> Self {
+        self.round_segments = segments.max(2);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` when the line has less than two distinct points, the width is not a finite positive number,
+    /// the dash pattern or offset is invalid or a segment is parallel to the facing normal.
+    pub fn build(mut self) -> Result<Polyline> {
+        if !(self.width.is_finite() && self.width > 0.0) {
+            return Err(Error::GeometryError(format!(
+                "Polyline width {} is not a finite positive number",
+                self.width
+            )));
+        }
+
+        self.points
+            .dedup_by(|next, previous| (*next - *previous).magnitude() < MIN_LENGTH);
+        if self.closed && self.points.len() > 2 {
+            let (first, last) = (self.points[0], self.points[self.points.len() - 1]);
+            if (last - first).magnitude() < MIN_LENGTH {
+                self.points.pop();
+            }
+        }
+        let min_points = if self.closed { 3 } else { 2 };
+        if self.points.len() < min_points {
+            return Err(Error::GeometryError(format!(
+                "Polyline needs at least {min_points} distinct points"
+            )));
+        }
+
+        let normal = match self.facing {
+            Facing::Plane(normal) if normal.magnitude() > MIN_LENGTH => Some(normal.normalize()),
+            Facing::Plane(_) => {
+                return Err(Error::GeometryError(
+                    "Polyline facing normal is a zero vector".to_string(),
+                ))
+            }
+            Facing::Screen => None,
+        };
+        if let Some(normal) = normal {
+            self.check_segments_across(normal)?;
+        }
+
+        let pieces = self.split_pieces()?;
+        let total_length = self.total_length();
+        let mut expansion = Expansion::default();
+        for piece in &pieces {
+            self.expand_piece(piece, &mut expansion);
+        }
+
+        Ok(match normal {
+            Some(normal) => Polyline::resolve_in_plane(expansion, normal, self.width, total_length),
+            None => Polyline::resolve_for_screen(expansion),
+        })
+    }
+
+    fn check_segments_across(&self, normal: Vector3<f32>) -> Result<()> {
+        let parallel = self
+            .segments()
+            .any(|(start, end)| (end - start).normalize().cross(normal).magnitude() < MIN_LENGTH);
+        if parallel {
+            return Err(Error::GeometryError(
+                "Polyline segment is parallel to the facing normal".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn segments(&self) -> impl Iterator<Item = (Vector3<f32>, Vector3<f32>)> + '_ {
+        let closing = self
+            .closed
+            .then(|| (self.points[self.points.len() - 1], self.points[0]));
+        self.points
+            .windows(2)
+            .map(|segment| (segment[0], segment[1]))
+            .chain(closing)
+    }
+
+    fn total_length(&self) -> f32 {
+        self.segments()
+            .map(|(start, end)| (end - start).magnitude())
+            .sum()
+    }
+
+    fn split_pieces(&self) -> Result<Vec<Piece>> {
+        if self.dashes.is_empty() {
+            return Ok(vec![Piece {
+                points: self.points.clone(),
+                start_distance: 0.0,
+                closed: self.closed,
+            }]);
+        }
+
+        let mut pattern = self.dashes.clone();
+        if !pattern.len().is_multiple_of(2) {
+            pattern.extend_from_within(..);
+        }
+        let pattern_length: f32 = pattern.iter().sum();
+        if pattern
+            .iter()
+            .any(|length| !(length.is_finite() && *length >= 0.0))
+            || pattern_length <= MIN_LENGTH
+        {
+            return Err(Error::GeometryError(format!(
+                "Invalid polyline dash pattern {:?}",
+                self.dashes
+            )));
+        }
+        if !self.dash_offset.is_finite() {
+            return Err(Error::GeometryError(format!(
+                "Polyline dash offset {} is not finite",
+                self.dash_offset
+            )));
+        }
+
+        let mut phase = self.dash_offset.rem_euclid(pattern_length);
+        let mut index = 0;
+        while phase >= pattern[index] {
+            phase -= pattern[index];
+            index = (index + 1) % pattern.len();
+        }
+        let mut remaining = pattern[index] - phase;
+        let is_dash = |index: usize| index.is_multiple_of(2);
+
+        let mut pieces = Vec::new();
+        let mut current = Piece {
+            points: vec![self.points[0]],
+            start_distance: 0.0,
+            closed: false,
+        };
+        let mut distance = 0.0;
+        for (start, end) in self.segments() {
+            let length = (end - start).magnitude();
+            let direction = (end - start) / length;
+            let mut travelled = 0.0;
+            while length - travelled > remaining {
+                travelled += remaining;
+                let point = start + direction * travelled;
+                if is_dash(index) {
+                    current.points.push(point);
+                    pieces.push(current);
+                }
+                current = Piece {
+                    points: vec![point],
+                    start_distance: distance + travelled,
+                    closed: false,
+                };
+                index = (index + 1) % pattern.len();
+                remaining = pattern[index];
+            }
+            remaining -= length - travelled;
+            distance += length;
+            current.points.push(end);
+        }
+        if is_dash(index) {
+            pieces.push(current);
+        }
+
+        //zero length dashes have no direction to be drawn with
+        for piece in &mut pieces {
+            piece
+                .points
+                .dedup_by(|next, previous| (*next - *previous).magnitude() < MIN_LENGTH);
+        }
+        pieces.retain(|piece| piece.points.len() > 1);
+        Ok(pieces)
+    }
+
+    fn expand_piece(&self, piece: &Piece, expansion: &mut Expansion) {
+        let points = &piece.points;
+        let segments_count = if piece.closed {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+        let segment = |index: usize| (points[index], points[(index + 1) % points.len()]);
+        let direction = |index: usize| {
+            let (start, end) = segment(index);
+            (end - start).normalize()
+        };
+        let half_width = self.width / 2.0;
+
+        let mut distance = piece.start_distance;
+        let mut first_quad = 0;
+        for index in 0..segments_count {
+            let (start, end) = segment(index);
+            let current = direction(index);
+            let length = (end - start).magnitude();
+            let is_first = index == 0 && !piece.closed;
+            let is_last = index + 1 == segments_count && !piece.closed;
+
+            let previous =
+                (!is_first).then(|| direction((index + segments_count - 1) % segments_count));
+            let next = (!is_last).then(|| direction((index + 1) % segments_count));
+
+            let start_end = self.segment_end(previous, current, -half_width);
+            let end_end = self.segment_end(next, current, half_width);
+            let quad = expansion.push_quad(
+                (start, &start_end, distance),
+                (end, &end_end, distance + length),
+            );
+            if index == 0 {
+                first_quad = quad;
+            }
+
+            if self.cap == Cap::Round {
+                let cap_center = |center| LineVertex {
+                    center,
+                    direction: current,
+                    along: 0.0,
+                    across: 0.0,
+                    distance,
+                };
+                if is_first {
+                    expansion.push_fan(
+                        cap_center(start),
+                        half_width,
+                        FRAC_PI_2,
+                        PI,
+                        self.round_segments,
+                    );
+                }
+                if is_last {
+                    let center = LineVertex {
+                        distance: distance + length,
+                        ..cap_center(end)
+                    };
+                    expansion.push_fan(center, half_width, -FRAC_PI_2, PI, self.round_segments);
+                }
+            }
+            if let Some(next) = next {
+                let joint = LineVertex {
+                    center: end,
+                    direction: next,
+                    along: 0.0,
+                    across: 0.0,
+                    distance: distance + length,
+                };
+                //the closing joint of a closed piece leads back to the first segment
+                let next_quad = (index + 1 == segments_count).then_some(first_quad);
+                self.push_join(expansion, joint, current, quad, next_quad);
+            }
+
+            distance += length;
+        }
+    }
+
+    /// Side of a segment at a joint (`neighbour` is the direction of the other segment)
+    /// or at an open end when there is no neighbour. `outwards` is the half width
+    /// pointing out of the segment, so it is negative at the segment start.
+    fn segment_end(
+        &self,
+        neighbour: Option<Vector3<f32>>,
+        direction: Vector3<f32>,
+        outwards: f32,
+    ) -> SegmentEnd {
+        let half_width = outwards.abs();
+        let butt = SegmentEnd {
+            direction,
+            along: 0.0,
+            across: half_width,
+        };
+
+        match neighbour {
+            None if self.cap == Cap::Square => SegmentEnd {
+                along: outwards,
+                ..butt
+            },
+            Some(neighbour) => match Builder::miter(self.join, direction, neighbour) {
+                Some((bisector, scale)) => SegmentEnd {
+                    direction: bisector,
+                    along: 0.0,
+                    across: half_width * scale,
+                },
+                None => butt,
+            },
+            None => butt,
+        }
+    }
+
+    /// Direction of the miter and the width scale, `None` when the miter is not used.
+    fn miter(join: Join, first: Vector3<f32>, second: Vector3<f32>) -> Option<(Vector3<f32>, f32)> {
+        let Join::Miter { limit } = join else {
+            return None;
+        };
+        let sum = first + second;
+        if sum.magnitude() < MIN_LENGTH {
+            return None;
+        }
+
+        let bisector = sum.normalize();
+        let scale = 1.0 / bisector.dot(second);
+        (scale <= limit).then_some((bisector, scale))
+    }
+
+    /// Fills the corner between the segment quad starting at `quad` and the next one,
+    /// which is pushed right after the join when `next_quad` is `None`.
+    fn push_join(
+        &self,
+        expansion: &mut Expansion,
+        joint: LineVertex,
+        incoming: Vector3<f32>,
+        quad: u32,
+        next_quad: Option<u32>,
+    ) {
+        if Builder::miter(self.join, incoming, joint.direction).is_some() {
+            return;
+        }
+
+        if self.join == Join::Round {
+            expansion.push_fan(joint, self.width / 2.0, 0.0, TAU, self.round_segments * 2);
+            return;
+        }
+
+        //the bevel is added on both sides, the one inside the corner is covered by the segments
+        let center = expansion.push_vertex(joint);
+        let next_quad = next_quad
+            .unwrap_or_else(|| u32::try_from(expansion.vertices.len()).unwrap_or(u32::MAX));
+        let (end_right, end_left) = (quad + 1, quad + 2);
+        let (start_right, start_left) = (next_quad, next_quad + 3);
+        expansion.indices.extend_from_slice(&[
+            center,
+            end_left,
+            start_left,
+            center,
+            start_right,
+            end_right,
+        ]);
+    }
+}
+
+impl Expansion {
+    fn push_vertex(&mut self, vertex: LineVertex) -> u32 {
+        self.vertices.push(vertex);
+        u32::try_from(self.vertices.len() - 1).unwrap_or(u32::MAX)
+    }
+
+    /// Vertices are pushed as: start right, end right, end left, start left.
+    fn push_quad(
+        &mut self,
+        (start, start_end, start_distance): (Vector3<f32>, &SegmentEnd, f32),
+        (end, end_end, end_distance): (Vector3<f32>, &SegmentEnd, f32),
+    ) -> u32 {
+        let first = u32::try_from(self.vertices.len()).unwrap_or(u32::MAX);
+        for (center, side, distance, sign) in [
+            (start, start_end, start_distance, -1.0),
+            (end, end_end, end_distance, -1.0),
+            (end, end_end, end_distance, 1.0),
+            (start, start_end, start_distance, 1.0),
+        ] {
+            self.vertices.push(LineVertex {
+                center,
+                direction: side.direction,
+                along: side.along,
+                across: side.across * sign,
+                distance,
+            });
+        }
+        self.indices
+            .extend_from_slice(&[first, first + 1, first + 2, first, first + 2, first + 3]);
+        first
+    }
+
+    /// Circle part around the `middle` vertex, angles are counted from its direction
+    /// towards the left side.
+    fn push_fan(
+        &mut self,
+        middle: LineVertex,
+        radius: f32,
+        start_angle: f32,
+        sweep_angle: f32,
+        segments: u16,
+    ) {
+        let (center, direction, distance) = (middle.center, middle.direction, middle.distance);
+        let middle = self.push_vertex(middle);
+        for segment in 0..=segments {
+            let angle = start_angle + sweep_angle * f32::from(segment) / f32::from(segments);
+            let point = self.push_vertex(LineVertex {
+                center,
+                direction,
+                along: radius * angle.cos(),
+                across: radius * angle.sin(),
+                distance,
+            });
+            if segment > 0 {
+                self.indices.extend_from_slice(&[middle, point - 1, point]);
+            }
+        }
+    }
+}
+
+impl Polyline {
+    /// Adds the triangles of another polyline, so a few lines can be drawn as one shape.
+    pub(super) fn append(&mut self, other: Polyline) {
+        let offset = u32::try_from(self.vertices.len() / 3).unwrap_or(u32::MAX);
+        self.vertices.extend(other.vertices);
+        self.normals.extend(other.normals);
+        self.texture_coords.extend(other.texture_coords);
+        self.indices
+            .extend(other.indices.into_iter().map(|index| index + offset));
+    }
+
+    fn resolve_in_plane(
+        expansion: Expansion,
+        normal: Vector3<f32>,
+        width: f32,
+        total_length: f32,
+    ) -> Self {
+        let mut vertices = Vec::with_capacity(expansion.vertices.len() * 3);
+        let mut texture_coords = Vec::with_capacity(expansion.vertices.len() * 2);
+        for vertex in &expansion.vertices {
+            let left = normal.cross(vertex.direction).normalize();
+            let position = vertex.center + vertex.direction * vertex.along + left * vertex.across;
+            vertices.extend_from_slice(&[position.x, position.y, position.z]);
+            texture_coords.extend_from_slice(&[
+                (vertex.distance + vertex.along) / total_length,
+                0.5 + vertex.across / width,
+            ]);
+        }
+
+        //keep the winding counter-clockwise when looking against the normal
+        let mut indices = expansion.indices;
+        let position = |index: u32| {
+            let start = index as usize * 3;
+            Vector3::new(vertices[start], vertices[start + 1], vertices[start + 2])
+        };
+        for triangle in indices.chunks_exact_mut(3) {
+            let (a, b, c) = (
+                position(triangle[0]),
+                position(triangle[1]),
+                position(triangle[2]),
+            );
+            if (b - a).cross(c - a).dot(normal) < 0.0 {
+                triangle.swap(1, 2);
+            }
+        }
+
+        Self {
+            normals: [normal.x, normal.y, normal.z].repeat(expansion.vertices.len()),
+            vertices,
+            texture_coords,
+            indices,
+        }
+    }
+
+    fn resolve_for_screen(expansion: Expansion) -> Self {
+        let mut vertices = Vec::with_capacity(expansion.vertices.len() * 3);
+        let mut normals = Vec::with_capacity(expansion.vertices.len() * 3);
+        let mut texture_coords = Vec::with_capacity(expansion.vertices.len() * 2);
+        for vertex in &expansion.vertices {
+            vertices.extend_from_slice(&[vertex.center.x, vertex.center.y, vertex.center.z]);
+            normals.extend_from_slice(&[
+                vertex.direction.x,
+                vertex.direction.y,
+                vertex.direction.z,
+            ]);
+            texture_coords.extend_from_slice(&[vertex.along, vertex.across]);
+        }
+
+        Self {
+            vertices,
+            normals,
+            texture_coords,
+            indices: expansion.indices,
+        }
+    }
+}
+
+impl Shape for Polyline {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Polyline
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        FillMode::Solid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn points(coordinates: &[(f32, f32)]) -> Vec<Point> {
+        coordinates
+            .iter()
+            .map(|(x, y)| Point {
+                x: *x,
+                y: *y,
+                z: 0.0,
+            })
+            .collect()
+    }
+
+    fn vertex(polyline: &Polyline, index: usize) -> [f32; 3] {
+        let vertices = polyline.get_vertices();
+        [
+            vertices[index * 3],
+            vertices[index * 3 + 1],
+            vertices[index * 3 + 2],
+        ]
+    }
+
+    fn assert_near(result: [f32; 3], expected: [f32; 3]) {
+        for (value, expected_value) in result.iter().zip(expected) {
+            assert!(
+                (value - expected_value).abs() < 1e-5,
+                "{result:?} != {expected:?}"
+            );
+        }
+    }
+
+    fn vertices_count(polyline: &Polyline) -> usize {
+        polyline.get_vertices().len() / 3
+    }
+
+    #[test]
+    fn test_polyline_straight_line() {
+        let polyline = Builder::new(&points(&[(0.0, 0.0), (2.0, 0.0)]))
+            .build()
+            .unwrap();
+
+        assert_eq!(vertices_count(&polyline), 4);
+        assert_near(vertex(&polyline, 0), [0.0, -0.5, 0.0]);
+        assert_near(vertex(&polyline, 1), [2.0, -0.5, 0.0]);
+        assert_near(vertex(&polyline, 2), [2.0, 0.5, 0.0]);
+        assert_near(vertex(&polyline, 3), [0.0, 0.5, 0.0]);
+        assert_eq!(polyline.get_indices().unwrap(), &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(polyline.get_normals().unwrap(), [0.0, 0.0, 1.0].repeat(4));
+        assert_eq!(
+            polyline.get_texture_coords().unwrap(),
+            &[0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0]
+        );
+        assert_eq!(polyline.get_type(), ShapeType::Polyline);
+    }
+
+    #[test]
+    fn test_polyline_caps() {
+        let line = points(&[(0.0, 0.0), (2.0, 0.0)]);
+        let square = Builder::new(&line).with_cap(Cap::Square).build().unwrap();
+        assert_near(vertex(&square, 0), [-0.5, -0.5, 0.0]);
+        assert_near(vertex(&square, 2), [2.5, 0.5, 0.0]);
+
+        let round = Builder::new(&line)
+            .with_cap(Cap::Round)
+            .with_round_segments(4)
+            .build()
+            .unwrap();
+        //quad and two fans of a center and five points
+        assert_eq!(vertices_count(&round), 4 + 2 * 6);
+        assert_eq!(round.get_indices().unwrap().len(), 6 + 2 * 4 * 3);
+        assert_near(vertex(&round, 4 + 3), [-0.5, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_polyline_joins() {
+        let corner = points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)]);
+        let miter = Builder::new(&corner).build().unwrap();
+        assert_eq!(vertices_count(&miter), 8);
+        assert_near(vertex(&miter, 1), [1.5, -0.5, 0.0]);
+        assert_near(vertex(&miter, 2), [0.5, 0.5, 0.0]);
+        assert_near(vertex(&miter, 4), [1.5, -0.5, 0.0]);
+
+        //the miter is longer than the limit and falls back to the bevel
+        let bevel = Builder::new(&corner)
+            .with_join(Join::Miter { limit: 1.2 })
+            .build()
+            .unwrap();
+        assert_eq!(vertices_count(&bevel), 9);
+        assert_eq!(bevel.get_indices().unwrap().len(), 18);
+        assert_near(vertex(&bevel, 1), [1.0, -0.5, 0.0]);
+
+        let round = Builder::new(&corner)
+            .with_join(Join::Round)
+            .with_round_segments(4)
+            .build()
+            .unwrap();
+        assert_eq!(vertices_count(&round), 8 + 10);
+        assert_eq!(round.get_indices().unwrap().len(), 12 + 8 * 3);
+    }
+
+    #[test]
+    fn test_polyline_triangles_face_normal() {
+        let square = points(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        for join in [Join::default(), Join::Bevel, Join::Round] {
+            let polyline = Builder::new(&square)
+                .with_join(join)
+                .with_closed(true)
+                .with_width(0.2)
+                .build()
+                .unwrap();
+            for triangle in polyline.get_indices().unwrap().chunks(3) {
+                let [a, b, c] =
+                    [0, 1, 2].map(|corner| vertex(&polyline, triangle[corner] as usize));
+                let area = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+                assert!(area >= -1e-6);
+            }
+        }
+
+        //closed lines have no caps, four quads joined by miters
+        let closed = Builder::new(&square)
+            .with_closed(true)
+            .with_cap(Cap::Round)
+            .build()
+            .unwrap();
+        assert_eq!(vertices_count(&closed), 16);
+    }
+
+    #[test]
+    fn test_polyline_dashes() {
+        let line = points(&[(0.0, 0.0), (4.0, 0.0), (10.0, 0.0)]);
+        let dashes = |pattern: Vec<f32>, offset: f32| {
+            let polyline = Builder::new(&line)
+                .with_join(Join::Bevel)
+                .with_dashes(pattern, offset)
+                .build()
+                .unwrap();
+            polyline.get_indices().unwrap().len() / 6
+        };
+
+        //dashes at 0-2, 3-5 (with a bevel at 4), 6-8 and 9-10
+        assert_eq!(dashes(vec![2.0, 1.0], 0.0), 4 + 2);
+        //odd pattern is repeated: 0-2, 4-6, 8-10
+        assert_eq!(dashes(vec![2.0], 0.0), 3);
+        //0-1, 2-4, 5-7, 8-10
+        assert_eq!(dashes(vec![2.0, 1.0], 1.0), 4);
+    }
+
+    #[test]
+    fn test_polyline_screen_facing() {
+        let polyline = Builder::new(&points(&[(0.0, 0.0), (2.0, 0.0)]))
+            .with_facing(Facing::Screen)
+            .with_width(2.0)
+            .build()
+            .unwrap();
+
+        assert_near(vertex(&polyline, 0), [0.0, 0.0, 0.0]);
+        assert_near(vertex(&polyline, 2), [2.0, 0.0, 0.0]);
+        assert_eq!(polyline.get_normals().unwrap(), [1.0, 0.0, 0.0].repeat(4));
+        assert_eq!(
+            polyline.get_texture_coords().unwrap(),
+            &[0.0, -1.0, 0.0, -1.0, 0.0, 1.0, 0.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_polyline_3d_plane() {
+        let line = vec![
+            Point {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            Point {
+                x: 0.0,
+                y: 0.0,
+                z: -2.0,
+            },
+        ];
+        let polyline = Builder::new(&line)
+            .with_facing(Facing::Plane(Vector3::unit_y()))
+            .build()
+            .unwrap();
+
+        assert_near(vertex(&polyline, 0), [0.5, 0.0, 0.0]);
+        assert_near(vertex(&polyline, 2), [-0.5, 0.0, -2.0]);
+
+        //the line can not be seen from the side of the plane
+        let result = Builder::new(&line).build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_polyline_invalid_get_error() {
+        let line = points(&[(0.0, 0.0), (1.0, 0.0)]);
+        assert!(Builder::new(&line).with_width(0.0).build().is_err());
+        assert!(Builder::new(&points(&[(0.0, 0.0), (0.0, 0.0)]))
+            .build()
+            .is_err());
+        assert!(Builder::new(&line).with_closed(true).build().is_err());
+        assert!(Builder::new(&line)
+            .with_dashes(vec![0.0, 0.0], 0.0)
+            .build()
+            .is_err());
+        assert!(Builder::new(&line)
+            .with_dashes(vec![1.0, -1.0], 0.0)
+            .build()
+            .is_err());
+
+        //NaN fails every comparison, so it has to be rejected on its own
+        assert!(Builder::new(&line).with_width(f32::NAN).build().is_err());
+        assert!(Builder::new(&line)
+            .with_width(f32::INFINITY)
+            .build()
+            .is_err());
+        assert!(Builder::new(&line)
+            .with_dashes(vec![1.0, f32::NAN], 0.0)
+            .build()
+            .is_err());
+        assert!(Builder::new(&line)
+            .with_dashes(vec![1.0, 1.0], f32::NAN)
+            .build()
+            .is_err());
+    }
+}
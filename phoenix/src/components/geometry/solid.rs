@@ -1,16 +1,76 @@
-use crate::components::{FillMode, Shape, ShapeType};
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use crate::components::{FillMode, NormalMode, Shape, ShapeType};
 
 use super::{Point, Radius};
 
 pub struct Cube {
-    vertices: [f32; 108],
+    vertices: [f32; 72],
+    normal_mode: NormalMode,
 }
 
+/// Texture coordinates follow the longitude and latitude, the seam column of vertices is doubled.
 pub struct Sphere {
     vertices: Vec<f32>,
+    indices: Vec<u32>,
+    texture_coords: Vec<f32>,
     mode: FillMode,
+    normal_mode: NormalMode,
+}
+
+/// Aligned with the y axis, like the other parametric shapes below.
+/// All of them carry their own outward normals and texture coordinates.
+pub struct Cylinder {
+    surface: Surface,
+}
+
+pub struct Cone {
+    surface: Surface,
+}
+
+pub struct Torus {
+    surface: Surface,
+}
+
+pub struct Capsule {
+    surface: Surface,
+}
+
+/// Grid in the XZ plane facing up, subdivided into `columns` x `rows` cells.
+pub struct Plane {
+    surface: Surface,
+}
+
+#[derive(Default)]
+struct Surface {
+    vertices: Vec<f32>,
+    normals: Vec<f32>,
+    texture_coords: Vec<f32>,
+    indices: Vec<u32>,
+    mode: FillMode,
+}
+
+struct Vertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+    texture_coord: [f32; 2],
 }
 
+const MIN_SEGMENTS: u16 = 3;
+
+/// Two triangles for each of the six faces, four vertices per face.
+const CUBE_INDICES: [u32; 36] = [
+    0, 1, 2, 2, 3, 0, 4, 5, 6, 6, 7, 4, 8, 9, 10, 10, 11, 8, 12, 13, 14, 14, 15, 12, 16, 17, 18,
+    18, 19, 16, 20, 21, 22, 22, 23, 20,
+];
+
+/// One whole texture image on every face.
+pub(crate) const CUBE_TEXTURE_COORDS: [f32; 48] = [
+    0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0,
+    1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0,
+    0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0,
+];
+
 impl Cube {
     #[must_use]
     pub fn new(side: f32, center_point: [f32; 3]) -> Self {
@@ -25,17 +85,22 @@ impl Cube {
         let near = cz + half_side;
 
         let vertices = [
-            left, bottom, far, right, bottom, far, right, top, far, right, top, far, left, top,
-            far, left, bottom, far, left, bottom, near, right, bottom, near, right, top, near,
-            right, top, near, left, top, near, left, bottom, near, left, top, near, left, top, far,
-            left, bottom, far, left, bottom, far, left, bottom, near, left, top, near, right, top,
-            near, right, top, far, right, bottom, far, right, bottom, far, right, bottom, near,
-            right, top, near, left, bottom, far, right, bottom, far, right, bottom, near, right,
-            bottom, near, left, bottom, near, left, bottom, far, left, top, far, right, top, far,
-            right, top, near, right, top, near, left, top, near, left, top, far,
+            left, bottom, far, right, bottom, far, right, top, far, left, top, far, left, bottom,
+            near, right, bottom, near, right, top, near, left, top, near, left, top, near, left,
+            top, far, left, bottom, far, left, bottom, near, right, top, near, right, top, far,
+            right, bottom, far, right, bottom, near, left, bottom, far, right, bottom, far, right,
+            bottom, near, left, bottom, near, left, top, far, right, top, far, right, top, near,
+            left, top, near,
         ];
 
-        Self { vertices }
+        Self {
+            vertices,
+            normal_mode: NormalMode::Flat,
+        }
+    }
+
+    pub fn set_normal_mode(&mut self, normal_mode: NormalMode) {
+        self.normal_mode = normal_mode;
     }
 }
 
@@ -47,8 +112,17 @@ impl Sphere {
         vertices.extend(Sphere::calculate_sector_points(center, radius, precision));
         vertices.push(Sphere::calculate_bottom_point(center, radius.height));
         Self {
-            vertices: Sphere::generate_geometry(&vertices, precision),
+            vertices: vertices
+                .iter()
+                .flatten()
+                .flat_map(|point| [point.x, point.y, point.z])
+                .collect(),
+            indices: Sphere::generate_indices(precision),
+            texture_coords: Sphere::generate_texture_coords(precision),
             mode: FillMode::Solid,
+            normal_mode: NormalMode::Smooth {
+                crease_angle: 180.0,
+            },
         }
     }
 
@@ -56,6 +130,10 @@ impl Sphere {
         self.mode = mode;
     }
 
+    pub fn set_normal_mode(&mut self, normal_mode: NormalMode) {
+        self.normal_mode = normal_mode;
+    }
+
     fn calculate_upper_point(center: &Point, height_radius: f32) -> Vec<Point> {
         vec![Point {
             x: center.x,
@@ -81,7 +159,7 @@ impl Sphere {
             let mut sector_points = Vec::new();
             let mut sector_angle = 0_f32;
             stack_angle -= stack_angle_leap;
-            for _ in 0..(precision * 3) {
+            for _ in 0..=(precision * 3) {
                 sector_points.push(Sphere::calculate_point(
                     center,
                     radius,
@@ -113,98 +191,322 @@ impl Sphere {
         }
     }
 
-    fn generate_geometry(vertices: &[Vec<Point>], precision: u16) -> Vec<f32> {
-        let mut result = Vec::with_capacity(Sphere::estimate_buffer_len(precision));
-        for i in 0..usize::from(precision - 1) {
-            let top = vertices[i + 1].clone();
-            let bottom = vertices[i + 2].clone();
-
-            for j in 0..usize::from(precision * 3) {
-                result.push(bottom[j].x);
-                result.push(bottom[j].y);
-                result.push(bottom[j].z);
+    /// Vertices are laid out as: upper point, `precision` stacks of `precision * 3 + 1`
+    /// sector points each (the last one closes the stack at the seam), bottom point.
+    fn generate_indices(precision: u16) -> Vec<u32> {
+        let sectors = u32::from(precision) * 3;
+        let stack_start = |stack: u32| 1 + stack * (sectors + 1);
 
-                let index = if j + 1 == bottom.len() { 0 } else { j + 1 };
-                result.push(bottom[index].x);
-                result.push(bottom[index].y);
-                result.push(bottom[index].z);
+        let mut result = Vec::with_capacity(Sphere::estimate_indices_len(precision));
+        for i in 0..u32::from(precision - 1) {
+            let top = stack_start(i);
+            let bottom = stack_start(i + 1);
 
-                result.push(top[j].x);
-                result.push(top[j].y);
-                result.push(top[j].z);
+            for j in 0..sectors {
+                result.extend_from_slice(&[bottom + j, bottom + j + 1, top + j]);
             }
 
-            for j in 0..usize::from(precision * 3) {
-                result.push(top[j].x);
-                result.push(top[j].y);
-                result.push(top[j].z);
-
-                let index = if j + 1 == bottom.len() { 0 } else { j + 1 };
-                result.push(top[index].x);
-                result.push(top[index].y);
-                result.push(top[index].z);
-
-                let tmp_index = if j + 1 == bottom.len() { 0 } else { j + 1 };
-                result.push(bottom[tmp_index].x);
-                result.push(bottom[tmp_index].y);
-                result.push(bottom[tmp_index].z);
+            for j in 0..sectors {
+                result.extend_from_slice(&[top + j, bottom + j + 1, top + j + 1]);
             }
         }
 
         //upper and bottom
-        let top_point = vertices[0].clone();
-        let bottom = vertices[1].clone();
-        for i in 0..usize::from(precision * 3) {
-            result.push(top_point[0].x);
-            result.push(top_point[0].y);
-            result.push(top_point[0].z);
-
-            let index = if i + 1 == bottom.len() { 0 } else { i + 1 };
-            result.push(bottom[index].x);
-            result.push(bottom[index].y);
-            result.push(bottom[index].z);
-
-            result.push(bottom[i].x);
-            result.push(bottom[i].y);
-            result.push(bottom[i].z);
+        let top_point = 0;
+        let first_stack = stack_start(0);
+        for i in 0..sectors {
+            result.extend_from_slice(&[top_point, first_stack + i, first_stack + i + 1]);
         }
 
-        let bottom_point = vertices.last().unwrap();
-        let last_stack = vertices[vertices.len() - 2].clone();
-        for i in 0..usize::from(precision * 3) {
-            result.push(bottom_point[0].x);
-            result.push(bottom_point[0].y);
-            result.push(bottom_point[0].z);
-
-            let index = if i + 1 == last_stack.len() { 0 } else { i + 1 };
-            result.push(last_stack[index].x);
-            result.push(last_stack[index].y);
-            result.push(last_stack[index].z);
-
-            result.push(last_stack[i].x);
-            result.push(last_stack[i].y);
-            result.push(last_stack[i].z);
+        let bottom_point = stack_start(u32::from(precision));
+        let last_stack = stack_start(u32::from(precision) - 1);
+        for i in 0..sectors {
+            result.extend_from_slice(&[bottom_point, last_stack + i + 1, last_stack + i]);
         }
 
         result
     }
 
-    fn estimate_buffer_len(precision: u16) -> usize {
-        let vertices_per_point = 9;
+    fn generate_texture_coords(precision: u16) -> Vec<f32> {
+        let sectors = precision * 3;
+        let mut result = vec![0.5, 1.0];
+        for stack in 1..=precision {
+            let v = 1.0 - ratio(stack, precision);
+            for sector in 0..=sectors {
+                result.extend_from_slice(&[ratio(sector, sectors), v]);
+            }
+        }
+        result.extend_from_slice(&[0.5, 0.0]);
+        result
+    }
+
+    fn estimate_indices_len(precision: u16) -> usize {
+        let indices_per_triangle = 3;
         let two_triangles_per_unit = 2;
         let sectors = usize::from(precision) * 3;
-        usize::from(precision) * two_triangles_per_unit * vertices_per_point * sectors
+        usize::from(precision) * two_triangles_per_unit * indices_per_triangle * sectors
+    }
+}
+
+impl Cylinder {
+    #[must_use]
+    pub fn new(center: &Point, radius: f32, height: f32, segments: u16) -> Self {
+        let segments = segments.max(MIN_SEGMENTS);
+        let half_height = height / 2.0;
+        let mut surface = Surface::default();
+        surface.add_grid(segments, 1, |column, row| {
+            let (u, v) = (ratio(column, segments), ratio(row, 1));
+            let (cos, sin) = circle_point(u);
+            Vertex {
+                position: [
+                    center.x + radius * cos,
+                    center.y - half_height + v * height,
+                    center.z + radius * sin,
+                ],
+                normal: [cos, 0.0, sin],
+                texture_coord: [u, v],
+            }
+        });
+        surface.add_cap(center, radius, half_height, segments);
+        surface.add_cap(center, radius, -half_height, segments);
+        Self { surface }
+    }
+
+    pub fn set_fill_mode(&mut self, mode: FillMode) {
+        self.surface.mode = mode;
+    }
+}
+
+impl Cone {
+    /// The base lies at `center.y - height / 2`, the apex at `center.y + height / 2`.
+    #[must_use]
+    pub fn new(center: &Point, radius: f32, height: f32, segments: u16) -> Self {
+        let segments = segments.max(MIN_SEGMENTS);
+        let half_height = height / 2.0;
+        let slant = f32::hypot(radius, height);
+        let mut surface = Surface::default();
+        surface.add_grid(segments, 1, |column, row| {
+            let (u, v) = (ratio(column, segments), ratio(row, 1));
+            let (cos, sin) = circle_point(u);
+            let ring_radius = radius * (1.0 - v);
+            Vertex {
+                position: [
+                    center.x + ring_radius * cos,
+                    center.y - half_height + v * height,
+                    center.z + ring_radius * sin,
+                ],
+                normal: [height / slant * cos, radius / slant, height / slant * sin],
+                texture_coord: [u, v],
+            }
+        });
+        surface.add_cap(center, radius, -half_height, segments);
+        Self { surface }
+    }
+
+    pub fn set_fill_mode(&mut self, mode: FillMode) {
+        self.surface.mode = mode;
     }
 }
 
+impl Torus {
+    /// `rings` go around the main circle, `sides` around the tube.
+    #[must_use]
+    pub fn new(
+        center: &Point,
+        major_radius: f32,
+        minor_radius: f32,
+        rings: u16,
+        sides: u16,
+    ) -> Self {
+        let (rings, sides) = (rings.max(MIN_SEGMENTS), sides.max(MIN_SEGMENTS));
+        let mut surface = Surface::default();
+        surface.add_grid(rings, sides, |column, row| {
+            let (u, v) = (ratio(column, rings), ratio(row, sides));
+            let (cos, sin) = circle_point(u);
+            let (tube_cos, tube_sin) = circle_point(v);
+            let distance = major_radius + minor_radius * tube_cos;
+            Vertex {
+                position: [
+                    center.x + distance * cos,
+                    center.y + minor_radius * tube_sin,
+                    center.z + distance * sin,
+                ],
+                normal: [tube_cos * cos, tube_sin, tube_cos * sin],
+                texture_coord: [u, v],
+            }
+        });
+        Self { surface }
+    }
+
+    pub fn set_fill_mode(&mut self, mode: FillMode) {
+        self.surface.mode = mode;
+    }
+}
+
+impl Capsule {
+    /// `height` is the length of the cylindrical part, each hemisphere has `rings` stacks.
+    #[must_use]
+    pub fn new(center: &Point, radius: f32, height: f32, segments: u16, rings: u16) -> Self {
+        let segments = segments.max(MIN_SEGMENTS);
+        let rings = rings.max(1);
+        let half_height = height / 2.0;
+        let total_height = height + 2.0 * radius;
+        let mut surface = Surface::default();
+        //bottom hemisphere rows, then top hemisphere rows, the band between them is the cylinder
+        surface.add_grid(segments, 2 * rings + 1, |column, row| {
+            let (latitude, offset) = if row <= rings {
+                (-FRAC_PI_2 + FRAC_PI_2 * ratio(row, rings), -half_height)
+            } else {
+                (FRAC_PI_2 * ratio(row - rings - 1, rings), half_height)
+            };
+
+            let u = ratio(column, segments);
+            let (cos, sin) = circle_point(u);
+            let normal = [latitude.cos() * cos, latitude.sin(), latitude.cos() * sin];
+            let y = offset + radius * normal[1];
+            Vertex {
+                position: [
+                    center.x + radius * normal[0],
+                    center.y + y,
+                    center.z + radius * normal[2],
+                ],
+                normal,
+                texture_coord: [u, (y + total_height / 2.0) / total_height],
+            }
+        });
+        Self { surface }
+    }
+
+    pub fn set_fill_mode(&mut self, mode: FillMode) {
+        self.surface.mode = mode;
+    }
+}
+
+impl Plane {
+    #[must_use]
+    pub fn new(center: &Point, width: f32, depth: f32, columns: u16, rows: u16) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let mut surface = Surface::default();
+        surface.add_grid(columns, rows, |column, row| {
+            let (u, v) = (ratio(column, columns), ratio(row, rows));
+            Vertex {
+                position: [
+                    center.x - width / 2.0 + u * width,
+                    center.y,
+                    center.z - depth / 2.0 + v * depth,
+                ],
+                normal: [0.0, 1.0, 0.0],
+                texture_coord: [u, 1.0 - v],
+            }
+        });
+        Self { surface }
+    }
+
+    pub fn set_fill_mode(&mut self, mode: FillMode) {
+        self.surface.mode = mode;
+    }
+}
+
+impl Surface {
+    fn add_vertex(&mut self, vertex: &Vertex) -> u32 {
+        let index = u32::try_from(self.vertices.len() / 3).unwrap_or(u32::MAX);
+        self.vertices.extend_from_slice(&vertex.position);
+        self.normals.extend_from_slice(&vertex.normal);
+        self.texture_coords.extend_from_slice(&vertex.texture_coord);
+        index
+    }
+
+    /// Adds `(columns + 1) * (rows + 1)` vertices, the seam column is duplicated
+    /// so the texture coordinates can wrap from 1 back to 0.
+    fn add_grid(&mut self, columns: u16, rows: u16, vertex: impl Fn(u16, u16) -> Vertex) {
+        let first = u32::try_from(self.vertices.len() / 3).unwrap_or(u32::MAX);
+        for row in 0..=rows {
+            for column in 0..=columns {
+                self.add_vertex(&vertex(column, row));
+            }
+        }
+
+        let stride = u32::from(columns) + 1;
+        for row in 0..u32::from(rows) {
+            for column in 0..u32::from(columns) {
+                let current = first + row * stride + column;
+                let above = current + stride;
+                self.indices.extend_from_slice(&[
+                    current,
+                    above + 1,
+                    current + 1,
+                    current,
+                    above,
+                    above + 1,
+                ]);
+            }
+        }
+    }
+
+    /// Flat disc at `offset` from the center, facing up for positive offsets and down otherwise.
+    fn add_cap(&mut self, center: &Point, radius: f32, offset: f32, segments: u16) {
+        let normal = [0.0, offset.signum(), 0.0];
+        let middle = self.add_vertex(&Vertex {
+            position: [center.x, center.y + offset, center.z],
+            normal,
+            texture_coord: [0.5, 0.5],
+        });
+
+        for segment in 0..segments {
+            let (cos, sin) = circle_point(ratio(segment, segments));
+            self.add_vertex(&Vertex {
+                position: [
+                    center.x + radius * cos,
+                    center.y + offset,
+                    center.z + radius * sin,
+                ],
+                normal,
+                texture_coord: [0.5 + 0.5 * cos, 0.5 + 0.5 * sin],
+            });
+        }
+
+        let segments = u32::from(segments);
+        for segment in 0..segments {
+            let current = middle + 1 + segment;
+            let next = middle + 1 + (segment + 1) % segments;
+            if offset > 0.0 {
+                self.indices.extend_from_slice(&[middle, next, current]);
+            } else {
+                self.indices.extend_from_slice(&[middle, current, next]);
+            }
+        }
+    }
+}
+
+fn ratio(step: u16, steps: u16) -> f32 {
+    f32::from(step) / f32::from(steps)
+}
+
+fn circle_point(ratio: f32) -> (f32, f32) {
+    let angle = ratio * TAU;
+    (angle.cos(), angle.sin())
+}
+
 impl Shape for Cube {
     fn get_vertices(&self) -> &[f32] {
         &self.vertices
     }
 
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&CUBE_INDICES)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&CUBE_TEXTURE_COORDS)
+    }
+
     fn get_type(&self) -> ShapeType {
         ShapeType::Cube
     }
+
+    fn get_normal_mode(&self) -> NormalMode {
+        self.normal_mode
+    }
 }
 
 impl Shape for Sphere {
@@ -212,6 +514,14 @@ impl Shape for Sphere {
         &self.vertices
     }
 
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
     fn get_type(&self) -> ShapeType {
         ShapeType::Sphere
     }
@@ -219,11 +529,264 @@ impl Shape for Sphere {
     fn get_fill_mode(&self) -> FillMode {
         self.mode
     }
+
+    fn get_normal_mode(&self) -> NormalMode {
+        self.normal_mode
+    }
+}
+
+impl Shape for Cylinder {
+    fn get_vertices(&self) -> &[f32] {
+        &self.surface.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.surface.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.surface.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.surface.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Cylinder
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        self.surface.mode
+    }
+}
+
+impl Shape for Cone {
+    fn get_vertices(&self) -> &[f32] {
+        &self.surface.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.surface.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.surface.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.surface.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Cone
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        self.surface.mode
+    }
+}
+
+impl Shape for Torus {
+    fn get_vertices(&self) -> &[f32] {
+        &self.surface.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.surface.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.surface.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.surface.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Torus
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        self.surface.mode
+    }
+}
+
+impl Shape for Capsule {
+    fn get_vertices(&self) -> &[f32] {
+        &self.surface.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.surface.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.surface.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.surface.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Capsule
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        self.surface.mode
+    }
+}
+
+impl Shape for Plane {
+    fn get_vertices(&self) -> &[f32] {
+        &self.surface.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.surface.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.surface.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.surface.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Plane
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        self.surface.mode
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use cgmath::{InnerSpace, Vector3};
+
+    fn origin() -> Point {
+        Point {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    fn radius_one() -> Radius {
+        Radius {
+            width: 1.0,
+            height: 1.0,
+        }
+    }
+
+    fn vertex_at(values: &[f32], index: u32) -> Vector3<f32> {
+        let start = index as usize * 3;
+        Vector3::new(values[start], values[start + 1], values[start + 2])
+    }
+
+    /// Every triangle must be wound counter-clockwise when looking at its normals.
+    fn check_parametric_shape(shape: &dyn Shape) {
+        let vertices = shape.get_vertices();
+        let normals = shape.get_normals().unwrap();
+        let vertices_count = vertices.len() / 3;
+        assert_eq!(normals.len(), vertices.len());
+        assert_eq!(
+            shape.get_texture_coords().unwrap().len(),
+            vertices_count * 2
+        );
+
+        for triangle in shape.get_indices().unwrap().chunks(3) {
+            assert!(triangle
+                .iter()
+                .all(|index| (*index as usize) < vertices_count));
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| vertex_at(vertices, i));
+            let face_normal = (b - a).cross(c - a);
+            if face_normal.magnitude() < 1e-6 {
+                continue;
+            }
+
+            let vertex_normal = vertex_at(normals, triangle[0])
+                + vertex_at(normals, triangle[1])
+                + vertex_at(normals, triangle[2]);
+            assert!(face_normal.dot(vertex_normal) > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_cylinder_new() {
+        let mut cylinder = Cylinder::new(&origin(), 0.5, 2.0, 16);
+        check_parametric_shape(&cylinder);
+
+        // Side grid with duplicated seam and two caps
+        assert_eq!(cylinder.get_vertices().len() / 3, 17 * 2 + 2 * 17);
+        assert_eq!(cylinder.get_indices().unwrap().len(), 16 * 6 + 2 * 16 * 3);
+        assert_eq!(cylinder.get_type(), ShapeType::Cylinder);
+
+        cylinder.set_fill_mode(FillMode::Lines);
+        assert_eq!(cylinder.get_fill_mode(), FillMode::Lines);
+    }
+
+    #[test]
+    fn test_cylinder_minimum_segments() {
+        let cylinder = Cylinder::new(&origin(), 0.5, 2.0, 1);
+        assert_eq!(cylinder.get_indices().unwrap().len(), 3 * 6 + 2 * 3 * 3);
+    }
+
+    #[test]
+    fn test_cone_new() {
+        let cone = Cone::new(&origin(), 1.0, 1.0, 12);
+        check_parametric_shape(&cone);
+        assert_eq!(cone.get_type(), ShapeType::Cone);
+
+        // Side normals lean upwards at 45 degrees for equal radius and height
+        let normal = vertex_at(cone.get_normals().unwrap(), 0);
+        assert!((normal.x - normal.y).abs() < 1e-6);
+        assert!((normal.magnitude() - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_torus_new() {
+        let torus = Torus::new(&origin(), 1.0, 0.25, 24, 12);
+        check_parametric_shape(&torus);
+        assert_eq!(torus.get_vertices().len() / 3, 25 * 13);
+        assert_eq!(torus.get_type(), ShapeType::Torus);
+
+        let outer = vertex_at(torus.get_vertices(), 0);
+        assert!((outer.x - 1.25).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_capsule_new() {
+        let capsule = Capsule::new(&origin(), 0.5, 1.0, 16, 4);
+        check_parametric_shape(&capsule);
+        assert_eq!(capsule.get_type(), ShapeType::Capsule);
+
+        let heights: Vec<f32> = capsule.get_vertices().chunks(3).map(|v| v[1]).collect();
+        let lowest = heights.iter().copied().fold(f32::MAX, f32::min);
+        let highest = heights.iter().copied().fold(f32::MIN, f32::max);
+        assert!((lowest + 1.0).abs() < 1e-6);
+        assert!((highest - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_plane_new() {
+        let plane = Plane::new(&origin(), 2.0, 4.0, 4, 2);
+        check_parametric_shape(&plane);
+        assert_eq!(plane.get_vertices().len() / 3, 5 * 3);
+        assert_eq!(plane.get_indices().unwrap().len(), 4 * 2 * 6);
+        assert_eq!(plane.get_type(), ShapeType::Plane);
+        assert!(plane
+            .get_normals()
+            .unwrap()
+            .chunks(3)
+            .all(|normal| normal == [0.0, 1.0, 0.0]));
+    }
 
     #[test]
     fn test_sphere_new() {
@@ -239,10 +802,16 @@ mod tests {
         let precision = 10;
         let mut sphere = Sphere::new(&center, &radius, precision);
 
-        // Calculate expected number of vertices
-        let expected_vertices = Sphere::estimate_buffer_len(precision);
+        // Upper point, 10 stacks of 31 sector points and bottom point
+        let expected_vertices = (1 + 10 * 31 + 1) * 3;
+        let expected_indices = Sphere::estimate_indices_len(precision);
 
         assert_eq!(sphere.get_vertices().len(), expected_vertices);
+        assert_eq!(
+            sphere.get_texture_coords().unwrap().len(),
+            expected_vertices / 3 * 2
+        );
+        assert_eq!(sphere.get_indices().unwrap().len(), expected_indices);
         assert_eq!(sphere.get_type(), ShapeType::Sphere);
         assert_eq!(sphere.get_fill_mode(), FillMode::Solid);
 
@@ -276,7 +845,76 @@ mod tests {
         let center_point = [0.1, 0.2, 0.3];
         let cube = Cube::new(0.25, center_point);
 
-        assert_eq!(108, cube.get_vertices().len());
+        assert_eq!(72, cube.get_vertices().len());
+        assert_eq!(36, cube.get_indices().unwrap().len());
         assert_eq!(ShapeType::Cube, cube.get_type());
     }
+
+    #[test]
+    fn test_sphere_indices_in_vertices_range() {
+        let center = Point {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let radius = Radius {
+            width: 1.0,
+            height: 1.0,
+        };
+        let sphere = Sphere::new(&center, &radius, 8);
+        let vertices_count = u32::try_from(sphere.get_vertices().len() / 3).unwrap();
+
+        assert!(sphere
+            .get_indices()
+            .unwrap()
+            .iter()
+            .all(|index| *index < vertices_count));
+    }
+
+    #[test]
+    fn test_sphere_triangles_face_outwards() {
+        let sphere = Sphere::new(&origin(), &radius_one(), 6);
+        let vertices = sphere.get_vertices();
+        for triangle in sphere.get_indices().unwrap().chunks(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| vertex_at(vertices, i));
+            // The last stack lies on the bottom pole, its triangles have no area
+            let face_normal = (b - a).cross(c - a);
+            if face_normal.magnitude() > 1e-6 {
+                assert!(face_normal.dot((a + b + c) / 3.0) > 0.0);
+            }
+        }
+        assert_eq!(
+            sphere.get_normal_mode(),
+            NormalMode::Smooth {
+                crease_angle: 180.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_cube_set_normal_mode() {
+        let mut cube = Cube::new(1.0, [0.0, 0.0, 0.0]);
+        assert_eq!(cube.get_normal_mode(), NormalMode::Flat);
+
+        cube.set_normal_mode(NormalMode::Winding);
+        assert_eq!(cube.get_normal_mode(), NormalMode::Winding);
+    }
+
+    #[test]
+    fn test_sphere_more_vertices_than_u16() {
+        let center = Point {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let radius = Radius {
+            width: 1.0,
+            height: 1.0,
+        };
+        let sphere = Sphere::new(&center, &radius, 160);
+        let max_index = *sphere.get_indices().unwrap().iter().max().unwrap();
+
+        assert!(max_index > u32::from(u16::MAX));
+        assert_eq!(sphere.get_vertices().len() / 3, max_index as usize + 1);
+    }
 }
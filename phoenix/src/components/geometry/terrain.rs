@@ -0,0 +1,319 @@
+use cgmath::{InnerSpace, Vector3};
+use image::DynamicImage;
+
+use crate::components::{Error, FillMode, Result, Shape, ShapeType};
+
+/// Grid of vertices lifted by the brightness of a grayscale image, lying in the XZ plane
+/// with the middle of the image at the origin. The image is expected to be loaded
+/// by `texture::load`, so its top row lies on the far side (towards negative z),
+/// the same way as the texture made from such an image is mapped.
+pub struct Terrain {
+    vertices: Vec<f32>,
+    normals: Vec<f32>,
+    texture_coords: Vec<f32>,
+    indices: Vec<u32>,
+    mode: FillMode,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// Distance between neighbouring pixels in the x and z directions.
+    pub spacing: f32,
+    /// Height of a white pixel, black pixels are at zero.
+    pub height_scale: f32,
+}
+
+struct Heightmap {
+    heights: Vec<f32>,
+    columns: u32,
+    rows: u32,
+    config: Config,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            spacing: 1.0,
+            height_scale: 1.0,
+        }
+    }
+}
+
+impl Terrain {
+    /// # Errors
+    ///
+    /// Will return `Err` if the image is smaller than 2x2 pixels or the spacing is not positive.
+    pub fn new(image: &DynamicImage, config: &Config) -> Result<Self> {
+        let heightmap = Heightmap::new(image, config)?;
+        Ok(heightmap.build_chunk(0, 0, heightmap.columns - 1, heightmap.rows - 1))
+    }
+
+    /// Splits the terrain into chunks of at most `chunk_size` x `chunk_size` cells,
+    /// so every chunk gets its own small buffers. Chunks share the positions and normals
+    /// on their borders, so they fit together without seams when drawn with the same transformation.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the image is smaller than 2x2 pixels, the spacing is not positive
+    /// or `chunk_size` is zero.
+    pub fn new_chunks(image: &DynamicImage, config: &Config, chunk_size: u32) -> Result<Vec<Self>> {
+        if chunk_size == 0 {
+            return Err(Error::GeometryError(
+                "Terrain chunk size must not be zero".to_string(),
+            ));
+        }
+
+        let heightmap = Heightmap::new(image, config)?;
+        let mut chunks = Vec::new();
+        for first_row in (0..heightmap.rows - 1).step_by(chunk_size as usize) {
+            for first_column in (0..heightmap.columns - 1).step_by(chunk_size as usize) {
+                let last_column = (first_column + chunk_size).min(heightmap.columns - 1);
+                let last_row = (first_row + chunk_size).min(heightmap.rows - 1);
+                chunks.push(heightmap.build_chunk(first_column, first_row, last_column, last_row));
+            }
+        }
+        Ok(chunks)
+    }
+
+    pub fn set_fill_mode(&mut self, mode: FillMode) {
+        self.mode = mode;
+    }
+}
+
+impl Heightmap {
+    fn new(image: &DynamicImage, config: &Config) -> Result<Self> {
+        let (columns, rows) = (image.width(), image.height());
+        if columns < 2 || rows < 2 {
+            return Err(Error::GeometryError(format!(
+                "Terrain needs an image of at least 2x2 pixels, got {columns}x{rows}"
+            )));
+        }
+        if config.spacing <= 0.0 {
+            return Err(Error::GeometryError(format!(
+                "Terrain spacing {} is not positive",
+                config.spacing
+            )));
+        }
+
+        let heights = image
+            .to_luma16()
+            .pixels()
+            .map(|pixel| f32::from(pixel.0[0]) / f32::from(u16::MAX) * config.height_scale)
+            .collect();
+        Ok(Self {
+            heights,
+            columns,
+            rows,
+            config: config.clone(),
+        })
+    }
+
+    /// Height of the pixel, coordinates outside the image are clamped to its edge.
+    fn height(&self, column: i64, row: i64) -> f32 {
+        let column = column.clamp(0, i64::from(self.columns) - 1);
+        let row = row.clamp(0, i64::from(self.rows) - 1);
+        usize::try_from(row * i64::from(self.columns) + column)
+            .ok()
+            .and_then(|index| self.heights.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn position(&self, column: u32, row: u32) -> [f32; 3] {
+        let spacing = self.config.spacing;
+        [
+            (column as f32 - (self.columns - 1) as f32 / 2.0) * spacing,
+            self.height(i64::from(column), i64::from(row)),
+            ((self.rows - 1) as f32 / 2.0 - row as f32) * spacing,
+        ]
+    }
+
+    /// Central differences over the whole image, so normals on chunk borders match.
+    fn normal(&self, column: u32, row: u32) -> [f32; 3] {
+        let (column, row) = (i64::from(column), i64::from(row));
+        let spacing = self.config.spacing;
+        let slope_x =
+            (self.height(column + 1, row) - self.height(column - 1, row)) / (2.0 * spacing);
+        //rows go towards negative z
+        let slope_z =
+            (self.height(column, row - 1) - self.height(column, row + 1)) / (2.0 * spacing);
+        let normal = Vector3::new(-slope_x, 1.0, -slope_z).normalize();
+        [normal.x, normal.y, normal.z]
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn build_chunk(
+        &self,
+        first_column: u32,
+        first_row: u32,
+        last_column: u32,
+        last_row: u32,
+    ) -> Terrain {
+        let vertices_count =
+            ((last_column - first_column + 1) * (last_row - first_row + 1)) as usize;
+        let mut terrain = Terrain {
+            vertices: Vec::with_capacity(vertices_count * 3),
+            normals: Vec::with_capacity(vertices_count * 3),
+            texture_coords: Vec::with_capacity(vertices_count * 2),
+            indices: Vec::new(),
+            mode: FillMode::Solid,
+        };
+
+        for row in first_row..=last_row {
+            for column in first_column..=last_column {
+                terrain
+                    .vertices
+                    .extend_from_slice(&self.position(column, row));
+                terrain.normals.extend_from_slice(&self.normal(column, row));
+                terrain.texture_coords.extend_from_slice(&[
+                    column as f32 / (self.columns - 1) as f32,
+                    row as f32 / (self.rows - 1) as f32,
+                ]);
+            }
+        }
+
+        //counter-clockwise when looking from above
+        let stride = last_column - first_column + 1;
+        for row in 0..last_row - first_row {
+            for column in 0..last_column - first_column {
+                let current = row * stride + column;
+                let next_row = current + stride;
+                terrain.indices.extend_from_slice(&[
+                    current,
+                    current + 1,
+                    next_row + 1,
+                    current,
+                    next_row + 1,
+                    next_row,
+                ]);
+            }
+        }
+        terrain
+    }
+}
+
+impl Shape for Terrain {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Terrain
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        self.mode
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GrayImage, Luma};
+
+    /// Brightness grows with the column, so the terrain rises towards positive x.
+    fn slope_image(columns: u32, rows: u32) -> DynamicImage {
+        DynamicImage::ImageLuma8(GrayImage::from_fn(columns, rows, |column, _| {
+            Luma([u8::try_from(column * 50).unwrap()])
+        }))
+    }
+
+    fn vertex(terrain: &Terrain, index: usize) -> &[f32] {
+        &terrain.get_vertices()[index * 3..index * 3 + 3]
+    }
+
+    #[test]
+    fn test_terrain_grid() {
+        let config = Config {
+            spacing: 2.0,
+            height_scale: 10.0,
+        };
+        let terrain = Terrain::new(&slope_image(3, 2), &config).unwrap();
+
+        assert_eq!(terrain.get_vertices().len(), 6 * 3);
+        assert_eq!(terrain.get_indices().unwrap().len(), 2 * 2 * 3);
+        assert_eq!(terrain.get_type(), ShapeType::Terrain);
+        assert_eq!(vertex(&terrain, 0), &[-2.0, 0.0, 1.0]);
+        let last = vertex(&terrain, 5);
+        assert_eq!([last[0], last[2]], [2.0, -1.0]);
+        assert!((last[1] - 100.0 / 255.0 * 10.0).abs() < 1e-5);
+        assert_eq!(
+            terrain.get_texture_coords().unwrap(),
+            &[0.0, 0.0, 0.5, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5, 1.0, 1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_terrain_normals_and_winding() {
+        let flat = DynamicImage::ImageLuma8(GrayImage::from_pixel(3, 3, Luma([128])));
+        let terrain = Terrain::new(&flat, &Config::default()).unwrap();
+        for normal in terrain.get_normals().unwrap().chunks(3) {
+            assert_eq!(normal, [0.0, 1.0, 0.0]);
+        }
+
+        //the slope rises towards positive x, so normals lean towards negative x
+        let terrain = Terrain::new(&slope_image(3, 3), &Config::default()).unwrap();
+        for normal in terrain.get_normals().unwrap().chunks(3) {
+            assert!(normal[0] < 0.0 && normal[1] > 0.0);
+            assert!(normal[2].abs() < 1e-6);
+        }
+
+        for triangle in terrain.get_indices().unwrap().chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|corner| {
+                let point = vertex(&terrain, triangle[corner] as usize);
+                Vector3::new(point[0], point[1], point[2])
+            });
+            assert!((b - a).cross(c - a).y > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_terrain_chunks() {
+        let image = slope_image(5, 4);
+        let whole = Terrain::new(&image, &Config::default()).unwrap();
+        let chunks = Terrain::new_chunks(&image, &Config::default(), 2).unwrap();
+
+        //4x3 cells split into 2x2 chunks: two columns and two rows of chunks
+        assert_eq!(chunks.len(), 4);
+        let cells: usize = chunks
+            .iter()
+            .map(|chunk| chunk.get_indices().unwrap().len() / 6)
+            .sum();
+        assert_eq!(cells, whole.get_indices().unwrap().len() / 6);
+
+        //the first chunk covers 3x3 vertices, its last one is shared with the last chunk
+        assert_eq!(chunks[0].get_vertices().len(), 9 * 3);
+        assert_eq!(vertex(&chunks[0], 8), vertex(&chunks[3], 0));
+        assert_eq!(
+            chunks[0].get_normals().unwrap()[8 * 3..],
+            chunks[3].get_normals().unwrap()[..3]
+        );
+    }
+
+    #[test]
+    fn test_terrain_invalid_get_error() {
+        let image = slope_image(1, 4);
+        assert!(Terrain::new(&image, &Config::default()).is_err());
+
+        let image = slope_image(3, 3);
+        let config = Config {
+            spacing: 0.0,
+            height_scale: 1.0,
+        };
+        assert!(Terrain::new(&image, &config).is_err());
+        assert!(Terrain::new_chunks(&image, &Config::default(), 0).is_err());
+    }
+}
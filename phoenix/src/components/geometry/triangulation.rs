@@ -0,0 +1,454 @@
+use crate::components::{Error, Result};
+
+const EPSILON: f32 = 1e-7;
+
+type Point2 = [f32; 2];
+
+/// Splits a polygon with holes into counter-clockwise triangles by ear clipping.
+/// The returned indices point into the outer contour followed by all holes, in the given order.
+/// Holes are joined to the outer contour by bridges, so every hole becomes part of one polygon.
+///
+/// # Errors
+///
+/// Will return `Err` if a contour is degenerate, contours intersect each other or themselves,
+/// or a hole lies outside the outer contour.
+pub fn triangulate(outer: &[Point2], holes: &[Vec<Point2>]) -> Result<Vec<u32>> {
+    let mut points: Vec<Point2> = outer.to_vec();
+    let mut contours = vec![contour_indices(0, outer.len())?];
+    for hole in holes {
+        contours.push(contour_indices(points.len(), hole.len())?);
+        points.extend_from_slice(hole);
+    }
+
+    check_intersections(&points, &contours)?;
+    for contour in &contours {
+        if signed_area(&points, contour).abs() < EPSILON {
+            return Err(Error::GeometryError(
+                "Polygon contour is degenerate, its area is zero".to_string(),
+            ));
+        }
+    }
+    check_holes_inside(&points, &contours)?;
+
+    //outer contour counter-clockwise, holes clockwise
+    for (i, contour) in contours.iter_mut().enumerate() {
+        let is_ccw = signed_area(&points, contour) > 0.0;
+        if is_ccw == (i != 0) {
+            contour.reverse();
+        }
+    }
+
+    let mut polygon = contours.remove(0);
+    contours.sort_by(|a, b| max_x(&points, b).total_cmp(&max_x(&points, a)));
+    for (i, hole) in contours.iter().enumerate() {
+        polygon = bridge_hole(&points, &polygon, hole, &contours[i + 1..])?;
+    }
+
+    clip_ears(&points, polygon)
+}
+
+fn contour_indices(first: usize, len: usize) -> Result<Vec<u32>> {
+    if len < 3 {
+        return Err(Error::GeometryError(format!(
+            "Polygon contour needs at least 3 points, got {len}"
+        )));
+    }
+
+    let first = u32::try_from(first)
+        .map_err(|_| Error::GeometryError("Too many polygon points".to_string()))?;
+    let last = u32::try_from(len)
+        .ok()
+        .and_then(|len| first.checked_add(len))
+        .ok_or_else(|| Error::GeometryError("Too many polygon points".to_string()))?;
+    Ok((first..last).collect())
+}
+
+fn point(points: &[Point2], index: u32) -> Point2 {
+    points[index as usize]
+}
+
+fn signed_area(points: &[Point2], contour: &[u32]) -> f32 {
+    let mut area = 0.0;
+    for (i, current) in contour.iter().enumerate() {
+        let [x1, y1] = point(points, *current);
+        let [x2, y2] = point(points, contour[(i + 1) % contour.len()]);
+        area += x1 * y2 - x2 * y1;
+    }
+    area / 2.0
+}
+
+fn max_x(points: &[Point2], contour: &[u32]) -> f32 {
+    contour
+        .iter()
+        .map(|index| point(points, *index)[0])
+        .fold(f32::MIN, f32::max)
+}
+
+fn cross(origin: Point2, a: Point2, b: Point2) -> f32 {
+    (a[0] - origin[0]) * (b[1] - origin[1]) - (a[1] - origin[1]) * (b[0] - origin[0])
+}
+
+fn is_on_segment(a: Point2, b: Point2, p: Point2) -> bool {
+    cross(a, b, p).abs() <= EPSILON
+        && p[0] >= a[0].min(b[0]) - EPSILON
+        && p[0] <= a[0].max(b[0]) + EPSILON
+        && p[1] >= a[1].min(b[1]) - EPSILON
+        && p[1] <= a[1].max(b[1]) + EPSILON
+}
+
+/// True when the segments share any point, touching included.
+fn segments_intersect(first: [Point2; 2], second: [Point2; 2]) -> bool {
+    let [first_start, first_end] = first;
+    let [second_start, second_end] = second;
+    let is_crossing = |(start_side, end_side): (f32, f32)| {
+        (start_side > EPSILON && end_side < -EPSILON)
+            || (start_side < -EPSILON && end_side > EPSILON)
+    };
+
+    let first_sides = (
+        cross(second_start, second_end, first_start),
+        cross(second_start, second_end, first_end),
+    );
+    let second_sides = (
+        cross(first_start, first_end, second_start),
+        cross(first_start, first_end, second_end),
+    );
+    if is_crossing(first_sides) && is_crossing(second_sides) {
+        return true;
+    }
+
+    is_on_segment(second_start, second_end, first_start)
+        || is_on_segment(second_start, second_end, first_end)
+        || is_on_segment(first_start, first_end, second_start)
+        || is_on_segment(first_start, first_end, second_end)
+}
+
+fn edges(contour: &[u32]) -> impl Iterator<Item = (u32, u32)> + '_ {
+    contour
+        .iter()
+        .enumerate()
+        .map(|(i, start)| (*start, contour[(i + 1) % contour.len()]))
+}
+
+fn check_intersections(points: &[Point2], contours: &[Vec<u32>]) -> Result<()> {
+    let all_edges: Vec<(u32, u32)> = contours.iter().flat_map(|contour| edges(contour)).collect();
+    for (i, (a, b)) in all_edges.iter().enumerate() {
+        for (c, d) in &all_edges[i + 1..] {
+            let shared = if b == c {
+                Some((*b, *a, *d))
+            } else if a == d {
+                Some((*a, *b, *c))
+            } else {
+                None
+            };
+
+            let intersects = match shared {
+                //neighbours always share one point, they must not fold back onto each other
+                Some((shared, first, second)) => {
+                    let (shared, first, second) = (
+                        point(points, shared),
+                        point(points, first),
+                        point(points, second),
+                    );
+                    is_on_segment(shared, first, second) || is_on_segment(shared, second, first)
+                }
+                None => segments_intersect(
+                    [point(points, *a), point(points, *b)],
+                    [point(points, *c), point(points, *d)],
+                ),
+            };
+
+            if intersects {
+                return Err(Error::GeometryError(
+                    "Polygon contours intersect themselves or each other".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_inside_contour(points: &[Point2], contour: &[u32], p: Point2) -> bool {
+    let mut inside = false;
+    for (start, end) in edges(contour) {
+        let [x1, y1] = point(points, start);
+        let [x2, y2] = point(points, end);
+        if (y1 > p[1]) != (y2 > p[1]) && p[0] < (x2 - x1) * (p[1] - y1) / (y2 - y1) + x1 {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+fn check_holes_inside(points: &[Point2], contours: &[Vec<u32>]) -> Result<()> {
+    for (i, hole) in contours.iter().enumerate().skip(1) {
+        let first = point(points, hole[0]);
+        let is_nested = contours
+            .iter()
+            .enumerate()
+            .skip(1)
+            .any(|(j, other)| i != j && is_inside_contour(points, other, first));
+        if !is_inside_contour(points, &contours[0], first) || is_nested {
+            return Err(Error::GeometryError(
+                "Polygon hole must lie inside the outer contour and outside other holes"
+                    .to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Connects the rightmost hole point with the closest visible polygon point,
+/// walking around the hole and coming back along the same bridge.
+fn bridge_hole(
+    points: &[Point2],
+    polygon: &[u32],
+    hole: &[u32],
+    remaining_holes: &[Vec<u32>],
+) -> Result<Vec<u32>> {
+    let (hole_start, hole_point) = hole
+        .iter()
+        .enumerate()
+        .max_by(|(_, a), (_, b)| point(points, **a)[0].total_cmp(&point(points, **b)[0]))
+        .map_or((0, [0.0, 0.0]), |(i, index)| (i, point(points, *index)));
+
+    let distance = |index: u32| {
+        let [x, y] = point(points, index);
+        (x - hole_point[0]).powi(2) + (y - hole_point[1]).powi(2)
+    };
+    let mut candidates: Vec<usize> = (0..polygon.len()).collect();
+    candidates.sort_by(|a, b| distance(polygon[*a]).total_cmp(&distance(polygon[*b])));
+
+    let blocking_edges: Vec<(u32, u32)> = edges(polygon)
+        .chain(edges(hole))
+        .chain(remaining_holes.iter().flat_map(|other| edges(other)))
+        .collect();
+
+    let hole_index = hole[hole_start];
+    let visible = candidates.into_iter().find(|candidate| {
+        let target_index = polygon[*candidate];
+        let bridge = [hole_point, point(points, target_index)];
+        blocking_edges.iter().all(|(start, end)| {
+            let touches_bridge_end = [*start, *end]
+                .iter()
+                .any(|index| *index == target_index || *index == hole_index);
+            touches_bridge_end
+                || !segments_intersect(bridge, [point(points, *start), point(points, *end)])
+        }) && is_inside_corner(points, polygon, *candidate, hole_point)
+    });
+
+    let Some(bridge) = visible else {
+        return Err(Error::GeometryError(
+            "Polygon hole could not be connected to the outer contour".to_string(),
+        ));
+    };
+
+    let mut result = Vec::with_capacity(polygon.len() + hole.len() + 2);
+    result.extend_from_slice(&polygon[..=bridge]);
+    result.extend_from_slice(&hole[hole_start..]);
+    result.extend_from_slice(&hole[..=hole_start]);
+    result.extend_from_slice(&polygon[bridge..]);
+    Ok(result)
+}
+
+/// The bridge has to leave the polygon vertex into the polygon interior.
+fn is_inside_corner(points: &[Point2], polygon: &[u32], i: usize, target: Point2) -> bool {
+    let previous = point(points, polygon[(i + polygon.len() - 1) % polygon.len()]);
+    let current = point(points, polygon[i]);
+    let next = point(points, polygon[(i + 1) % polygon.len()]);
+
+    if cross(previous, current, next) >= 0.0 {
+        cross(current, next, target) >= 0.0 && cross(previous, current, target) >= 0.0
+    } else {
+        cross(current, next, target) >= 0.0 || cross(previous, current, target) >= 0.0
+    }
+}
+
+fn clip_ears(points: &[Point2], mut polygon: Vec<u32>) -> Result<Vec<u32>> {
+    let mut result = Vec::with_capacity((polygon.len() - 2) * 3);
+    while polygon.len() > 3 {
+        let len = polygon.len();
+        let neighbours = |i: usize| {
+            (
+                polygon[(i + len - 1) % len],
+                polygon[i],
+                polygon[(i + 1) % len],
+            )
+        };
+
+        if let Some(ear) = (0..len).find(|i| is_ear(points, &polygon, *i)) {
+            let (previous, current, next) = neighbours(ear);
+            result.extend_from_slice(&[previous, current, next]);
+            polygon.remove(ear);
+            continue;
+        }
+
+        //collinear points do not form ears, they can be dropped without losing any area
+        let collinear = (0..len).find(|i| {
+            let (previous, current, next) = neighbours(*i);
+            cross(
+                point(points, previous),
+                point(points, current),
+                point(points, next),
+            )
+            .abs()
+                <= EPSILON
+        });
+        match collinear {
+            Some(index) => {
+                polygon.remove(index);
+            }
+            None => {
+                return Err(Error::GeometryError(
+                    "Polygon could not be triangulated".to_string(),
+                ))
+            }
+        }
+    }
+
+    let (a, b, c) = (polygon[0], polygon[1], polygon[2]);
+    if cross(point(points, a), point(points, b), point(points, c)) > EPSILON {
+        result.extend_from_slice(&[a, b, c]);
+    }
+    Ok(result)
+}
+
+fn is_ear(points: &[Point2], polygon: &[u32], i: usize) -> bool {
+    let len = polygon.len();
+    let corners = [
+        polygon[(i + len - 1) % len],
+        polygon[i],
+        polygon[(i + 1) % len],
+    ];
+    let [a, b, c] = corners.map(|index| point(points, index));
+    if cross(a, b, c) <= EPSILON {
+        return false;
+    }
+
+    //bridges visit the same points twice, those are the ear corners themselves
+    polygon
+        .iter()
+        .all(|index| corners.contains(index) || !is_in_triangle(a, b, c, point(points, *index)))
+}
+
+fn is_in_triangle(a: Point2, b: Point2, c: Point2, p: Point2) -> bool {
+    cross(a, b, p) >= -EPSILON && cross(b, c, p) >= -EPSILON && cross(c, a, p) >= -EPSILON
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn triangles_area(points: &[Point2], indices: &[u32]) -> f32 {
+        indices
+            .chunks(3)
+            .map(|triangle| {
+                let area = cross(
+                    point(points, triangle[0]),
+                    point(points, triangle[1]),
+                    point(points, triangle[2]),
+                ) / 2.0;
+                assert!(area > 0.0);
+                area
+            })
+            .sum()
+    }
+
+    fn square(center: Point2, half: f32) -> Vec<Point2> {
+        let [x, y] = center;
+        vec![
+            [x - half, y - half],
+            [x + half, y - half],
+            [x + half, y + half],
+            [x - half, y + half],
+        ]
+    }
+
+    #[test]
+    fn test_triangulate_square() {
+        let outer = square([0.0, 0.0], 1.0);
+        let indices = triangulate(&outer, &[]).unwrap();
+
+        assert_eq!(indices.len(), 6);
+        assert!((triangles_area(&outer, &indices) - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_triangulate_concave_clockwise_polygon() {
+        // L shape given clockwise
+        let outer = vec![
+            [0.0, 0.0],
+            [0.0, 2.0],
+            [1.0, 2.0],
+            [1.0, 1.0],
+            [2.0, 1.0],
+            [2.0, 0.0],
+        ];
+        let indices = triangulate(&outer, &[]).unwrap();
+
+        assert_eq!(indices.len(), (outer.len() - 2) * 3);
+        assert!((triangles_area(&outer, &indices) - 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_triangulate_collinear_points() {
+        let outer = vec![[0.0, 0.0], [1.0, 0.0], [2.0, 0.0], [2.0, 2.0], [0.0, 2.0]];
+        let indices = triangulate(&outer, &[]).unwrap();
+        assert!((triangles_area(&outer, &indices) - 4.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_triangulate_with_holes() {
+        let outer = square([0.0, 0.0], 4.0);
+        let holes = vec![square([-2.0, 0.0], 1.0), square([2.0, 1.0], 1.0)];
+        let indices = triangulate(&outer, &holes).unwrap();
+
+        let mut points = outer.clone();
+        for hole in &holes {
+            points.extend_from_slice(hole);
+        }
+        assert!(indices.iter().all(|index| (*index as usize) < points.len()));
+        assert!((triangles_area(&points, &indices) - (64.0 - 8.0)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_triangulate_star_with_round_hole() {
+        let contour = |count: u16, radius: &dyn Fn(u16) -> f32| -> Vec<Point2> {
+            (0..count)
+                .map(|i| {
+                    let angle = std::f32::consts::TAU * f32::from(i) / f32::from(count);
+                    [radius(i) * angle.cos(), radius(i) * angle.sin()]
+                })
+                .collect()
+        };
+        let outer = contour(100, &|i| if i % 2 == 0 { 1.0 } else { 0.5 });
+        let hole = contour(50, &|_| 0.2);
+        let indices = triangulate(&outer, std::slice::from_ref(&hole)).unwrap();
+
+        let mut points = outer.clone();
+        points.extend_from_slice(&hole);
+        let outer_area = signed_area(&outer, &(0..100).collect::<Vec<u32>>());
+        let hole_area = signed_area(&hole, &(0..50).collect::<Vec<u32>>());
+        assert!((triangles_area(&points, &indices) - (outer_area - hole_area)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_triangulate_self_intersecting_get_error() {
+        let bow_tie = vec![[0.0, 0.0], [1.0, 1.0], [1.0, 0.0], [0.0, 1.0]];
+        let error = triangulate(&bow_tie, &[]).unwrap_err();
+        assert!(error.to_string().contains("intersect"));
+    }
+
+    #[test]
+    fn test_triangulate_degenerate_get_error() {
+        assert!(triangulate(&[[0.0, 0.0], [1.0, 1.0]], &[]).is_err());
+        assert!(triangulate(&[[0.0, 0.0], [1.0, 1.0], [2.0, 2.0]], &[]).is_err());
+    }
+
+    #[test]
+    fn test_triangulate_hole_outside_or_crossing_get_error() {
+        let outer = square([0.0, 0.0], 1.0);
+        assert!(triangulate(&outer, &[square([5.0, 5.0], 0.5)]).is_err());
+        assert!(triangulate(&outer, &[square([1.0, 0.0], 0.5)]).is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use super::color::RGBA;
+use super::transformer::Transformer;
+use super::{Error, Result};
+
+/// Copies of the entity geometry drawn in one call, each one moved by its own transformer.
+/// The entity transformer is applied on top of every instance one.
+pub struct Instances {
+    transformers: Vec<Transformer>,
+    /// Multiplied with the entity color, so a white entity takes them as they are.
+    colors: Option<Vec<RGBA>>,
+}
+
+impl Instances {
+    #[must_use]
+    pub fn new(transformers: Vec<Transformer>) -> Self {
+        Self {
+            transformers,
+            colors: None,
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if there is not exactly one color for every transformer.
+    pub fn new_with_colors(transformers: Vec<Transformer>, colors: Vec<RGBA>) -> Result<Self> {
+        if transformers.len() != colors.len() {
+            return Err(Error::InstancesError(format!(
+                "{} colors for {} instances",
+                colors.len(),
+                transformers.len()
+            )));
+        }
+
+        Ok(Self {
+            transformers,
+            colors: Some(colors),
+        })
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.transformers.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.transformers.is_empty()
+    }
+
+    #[must_use]
+    pub fn get_transformers(&self) -> &[Transformer] {
+        &self.transformers
+    }
+
+    #[must_use]
+    pub fn get_colors(&self) -> Option<&[RGBA]> {
+        self.colors.as_deref()
+    }
+
+    /// Instance matrices one after another, 16 floats in column order each.
+    #[must_use]
+    pub fn unpack_matrices(&self) -> Vec<f32> {
+        self.transformers
+            .iter()
+            .flat_map(|transformer| *AsRef::<[f32; 16]>::as_ref(&transformer.get_matrix()))
+            .collect()
+    }
+
+    /// Normalized RGBA of every instance, `None` without instance colors.
+    #[must_use]
+    pub fn unpack_colors(&self) -> Option<Vec<f32>> {
+        self.colors.as_ref().map(|colors| {
+            colors
+                .iter()
+                .flat_map(RGBA::get_as_normalized_f32)
+                .collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::Vector3;
+
+    fn create_transformers() -> Vec<Transformer> {
+        vec![
+            Transformer::new_translate(Vector3::new(1.0, 2.0, 3.0)),
+            Transformer::new_scale(Vector3::new(2.0, 2.0, 2.0)),
+        ]
+    }
+
+    #[test]
+    fn test_instances_unpack() {
+        let instances = Instances::new_with_colors(
+            create_transformers(),
+            vec![RGBA::new(255, 0, 0, 1.0), RGBA::new(0, 0, 255, 0.5)],
+        )
+        .unwrap();
+
+        let matrices = instances.unpack_matrices();
+        assert_eq!(instances.len(), 2);
+        assert_eq!(matrices.len(), 32);
+        //the translation is in the last column of the first matrix
+        assert_eq!(matrices[12..16], [1.0, 2.0, 3.0, 1.0]);
+        assert_eq!(matrices[16..20], [2.0, 0.0, 0.0, 0.0]);
+        assert_eq!(
+            instances.unpack_colors().unwrap(),
+            [1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5]
+        );
+    }
+
+    #[test]
+    fn test_instances_without_colors() {
+        let instances = Instances::new(create_transformers());
+
+        assert!(instances.get_colors().is_none());
+        assert!(instances.unpack_colors().is_none());
+        assert!(Instances::new(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_instances_colors_count_get_error() {
+        assert!(Instances::new_with_colors(create_transformers(), vec![RGBA::default()]).is_err());
+    }
+}
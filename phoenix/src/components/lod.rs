@@ -0,0 +1,267 @@
+use super::{Error, Result, Shape};
+use crate::entities::entity::ID;
+
+/// Smallest detail made by `Builder::with_halved_detail`, enough to close a circle.
+const MIN_DETAIL: u16 = 3;
+
+/// Coarser variants of the entity geometry, each one used from its distance to the camera.
+/// The `Geometry` component of the entity is the most detailed variant, used up close.
+pub struct Lod {
+    levels: Vec<Level>,
+    hysteresis: f32,
+    current: usize,
+}
+
+struct Level {
+    shape: Box<dyn Shape>,
+    distance: f32,
+    /// Assigned by the entity manager, the renderer keeps separate buffers for every level.
+    render_id: ID,
+}
+
+pub struct Builder {
+    levels: Vec<(Box<dyn Shape>, f32)>,
+    hysteresis: f32,
+}
+
+impl Builder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            levels: Vec::new(),
+            hysteresis: 0.1,
+        }
+    }
+
+    /// Adds a variant used from `distance` on, the distances must increase with every level.
+    #[must_use]
+    pub fn with_level(mut self, shape: Box<dyn Shape>, distance: f32) -> Self {
+        self.levels.push((shape, distance));
+        self
+    }
+
+    /// Adds a level for every distance, made by `make` from `detail` halved at each one.
+    /// Fits parametric shapes, for example the precision of a `Sphere` or the segments of a `Cylinder`.
+    #[must_use]
+    pub fn with_halved_detail<S: Shape + 'static>(
+        mut self,
+        detail: u16,
+        distances: &[f32],
+        make: impl Fn(u16) -> S,
+    ) -> Self {
+        let mut detail = detail;
+        for distance in distances {
+            detail = (detail / 2).max(MIN_DETAIL);
+            self.levels.push((Box::new(make(detail)), *distance));
+        }
+        self
+    }
+
+    /// Relative width of the band around every distance in which the level does not change,
+    /// 0.1 means switching to the coarser level 10% beyond the distance and back 10% before it.
+    #[must_use]
+    pub fn with_hysteresis(mut self, hysteresis: f32) -> Self {
+        self.hysteresis = hysteresis;
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the distances are not positive and increasing
+    /// or the hysteresis is outside of the range from 0 to 1.
+    pub fn build(self) -> Result<Lod> {
+        if !(0.0..1.0).contains(&self.hysteresis) {
+            return Err(Error::GeometryError(format!(
+                "LOD hysteresis {} is outside of the range from 0 to 1",
+                self.hysteresis
+            )));
+        }
+
+        let mut previous = 0.0;
+        for (_, distance) in &self.levels {
+            if *distance <= previous {
+                return Err(Error::GeometryError(format!(
+                    "LOD distance {distance} does not increase"
+                )));
+            }
+            previous = *distance;
+        }
+
+        Ok(Lod {
+            levels: self
+                .levels
+                .into_iter()
+                .map(|(shape, distance)| Level {
+                    shape,
+                    distance,
+                    render_id: 0,
+                })
+                .collect(),
+            hysteresis: self.hysteresis,
+            current: 0,
+        })
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lod {
+    /// Number of the coarser levels, without the entity geometry.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.levels.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.levels.is_empty()
+    }
+
+    /// Current level, 0 is the entity geometry.
+    #[must_use]
+    pub fn get_current_level(&self) -> usize {
+        self.current
+    }
+
+    /// Level for the distance, starting from the `current` one. A coarser level is chosen
+    /// when the distance goes past its threshold by the hysteresis, a finer one when it comes
+    /// back below its threshold by the hysteresis, so small moves around a threshold do not pop.
+    #[must_use]
+    pub fn select_level(&self, current: usize, distance: f32) -> usize {
+        let mut level = current.min(self.levels.len());
+        while level < self.levels.len()
+            && distance > self.levels[level].distance * (1.0 + self.hysteresis)
+        {
+            level += 1;
+        }
+        while level > 0 && distance < self.levels[level - 1].distance * (1.0 - self.hysteresis) {
+            level -= 1;
+        }
+        level
+    }
+
+    /// Geometry and renderer ID of the current level, `None` for the entity geometry.
+    pub(crate) fn get_current(&self) -> Option<(&dyn Shape, ID)> {
+        let level = self.levels.get(self.current.checked_sub(1)?)?;
+        Some((level.shape.as_ref(), level.render_id))
+    }
+
+    pub(crate) fn update(&mut self, distance: f32) {
+        self.current = self.select_level(self.current, distance);
+    }
+
+    pub(crate) fn assign_render_ids(&mut self, mut create_id: impl FnMut() -> ID) {
+        for level in &mut self.levels {
+            level.render_id = create_id();
+        }
+    }
+
+    pub(crate) fn get_render_ids(&self) -> impl Iterator<Item = ID> + '_ {
+        self.levels.iter().map(|level| level.render_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::geometry::plane::Triangle;
+    use crate::components::geometry::solid::Sphere;
+    use crate::components::geometry::{Point, Radius};
+
+    fn triangle() -> Box<dyn Shape> {
+        Box::new(Triangle::new([0.0; 9]))
+    }
+
+    fn create_lod() -> Lod {
+        Builder::new()
+            .with_level(triangle(), 10.0)
+            .with_level(triangle(), 20.0)
+            .with_hysteresis(0.1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_lod_select_level() {
+        let lod = create_lod();
+
+        assert_eq!(lod.select_level(0, 5.0), 0);
+        assert_eq!(lod.select_level(0, 15.0), 1);
+        assert_eq!(lod.select_level(0, 50.0), 2);
+        assert_eq!(lod.select_level(2, 1.0), 0);
+        assert_eq!(lod.select_level(5, 50.0), 2);
+    }
+
+    #[test]
+    fn test_lod_hysteresis() {
+        let mut lod = create_lod();
+
+        //inside the band around 10 the level stays the same in both directions
+        lod.update(10.5);
+        assert_eq!(lod.get_current_level(), 0);
+        lod.update(11.5);
+        assert_eq!(lod.get_current_level(), 1);
+        lod.update(9.5);
+        assert_eq!(lod.get_current_level(), 1);
+        lod.update(8.5);
+        assert_eq!(lod.get_current_level(), 0);
+        assert!(lod.get_current().is_none());
+    }
+
+    #[test]
+    fn test_lod_halved_detail() {
+        let center = Point {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+        let radius = Radius {
+            width: 1.0,
+            height: 1.0,
+        };
+        let mut lod = Builder::new()
+            .with_halved_detail(16, &[10.0, 20.0, 40.0, 80.0], |precision| {
+                Sphere::new(&center, &radius, precision)
+            })
+            .build()
+            .unwrap();
+        let mut next_id = 100;
+        lod.assign_render_ids(|| {
+            next_id += 1;
+            next_id
+        });
+        assert_eq!(lod.len(), 4);
+        assert_eq!(
+            lod.get_render_ids().collect::<Vec<_>>(),
+            [101, 102, 103, 104]
+        );
+
+        let mut vertices = Sphere::new(&center, &radius, 16).get_vertices().len();
+        for (level, distance) in [(1, 15.0), (2, 30.0), (3, 60.0)] {
+            lod.update(distance);
+            let (shape, render_id) = lod.get_current().unwrap();
+            assert!(shape.get_vertices().len() < vertices);
+            assert_eq!(render_id, 100 + level);
+            vertices = shape.get_vertices().len();
+        }
+
+        //the detail does not go below the minimum
+        lod.update(200.0);
+        assert_eq!(lod.get_current().unwrap().0.get_vertices().len(), vertices);
+    }
+
+    #[test]
+    fn test_lod_invalid_get_error() {
+        assert!(Builder::new()
+            .with_level(triangle(), 20.0)
+            .with_level(triangle(), 10.0)
+            .build()
+            .is_err());
+        assert!(Builder::new().with_level(triangle(), 0.0).build().is_err());
+        assert!(Builder::new().with_hysteresis(1.0).build().is_err());
+    }
+}
@@ -1,5 +1,6 @@
 use cgmath::Vector3;
 
+#[derive(Clone, Debug, PartialEq)]
 pub struct Material {
     pub ambient: Vector3<f32>,
     pub diffuse: Vector3<f32>,
@@ -0,0 +1,353 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::rc::Rc;
+
+use cgmath::{Matrix4, SquareMatrix, Vector3};
+use gltf::image::Format;
+use gltf::mesh::Mode;
+use gltf::texture::{MagFilter, MinFilter, WrappingMode};
+use gltf::{Node, Primitive};
+use image::{DynamicImage, GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+
+use crate::components::color::{Color, RGBA};
+use crate::components::geometry::mesh::{Builder, Mesh};
+use crate::components::material::Material;
+use crate::components::texture::{
+    ColorSpace, Config, Filtering, MinFiltering, Mipmaps, Texture, Wrapping,
+};
+use crate::components::transformer::Transformer;
+use crate::components::{Component, Error, FillMode, Result, Shape};
+use crate::entities::entity::Entity;
+
+struct Model {
+    document: gltf::Document,
+    buffers: Vec<gltf::buffer::Data>,
+    images: Vec<gltf::image::Data>,
+}
+
+/// Loads a glTF 2.0 scene (.gltf with embedded or external data, or binary .glb).
+/// Every mesh primitive of the default scene becomes one entity with its node world transformation.
+///
+/// # Errors
+///
+/// Will return `Err` if the file could not be loaded or it uses features which are not supported,
+/// like required extensions or 16-bit images.
+pub fn load(path: &Path) -> Result<Vec<Entity>> {
+    //the validation rejects unknown required extensions, so they are checked before it
+    let reader = BufReader::new(File::open(path)?);
+    let gltf::Gltf { document, blob } = gltf::Gltf::from_reader_without_validation(reader)?;
+    if let Some(extension) = document.extensions_required().next() {
+        return Err(Error::UnsupportedModelError(format!(
+            "required extension {extension}"
+        )));
+    }
+    let document = gltf::Document::from_json(document.into_json())?;
+    let base = path.parent().unwrap_or_else(|| Path::new("./"));
+    let buffers = gltf::import_buffers(&document, Some(base), blob)?;
+    let images = gltf::import_images(&document, Some(base), &buffers)?;
+
+    let gltf = Model {
+        document,
+        buffers,
+        images,
+    };
+
+    let mut result = Vec::new();
+    let scene = gltf
+        .document
+        .default_scene()
+        .or_else(|| gltf.document.scenes().next());
+    if let Some(scene) = scene {
+        for node in scene.nodes() {
+            gltf.load_node(&node, Matrix4::identity(), &mut result)?;
+        }
+    }
+
+    Ok(result)
+}
+
+impl Model {
+    fn load_node(
+        &self,
+        node: &Node,
+        parent_matrix: Matrix4<f32>,
+        result: &mut Vec<Entity>,
+    ) -> Result<()> {
+        let matrix = parent_matrix * Matrix4::from(node.transform().matrix());
+        if let Some(mesh) = node.mesh() {
+            for primitive in mesh.primitives() {
+                result.push(self.create_entity(&primitive, matrix)?);
+            }
+        }
+
+        for child in node.children() {
+            self.load_node(&child, matrix, result)?;
+        }
+
+        Ok(())
+    }
+
+    fn create_entity(&self, primitive: &Primitive, matrix: Matrix4<f32>) -> Result<Entity> {
+        let gltf_material = primitive.material();
+        let pbr = gltf_material.pbr_metallic_roughness();
+        let texture_info = pbr.base_color_texture();
+
+        let mesh = self.create_mesh(
+            primitive,
+            texture_info
+                .as_ref()
+                .map_or(0, gltf::texture::Info::tex_coord),
+        )?;
+        let has_vertex_colors = mesh.get_colors().is_some();
+
+        let [red, green, blue, alpha] = pbr.base_color_factor();
+        let diffuse = Vector3::new(red, green, blue);
+        let material = Material {
+            ambient: diffuse * 0.2,
+            diffuse,
+            //rough surfaces get a weak and wide specular highlight
+            specular: Vector3::from([1.0 - pbr.roughness_factor(); 3]) * 0.5,
+            ..Default::default()
+        };
+
+        let mut entity = Entity::new(vec![
+            Component::Geometry(Box::new(mesh)),
+            Component::Transformer(Transformer::new_from_matrix(matrix)),
+            Component::Material(material),
+        ]);
+
+        if let Some(info) = texture_info {
+            entity.add_component(Component::Texture(self.create_texture(&info.texture())?));
+        } else if !has_vertex_colors {
+            let (red, green, blue, _) = RGBA::from(diffuse).get_rgba();
+            entity.add_component(Component::Color(Color::new(red, green, blue, alpha)));
+        }
+
+        Ok(entity)
+    }
+
+    fn create_mesh(&self, primitive: &Primitive, tex_coord_set: u32) -> Result<Mesh> {
+        let mode = match primitive.mode() {
+            Mode::Points => FillMode::Points { size: 1.0 },
+            Mode::Lines => FillMode::Lines,
+            Mode::LineLoop => FillMode::LineLoop,
+            Mode::LineStrip => FillMode::LineStrip,
+            Mode::Triangles => FillMode::Solid,
+            Mode::TriangleStrip => FillMode::TriangleStrip,
+            Mode::TriangleFan => FillMode::Fan,
+        };
+
+        let reader = primitive.reader(|buffer| {
+            self.buffers
+                .get(buffer.index())
+                .map(|data| data.0.as_slice())
+        });
+
+        let positions = reader
+            .read_positions()
+            .ok_or_else(|| Error::GeometryError("glTF primitive without positions".to_string()))?
+            .flatten()
+            .collect();
+
+        let mut builder = Builder::new(positions).with_fill_mode(mode);
+        if let Some(normals) = reader.read_normals() {
+            builder = builder.with_normals(normals.flatten().collect());
+        }
+        if let Some(texture_coords) = reader.read_tex_coords(tex_coord_set) {
+            builder = builder.with_texture_coords(texture_coords.into_f32().flatten().collect());
+        }
+        if let Some(colors) = reader.read_colors(0) {
+            builder = builder.with_colors(colors.into_rgba_f32().flatten().collect());
+        }
+        if let Some(indices) = reader.read_indices() {
+            builder = builder.with_indices(indices.into_u32().collect());
+        }
+
+        builder.build()
+    }
+
+    fn create_texture(&self, texture: &gltf::Texture) -> Result<Texture> {
+        let data = self
+            .images
+            .get(texture.source().index())
+            .ok_or_else(|| Error::UnsupportedModelError("missing image data".to_string()))?;
+
+        //glTF images start from the top row, which matches its texture coordinates, no flip needed
+        let image = match data.format {
+            Format::R8G8B8 => RgbImage::from_raw(data.width, data.height, data.pixels.clone())
+                .map(DynamicImage::ImageRgb8),
+            Format::R8G8B8A8 => RgbaImage::from_raw(data.width, data.height, data.pixels.clone())
+                .map(DynamicImage::ImageRgba8),
+            Format::R8 => GrayImage::from_raw(data.width, data.height, data.pixels.clone())
+                .map(|image| DynamicImage::ImageRgb8(DynamicImage::ImageLuma8(image).to_rgb8())),
+            Format::R8G8 => GrayAlphaImage::from_raw(data.width, data.height, data.pixels.clone())
+                .map(|image| DynamicImage::ImageRgba8(DynamicImage::ImageLumaA8(image).to_rgba8())),
+            format => {
+                return Err(Error::UnsupportedModelError(format!(
+                    "image format {format:?}"
+                )))
+            }
+        }
+        .ok_or_else(|| Error::UnsupportedModelError("corrupted image data".to_string()))?;
+
+        Ok(Texture::new(
+            Rc::new(image),
+            create_config(&texture.sampler()),
+        ))
+    }
+}
+
+fn create_config(sampler: &gltf::texture::Sampler) -> Config {
+    let default_config = Texture::default().get_config().clone();
+    let max_filtering = match sampler.mag_filter() {
+        Some(MagFilter::Nearest) => Filtering::Nearest,
+        Some(MagFilter::Linear) => Filtering::Linear,
+        None => default_config.max_filtering,
+    };
+    let min_filtering = match sampler.min_filter() {
+        Some(MinFilter::Nearest) => MinFiltering::Filtering(Filtering::Nearest),
+        Some(MinFilter::Linear) => MinFiltering::Filtering(Filtering::Linear),
+        Some(MinFilter::NearestMipmapNearest) => {
+            MinFiltering::Mipmap(Mipmaps::NearestMipmapNearest)
+        }
+        Some(MinFilter::LinearMipmapNearest) => MinFiltering::Mipmap(Mipmaps::LinearMipmapNearest),
+        Some(MinFilter::NearestMipmapLinear) => MinFiltering::Mipmap(Mipmaps::NearestMipmapLinear),
+        Some(MinFilter::LinearMipmapLinear) => MinFiltering::Mipmap(Mipmaps::LinearMipmapLinear),
+        None => default_config.min_filtering,
+    };
+
+    Config {
+        wrapping_horizontal: convert_wrapping(sampler.wrap_s()),
+        wrapping_vertical: convert_wrapping(sampler.wrap_t()),
+        min_filtering,
+        max_filtering,
+        //only the base color textures are loaded, glTF stores them sRGB encoded
+        color_space: ColorSpace::Srgb,
+    }
+}
+
+fn convert_wrapping(mode: WrappingMode) -> Wrapping {
+    match mode {
+        WrappingMode::ClampToEdge => Wrapping::ClampToEdge,
+        WrappingMode::MirroredRepeat => Wrapping::MirroredRepeat,
+        WrappingMode::Repeat => Wrapping::Repeat,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::entity::Manager;
+    use std::path::PathBuf;
+
+    fn asset(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("assets/models")
+            .join(name)
+    }
+
+    fn check_triangles_scene(entities: &[Entity]) {
+        assert_eq!(entities.len(), 2);
+
+        //red material primitive with indices
+        assert!(entities[0].has_material());
+        let color = Color::unpack_rgba(entities[0].get_color()).unwrap();
+        assert_eq!(color.get_rgba(), (255, 0, 0, 1.0));
+
+        //textured primitive, the texture replaces the base color
+        assert!(entities[1].get_color().is_none());
+        assert_eq!(entities[1].len(), 4);
+    }
+
+    #[test]
+    fn test_load_gltf_embedded_data() {
+        let entities = load(&asset("triangles.gltf")).unwrap();
+        check_triangles_scene(&entities);
+    }
+
+    #[test]
+    fn test_load_glb() {
+        let entities = load(&asset("triangles.glb")).unwrap();
+        check_triangles_scene(&entities);
+    }
+
+    #[test]
+    fn test_load_gltf_node_transform_and_primitives() {
+        let (document, buffers, images) = gltf::import(asset("triangles.gltf")).unwrap();
+        let gltf = Model {
+            document,
+            buffers,
+            images,
+        };
+
+        let mut entities = Vec::new();
+        let root = gltf.document.nodes().next().unwrap();
+        gltf.load_node(&root, Matrix4::identity(), &mut entities)
+            .unwrap();
+        assert_eq!(entities.len(), 2);
+
+        let mesh = gltf.document.meshes().next().unwrap();
+        let mut primitives = mesh.primitives();
+        let indexed = gltf.create_mesh(&primitives.next().unwrap(), 0).unwrap();
+        assert_eq!(indexed.get_vertices().len(), 9);
+        assert_eq!(indexed.get_indices().unwrap(), &[0, 1, 2]);
+        assert!(indexed.get_texture_coords().is_none());
+
+        let textured = gltf.create_mesh(&primitives.next().unwrap(), 0).unwrap();
+        assert!(textured.get_indices().is_none());
+        assert_eq!(
+            textured.get_texture_coords().unwrap(),
+            &[0.0, 1.0, 1.0, 1.0, 0.0, 0.0]
+        );
+
+        let texture = gltf
+            .create_texture(&gltf.document.textures().next().unwrap())
+            .unwrap();
+        assert_eq!(texture.get_width(), 2);
+        assert_eq!(texture.get_raw_data()[..3], [0, 0, 255]);
+        assert_eq!(
+            texture.get_config().wrapping_horizontal,
+            Wrapping::ClampToEdge
+        );
+        assert_eq!(
+            texture.get_config().wrapping_vertical,
+            Wrapping::MirroredRepeat
+        );
+        assert_eq!(
+            texture.get_config().min_filtering,
+            MinFiltering::Filtering(Filtering::Nearest)
+        );
+    }
+
+    #[test]
+    fn test_load_gltf_node_world_transformation() {
+        let mut manager = Manager::default();
+        let ids: Vec<_> = load(&asset("triangles.gltf"))
+            .unwrap()
+            .into_iter()
+            .map(|entity| manager.add_entity(entity))
+            .collect();
+
+        let expected =
+            Matrix4::from_translation(Vector3::new(1.0, 0.0, 0.0)) * Matrix4::from_scale(2.0);
+        for id in ids {
+            let transformer = manager.as_ref_transformers(id).unwrap();
+            assert_eq!(transformer.get_matrix(), expected);
+        }
+    }
+
+    #[test]
+    fn test_load_gltf_unsupported_extension_get_error() {
+        let result = load(&asset("unsupported_extension.gltf"));
+        assert!(matches!(
+            result,
+            Err(Error::UnsupportedModelError(message)) if message.contains("KHR_draco_mesh_compression")
+        ));
+    }
+
+    #[test]
+    fn test_load_gltf_missing_file_get_error() {
+        assert!(load(Path::new("/nonExistedPath/model.gltf")).is_err());
+    }
+}
@@ -0,0 +1,558 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::str::SplitWhitespace;
+
+use cgmath::Vector3;
+
+use crate::components::color::{Color, RGBA};
+use crate::components::geometry::mesh::Builder;
+use crate::components::material::Material;
+use crate::components::shaders::read_src_from_file;
+use crate::components::texture::{self, ColorSpace, Config, Texture};
+use crate::components::{Component, Error, Result};
+use crate::entities::entity::Entity;
+
+#[derive(Default, Debug)]
+struct ObjData {
+    positions: Vec<[f32; 3]>,
+    texture_coords: Vec<[f32; 2]>,
+    normals: Vec<[f32; 3]>,
+    groups: Vec<Group>,
+    material_libs: Vec<String>,
+}
+
+#[derive(Default, Debug)]
+struct Group {
+    material: Option<String>,
+    //three vertices per triangle
+    vertices: Vec<VertexKey>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct VertexKey {
+    position: usize,
+    texture_coord: Option<usize>,
+    normal: Option<usize>,
+}
+
+#[derive(Default, Debug)]
+struct MaterialDescription {
+    material: Material,
+    diffuse_map: Option<String>,
+    specular_map: Option<String>,
+}
+
+struct LineParser<'a> {
+    path: &'a str,
+    line: usize,
+}
+
+/// Loads a Wavefront OBJ file together with its MTL libraries.
+/// One entity is created for every object, group and material change in the file.
+///
+/// # Errors
+///
+/// Will return `Err` if any of the files could not be opened or their content is malformed.
+pub fn load(path: &Path) -> Result<Vec<Entity>> {
+    let source = read_src_from_file(path)?;
+    let obj = parse_obj(&source, &path.to_string_lossy())?;
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut materials = HashMap::new();
+    for library in &obj.material_libs {
+        let library_path = directory.join(library);
+        let library_source = read_src_from_file(&library_path)?;
+        materials.extend(parse_mtl(&library_source, &library_path.to_string_lossy())?);
+    }
+
+    create_entities(&obj, &materials, directory)
+}
+
+fn create_entities(
+    obj: &ObjData,
+    materials: &HashMap<String, MaterialDescription>,
+    directory: &Path,
+) -> Result<Vec<Entity>> {
+    let mut result = Vec::new();
+    let mut loaded_textures: HashMap<(PathBuf, ColorSpace), Texture> = HashMap::new();
+    let mut load_texture = |name: &str, color_space: ColorSpace| -> Result<Texture> {
+        let key = (directory.join(name), color_space);
+        if let Some(texture) = loaded_textures.get(&key) {
+            return Ok(texture.clone());
+        }
+
+        let texture = Texture::new(
+            texture::load(&key.0)?,
+            Config {
+                color_space,
+                ..Texture::default().get_config().clone()
+            },
+        );
+        loaded_textures.insert(key, texture.clone());
+        Ok(texture)
+    };
+
+    for group in obj.groups.iter().filter(|group| !group.vertices.is_empty()) {
+        let mut entity = Entity::new(vec![Component::Geometry(Box::new(create_mesh(
+            obj, group,
+        )?))]);
+
+        let default_description = MaterialDescription::default();
+        let description = group
+            .material
+            .as_ref()
+            .and_then(|name| materials.get(name))
+            .unwrap_or(&default_description);
+
+        entity.add_component(Component::Material(description.material.clone()));
+        if let Some(diffuse_map) = &description.diffuse_map {
+            entity.add_component(Component::Texture(load_texture(
+                diffuse_map,
+                ColorSpace::Srgb,
+            )?));
+            //the specular map holds data, not colors
+            if let Some(specular_map) = &description.specular_map {
+                entity.add_component(Component::Texture(load_texture(
+                    specular_map,
+                    ColorSpace::Linear,
+                )?));
+            }
+        } else {
+            let color: RGBA = description.material.diffuse.into();
+            entity.add_component(Component::Color(Color::from_rgba(color)));
+        }
+
+        result.push(entity);
+    }
+
+    Ok(result)
+}
+
+fn create_mesh(obj: &ObjData, group: &Group) -> Result<crate::components::geometry::mesh::Mesh> {
+    let has_texture_coords = group
+        .vertices
+        .iter()
+        .all(|vertex| vertex.texture_coord.is_some());
+    let has_normals = group.vertices.iter().all(|vertex| vertex.normal.is_some());
+
+    let mut unique_vertices: HashMap<VertexKey, u32> = HashMap::new();
+    let mut positions = Vec::new();
+    let mut texture_coords = Vec::new();
+    let mut normals = Vec::new();
+    let mut indices = Vec::with_capacity(group.vertices.len());
+
+    for vertex in &group.vertices {
+        if let Some(index) = unique_vertices.get(vertex) {
+            indices.push(*index);
+            continue;
+        }
+
+        let index = u32::try_from(unique_vertices.len())
+            .map_err(|_| Error::GeometryError("Too many vertices in OBJ group".to_string()))?;
+        positions.extend_from_slice(&obj.positions[vertex.position]);
+        if let (true, Some(texture_coord)) = (has_texture_coords, vertex.texture_coord) {
+            texture_coords.extend_from_slice(&obj.texture_coords[texture_coord]);
+        }
+        if let (true, Some(normal)) = (has_normals, vertex.normal) {
+            normals.extend_from_slice(&obj.normals[normal]);
+        }
+
+        unique_vertices.insert(*vertex, index);
+        indices.push(index);
+    }
+
+    let mut builder = Builder::new(positions).with_indices(indices);
+    if has_texture_coords {
+        builder = builder.with_texture_coords(texture_coords);
+    }
+    if has_normals {
+        builder = builder.with_normals(normals);
+    }
+
+    builder.build()
+}
+
+fn parse_obj(source: &str, path: &str) -> Result<ObjData> {
+    let mut result = ObjData {
+        groups: vec![Group::default()],
+        ..Default::default()
+    };
+
+    for (number, line) in source.lines().enumerate() {
+        let parser = LineParser {
+            path,
+            line: number + 1,
+        };
+        let Some((keyword, mut values)) = split_line(line) else {
+            continue;
+        };
+
+        match keyword {
+            "v" => result.positions.push(parser.parse_floats(&mut values)?),
+            "vt" => result
+                .texture_coords
+                .push(parser.parse_floats(&mut values)?),
+            "vn" => result.normals.push(parser.parse_floats(&mut values)?),
+            "f" => {
+                let face = values
+                    .map(|vertex| parser.parse_face_vertex(vertex, &result))
+                    .collect::<Result<Vec<VertexKey>>>()?;
+                if face.len() < 3 {
+                    return Err(parser.error("face needs at least 3 vertices"));
+                }
+
+                let group = result.groups.last_mut().unwrap();
+                for i in 1..face.len() - 1 {
+                    group
+                        .vertices
+                        .extend_from_slice(&[face[0], face[i], face[i + 1]]);
+                }
+            }
+            "o" | "g" => {
+                let material = result.groups.last().unwrap().material.clone();
+                start_group(&mut result.groups, material);
+            }
+            "usemtl" => {
+                start_group(&mut result.groups, Some(parser.parse_name(line, keyword)?));
+            }
+            "mtllib" => result.material_libs.push(parser.parse_name(line, keyword)?),
+            _ => {} //smoothing groups, lines, points and curves are not supported
+        }
+    }
+
+    Ok(result)
+}
+
+fn parse_mtl(source: &str, path: &str) -> Result<HashMap<String, MaterialDescription>> {
+    let mut result = HashMap::new();
+    let mut current: Option<(String, MaterialDescription)> = None;
+
+    for (number, line) in source.lines().enumerate() {
+        let parser = LineParser {
+            path,
+            line: number + 1,
+        };
+        let Some((keyword, mut values)) = split_line(line) else {
+            continue;
+        };
+
+        if keyword == "newmtl" {
+            if let Some((name, description)) = current.take() {
+                result.insert(name, description);
+            }
+            current = Some((
+                parser.parse_name(line, keyword)?,
+                MaterialDescription::default(),
+            ));
+            continue;
+        }
+
+        let Some((_, description)) = current.as_mut() else {
+            return Err(parser.error("material statement before newmtl"));
+        };
+
+        match keyword {
+            "Ka" => description.material.ambient = parser.parse_color(&mut values)?,
+            "Kd" => description.material.diffuse = parser.parse_color(&mut values)?,
+            "Ks" => description.material.specular = parser.parse_color(&mut values)?,
+            "Ns" => {
+                let [shininess] = parser.parse_floats(&mut values)?;
+                description.material.shininess = shininess;
+            }
+            "map_Kd" => description.diffuse_map = Some(parser.parse_map_name(line, keyword)?),
+            "map_Ks" => description.specular_map = Some(parser.parse_map_name(line, keyword)?),
+            _ => {}
+        }
+    }
+
+    if let Some((name, description)) = current {
+        result.insert(name, description);
+    }
+
+    Ok(result)
+}
+
+fn split_line(line: &str) -> Option<(&str, SplitWhitespace<'_>)> {
+    let content = line.split('#').next().unwrap_or("");
+    let mut values = content.split_whitespace();
+    values.next().map(|keyword| (keyword, values))
+}
+
+fn start_group(groups: &mut Vec<Group>, material: Option<String>) {
+    let last = groups.last_mut().unwrap();
+    if last.vertices.is_empty() {
+        last.material = material;
+    } else {
+        groups.push(Group {
+            material,
+            vertices: Vec::new(),
+        });
+    }
+}
+
+impl LineParser<'_> {
+    fn error(&self, message: &str) -> Error {
+        Error::ParseError {
+            path: self.path.to_string(),
+            line: self.line,
+            message: message.to_string(),
+        }
+    }
+
+    /// Reads exactly `N` leading values, an optional trailing `w` component is ignored.
+    fn parse_floats<const N: usize>(&self, values: &mut SplitWhitespace) -> Result<[f32; N]> {
+        let mut result = [0.0; N];
+        for value in &mut result {
+            let text = values
+                .next()
+                .ok_or_else(|| self.error(&format!("expected {N} numbers")))?;
+            *value = text
+                .parse()
+                .map_err(|_| self.error(&format!("invalid number '{text}'")))?;
+        }
+        Ok(result)
+    }
+
+    fn parse_color(&self, values: &mut SplitWhitespace) -> Result<Vector3<f32>> {
+        Ok(self.parse_floats::<3>(values)?.into())
+    }
+
+    fn parse_name(&self, line: &str, keyword: &str) -> Result<String> {
+        let content = line.split('#').next().unwrap_or("").trim_start();
+        let name = content[keyword.len()..].trim();
+        if name.is_empty() {
+            Err(self.error(&format!("missing name after {keyword}")))
+        } else {
+            Ok(name.to_string())
+        }
+    }
+
+    //Texture options like "-s 1 1 1" may precede the file name
+    fn parse_map_name(&self, line: &str, keyword: &str) -> Result<String> {
+        let name = self.parse_name(line, keyword)?;
+        if name.starts_with('-') {
+            Ok(name.split_whitespace().last().unwrap_or("").to_string())
+        } else {
+            Ok(name)
+        }
+    }
+
+    fn parse_face_vertex(&self, vertex: &str, obj: &ObjData) -> Result<VertexKey> {
+        let mut parts = vertex.split('/');
+        let position = self.parse_index(parts.next(), obj.positions.len())?;
+        let texture_coord = match parts.next() {
+            Some("") | None => None,
+            part => Some(self.parse_index(part, obj.texture_coords.len())?),
+        };
+        let normal = match parts.next() {
+            Some("") | None => None,
+            part => Some(self.parse_index(part, obj.normals.len())?),
+        };
+
+        if parts.next().is_some() {
+            return Err(self.error(&format!("invalid face vertex '{vertex}'")));
+        }
+
+        Ok(VertexKey {
+            position,
+            texture_coord,
+            normal,
+        })
+    }
+
+    //OBJ indices start from 1, negative values count back from the last element
+    fn parse_index(&self, text: Option<&str>, count: usize) -> Result<usize> {
+        let text = text.unwrap_or("");
+        let index: i64 = text
+            .parse()
+            .map_err(|_| self.error(&format!("invalid index '{text}'")))?;
+
+        let count_i64 = i64::try_from(count).unwrap_or(i64::MAX);
+        let resolved = if index < 0 {
+            count_i64 + index
+        } else {
+            index - 1
+        };
+        if index == 0 || resolved < 0 || resolved >= count_i64 {
+            return Err(self.error(&format!("index {index} out of range")));
+        }
+
+        Ok(usize::try_from(resolved).unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::Shape;
+    use crate::entities::entity::Manager;
+    use image::{ImageBuffer, Rgb};
+    use std::fs;
+
+    const CUBE_FACE_OBJ: &str = "
+        # two triangles
+        mtllib test.mtl
+        o quad
+        v -1.0 -1.0 0.0
+        v 1.0 -1.0 0.0
+        v 1.0 1.0 0.0
+        v -1.0 1.0 0.0
+        vt 0.0 0.0
+        vt 1.0 0.0
+        vt 1.0 1.0
+        vt 0.0 1.0
+        vn 0.0 0.0 1.0
+        usemtl red
+        f 1/1/1 2/2/1 3/3/1 4/4/1
+    ";
+
+    #[test]
+    fn test_parse_obj_quad_triangulated() {
+        let obj = parse_obj(CUBE_FACE_OBJ, "test.obj").unwrap();
+
+        assert_eq!(obj.positions.len(), 4);
+        assert_eq!(obj.texture_coords.len(), 4);
+        assert_eq!(obj.normals.len(), 1);
+        assert_eq!(obj.material_libs, vec!["test.mtl".to_string()]);
+        assert_eq!(obj.groups.len(), 1);
+        assert_eq!(obj.groups[0].material, Some("red".to_string()));
+        assert_eq!(obj.groups[0].vertices.len(), 6);
+
+        let mesh = create_mesh(&obj, &obj.groups[0]).unwrap();
+        assert_eq!(mesh.get_vertices().len(), 12);
+        assert_eq!(mesh.get_indices().unwrap(), &[0, 1, 2, 0, 2, 3]);
+        assert_eq!(mesh.get_texture_coords().unwrap().len(), 8);
+        assert_eq!(mesh.get_normals().unwrap().len(), 12);
+    }
+
+    #[test]
+    fn test_parse_obj_negative_indices_and_no_attributes() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nf -3 -2 -1\n";
+        let obj = parse_obj(source, "test.obj").unwrap();
+        let mesh = create_mesh(&obj, &obj.groups[0]).unwrap();
+
+        assert_eq!(mesh.get_indices().unwrap(), &[0, 1, 2]);
+        assert!(mesh.get_texture_coords().is_none());
+        assert!(mesh.get_normals().is_none());
+    }
+
+    #[test]
+    fn test_parse_obj_groups_split_by_material() {
+        let source = "v 0 0 0\nv 1 0 0\nv 0 1 0\nusemtl a\nf 1 2 3\nusemtl b\nf 1 2 3\n";
+        let obj = parse_obj(source, "test.obj").unwrap();
+
+        assert_eq!(obj.groups.len(), 2);
+        assert_eq!(obj.groups[0].material, Some("a".to_string()));
+        assert_eq!(obj.groups[1].material, Some("b".to_string()));
+    }
+
+    #[test]
+    fn test_parse_obj_errors_with_line_number() {
+        let error = parse_obj("v 0 0 0\nv 1 x 0\n", "bad.obj").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Parse error in bad.obj at line 2: invalid number 'x'"
+        );
+
+        let error = parse_obj("v 0 0 0\nv 1 0 0\nf 1 2 3\n", "bad.obj").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Parse error in bad.obj at line 3: index 3 out of range"
+        );
+
+        let error = parse_obj("v 0 0 0\nv 1 0 0\n\nf 1 2\n", "bad.obj").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Parse error in bad.obj at line 4: face needs at least 3 vertices"
+        );
+    }
+
+    #[test]
+    fn test_parse_mtl() {
+        let source = "
+            newmtl red
+            Ka 0.1 0.0 0.0
+            Kd 0.9 0.0 0.0
+            Ks 0.5 0.5 0.5
+            Ns 64
+            map_Kd -s 1 1 1 diffuse.png
+            map_Ks specular map.png
+            newmtl plain
+        ";
+        let materials = parse_mtl(source, "test.mtl").unwrap();
+
+        let red = materials.get("red").unwrap();
+        assert_eq!(red.material.ambient, Vector3::new(0.1, 0.0, 0.0));
+        assert_eq!(red.material.diffuse, Vector3::new(0.9, 0.0, 0.0));
+        assert_eq!(red.material.specular, Vector3::new(0.5, 0.5, 0.5));
+        assert_eq!(red.material.shininess, 64.0);
+        assert_eq!(red.diffuse_map, Some("diffuse.png".to_string()));
+        assert_eq!(red.specular_map, Some("specular map.png".to_string()));
+
+        let plain = materials.get("plain").unwrap();
+        assert_eq!(plain.material, Material::default());
+        assert!(plain.diffuse_map.is_none());
+    }
+
+    #[test]
+    fn test_parse_mtl_statement_before_newmtl_get_error() {
+        let error = parse_mtl("Kd 1 1 1\n", "bad.mtl").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Parse error in bad.mtl at line 1: material statement before newmtl"
+        );
+    }
+
+    #[test]
+    fn test_load_obj_with_material_and_texture() {
+        let directory = std::env::temp_dir().join("phoenix_test_load_obj");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("test.obj"), CUBE_FACE_OBJ).unwrap();
+        fs::write(
+            directory.join("test.mtl"),
+            "newmtl red\nKd 1.0 0.0 0.0\nmap_Kd red.png\n",
+        )
+        .unwrap();
+        ImageBuffer::from_pixel(2, 2, Rgb([255_u8, 0, 0]))
+            .save(directory.join("red.png"))
+            .unwrap();
+
+        let entities = load(&directory.join("test.obj")).unwrap();
+        assert_eq!(entities.len(), 1);
+        assert!(entities[0].has_material());
+        assert_eq!(entities[0].len(), 3);
+        assert!(entities[0].get_color().is_none());
+
+        assert!(fs::remove_dir_all(&directory).is_ok());
+    }
+
+    #[test]
+    fn test_load_obj_specular_map_is_linear() {
+        let directory = std::env::temp_dir().join("phoenix_test_load_obj_specular");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("test.obj"), CUBE_FACE_OBJ).unwrap();
+        fs::write(
+            directory.join("test.mtl"),
+            "newmtl red\nmap_Kd red.png\nmap_Ks red.png\n",
+        )
+        .unwrap();
+        ImageBuffer::from_pixel(2, 2, Rgb([255_u8, 0, 0]))
+            .save(directory.join("red.png"))
+            .unwrap();
+
+        let mut entities = load(&directory.join("test.obj")).unwrap();
+        let mut manager = Manager::default();
+        let key = manager.add_entity(entities.remove(0));
+        let textures = manager.as_ref_entity(key).texture.unwrap();
+        assert_eq!(textures.len(), 2);
+        assert_eq!(textures[0].get_config().color_space, ColorSpace::Srgb);
+        assert_eq!(textures[1].get_config().color_space, ColorSpace::Linear);
+
+        assert!(fs::remove_dir_all(&directory).is_ok());
+    }
+
+    #[test]
+    fn test_load_obj_missing_file_get_error() {
+        assert!(load(Path::new("/nonExistedPath/model.obj")).is_err());
+    }
+}
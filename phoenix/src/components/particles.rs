@@ -0,0 +1,523 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use cgmath::{ElementWise, Vector3};
+
+use super::color::RGBA;
+use super::{Blending, Error, FillMode, Result, Shape, ShapeType};
+
+/// How every particle is drawn.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ParticleShape {
+    /// Square points, the sizes are in pixels. Textures are not applied to points.
+    Points,
+    /// Quads turned towards the camera, the sizes are in world units.
+    #[default]
+    Quads,
+}
+
+/// Particles spawned at the entity position and simulated on the CPU.
+/// The scene updates the emitter every frame with `Scene::get_delta_time()`.
+pub struct Emitter {
+    settings: Settings,
+    particles: Vec<Particle>,
+    rng: Rng,
+    spawn_debt: f32,
+    emitting: bool,
+    vertices: Vec<f32>,
+    offsets: Vec<f32>,
+    colors: Vec<f32>,
+    texture_coords: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+struct Settings {
+    spawn_rate: f32,
+    spawn_area: Vector3<f32>,
+    max_particles: usize,
+    lifetime: (f32, f32),
+    velocity: (Vector3<f32>, Vector3<f32>),
+    gravity: Vector3<f32>,
+    colors: ([f32; 4], [f32; 4]),
+    sizes: (f32, f32),
+    shape: ParticleShape,
+    blending: Blending,
+}
+
+struct Particle {
+    position: Vector3<f32>,
+    velocity: Vector3<f32>,
+    age: f32,
+    lifetime: f32,
+}
+
+/// Small `SplitMix64` generator, the same seed always gives the same particles.
+struct Rng {
+    state: u64,
+}
+
+pub struct Builder {
+    settings: Settings,
+    seed: Option<u64>,
+}
+
+impl Builder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            settings: Settings {
+                spawn_rate: 10.0,
+                spawn_area: Vector3::new(0.0, 0.0, 0.0),
+                max_particles: 1000,
+                lifetime: (1.0, 1.0),
+                velocity: (Vector3::new(0.0, 1.0, 0.0), Vector3::new(0.0, 1.0, 0.0)),
+                gravity: Vector3::new(0.0, 0.0, 0.0),
+                colors: ([1.0; 4], [1.0; 4]),
+                sizes: (0.1, 0.1),
+                shape: ParticleShape::default(),
+                blending: Blending::default(),
+            },
+            seed: None,
+        }
+    }
+
+    /// Particles spawned per second.
+    #[must_use]
+    pub fn with_spawn_rate(mut self, spawn_rate: f32) -> Self {
+        self.settings.spawn_rate = spawn_rate;
+        self
+    }
+
+    /// Half size of the box around the emitter in which the particles appear, a point by default.
+    #[must_use]
+    pub fn with_spawn_area(mut self, half_extents: Vector3<f32>) -> Self {
+        self.settings.spawn_area = half_extents;
+        self
+    }
+
+    /// Alive particles above the limit are not spawned.
+    #[must_use]
+    pub fn with_max_particles(mut self, max_particles: usize) -> Self {
+        self.settings.max_particles = max_particles;
+        self
+    }
+
+    /// Every particle lives a random time between `min` and `max` seconds.
+    #[must_use]
+    pub fn with_lifetime(mut self, min: f32, max: f32) -> Self {
+        self.settings.lifetime = (min, max);
+        self
+    }
+
+    /// Every component of the initial velocity is random between the ones of `min` and `max`.
+    #[must_use]
+    pub fn with_velocity(mut self, min: Vector3<f32>, max: Vector3<f32>) -> Self {
+        self.settings.velocity = (min, max);
+        self
+    }
+
+    #[must_use]
+    pub fn with_gravity(mut self, gravity: Vector3<f32>) -> Self {
+        self.settings.gravity = gravity;
+        self
+    }
+
+    /// Colors at the birth and at the death of a particle, blended linearly in between.
+    #[must_use]
+    pub fn with_colors(mut self, start: &RGBA, end: &RGBA) -> Self {
+        self.settings.colors = (normalize(start), normalize(end));
+        self
+    }
+
+    /// Sizes at the birth and at the death of a particle, blended linearly in between.
+    #[must_use]
+    pub fn with_sizes(mut self, start: f32, end: f32) -> Self {
+        self.settings.sizes = (start, end);
+        self
+    }
+
+    #[must_use]
+    pub fn with_shape(mut self, shape: ParticleShape) -> Self {
+        self.settings.shape = shape;
+        self
+    }
+
+    #[must_use]
+    pub fn with_blending(mut self, blending: Blending) -> Self {
+        self.settings.blending = blending;
+        self
+    }
+
+    /// Without a seed the particles differ from run to run.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the lifetime is not positive, the lifetime range is reversed,
+    /// or the spawn rate or a size is negative.
+    pub fn build(self) -> Result<Emitter> {
+        let settings = self.settings;
+        let (min_lifetime, max_lifetime) = settings.lifetime;
+        if min_lifetime <= 0.0 || max_lifetime < min_lifetime {
+            return Err(Error::ParticlesError(format!(
+                "invalid lifetime range from {min_lifetime} to {max_lifetime}"
+            )));
+        }
+        if settings.spawn_rate < 0.0 {
+            return Err(Error::ParticlesError(format!(
+                "negative spawn rate {}",
+                settings.spawn_rate
+            )));
+        }
+        if settings.sizes.0 < 0.0 || settings.sizes.1 < 0.0 {
+            return Err(Error::ParticlesError("negative particle size".to_string()));
+        }
+
+        let seed = self.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs() ^ u64::from(time.subsec_nanos()))
+        });
+        Ok(Emitter {
+            settings,
+            particles: Vec::new(),
+            rng: Rng::new(seed),
+            spawn_debt: 0.0,
+            emitting: true,
+            vertices: Vec::new(),
+            offsets: Vec::new(),
+            colors: Vec::new(),
+            texture_coords: Vec::new(),
+            indices: Vec::new(),
+        })
+    }
+}
+
+impl Default for Builder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Emitter {
+    /// Ages, moves and spawns the particles, then rebuilds their geometry.
+    pub fn update(&mut self, delta_time: f32) {
+        let delta_time = delta_time.max(0.0);
+        let gravity = self.settings.gravity;
+        for particle in &mut self.particles {
+            particle.age += delta_time;
+            particle.velocity += gravity * delta_time;
+            particle.position += particle.velocity * delta_time;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+
+        if self.emitting {
+            self.spawn_debt += self.settings.spawn_rate * delta_time;
+            while self.spawn_debt >= 1.0 && self.particles.len() < self.settings.max_particles {
+                let particle = self.spawn();
+                self.particles.push(particle);
+                self.spawn_debt -= 1.0;
+            }
+            //a full emitter does not save the particles for later
+            self.spawn_debt = self.spawn_debt.min(1.0);
+        }
+
+        self.rebuild_geometry();
+    }
+
+    /// A stopped emitter lets the alive particles finish, it is emitting from the start.
+    pub fn set_emitting(&mut self, emitting: bool) {
+        self.emitting = emitting;
+        if !emitting {
+            self.spawn_debt = 0.0;
+        }
+    }
+
+    #[must_use]
+    pub fn is_emitting(&self) -> bool {
+        self.emitting
+    }
+
+    /// Number of alive particles.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.particles.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.particles.is_empty()
+    }
+
+    #[must_use]
+    pub fn get_blending(&self) -> Blending {
+        self.settings.blending
+    }
+
+    /// Positions of the alive particles.
+    pub fn positions(&self) -> impl Iterator<Item = Vector3<f32>> + '_ {
+        self.particles.iter().map(|particle| particle.position)
+    }
+
+    fn spawn(&mut self) -> Particle {
+        let settings = &self.settings;
+        let area = settings.spawn_area;
+        let (min_velocity, max_velocity) = settings.velocity;
+        let (min_lifetime, max_lifetime) = settings.lifetime;
+        let unit = Vector3::new(
+            self.rng.next_f32(),
+            self.rng.next_f32(),
+            self.rng.next_f32(),
+        );
+        let velocity = Vector3::new(
+            self.rng.next_f32(),
+            self.rng.next_f32(),
+            self.rng.next_f32(),
+        );
+
+        Particle {
+            position: (unit * 2.0 - Vector3::new(1.0, 1.0, 1.0)).mul_element_wise(area),
+            velocity: min_velocity + (max_velocity - min_velocity).mul_element_wise(velocity),
+            age: 0.0,
+            lifetime: min_lifetime + (max_lifetime - min_lifetime) * self.rng.next_f32(),
+        }
+    }
+
+    /// Every quad gets four copies of the particle position, the vertex shader moves
+    /// them apart by the offsets in the normal attribute. Points carry their size there.
+    fn rebuild_geometry(&mut self) {
+        let (start_color, end_color) = self.settings.colors;
+        let (start_size, end_size) = self.settings.sizes;
+        self.vertices.clear();
+        self.offsets.clear();
+        self.colors.clear();
+        self.texture_coords.clear();
+        self.indices.clear();
+
+        let mut first_index = 0;
+        for particle in &self.particles {
+            let progress = (particle.age / particle.lifetime).clamp(0.0, 1.0);
+            let size = start_size + (end_size - start_size) * progress;
+            let color: [f32; 4] = std::array::from_fn(|index| {
+                start_color[index] + (end_color[index] - start_color[index]) * progress
+            });
+            let position = [
+                particle.position.x,
+                particle.position.y,
+                particle.position.z,
+            ];
+
+            match self.settings.shape {
+                ParticleShape::Points => {
+                    self.vertices.extend(position);
+                    self.offsets.extend([0.0, 0.0, size]);
+                    self.colors.extend(color);
+                    self.texture_coords.extend([0.5, 0.5]);
+                }
+                ParticleShape::Quads => {
+                    let half = size / 2.0;
+                    for corner in [[-1.0, -1.0], [1.0, -1.0], [1.0, 1.0], [-1.0, 1.0]] {
+                        self.vertices.extend(position);
+                        self.offsets
+                            .extend([corner[0] * half, corner[1] * half, 0.0]);
+                        self.colors.extend(color);
+                        self.texture_coords
+                            .extend([f32::midpoint(corner[0], 1.0), f32::midpoint(corner[1], 1.0)]);
+                    }
+                    self.indices
+                        .extend([0, 1, 2, 0, 2, 3].map(|index: u32| first_index + index));
+                    first_index += 4;
+                }
+            }
+        }
+    }
+}
+
+impl Shape for Emitter {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        match self.settings.shape {
+            ParticleShape::Points => None,
+            ParticleShape::Quads => Some(&self.indices),
+        }
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.offsets)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_colors(&self) -> Option<&[f32]> {
+        Some(&self.colors)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Particles
+    }
+
+    fn get_fill_mode(&self) -> FillMode {
+        match self.settings.shape {
+            ParticleShape::Points => FillMode::Points { size: 1.0 },
+            ParticleShape::Quads => FillMode::Solid,
+        }
+    }
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        value ^ (value >> 31)
+    }
+
+    /// Uniform value from 0 up to 1, made of the 24 top bits which an `f32` holds exactly.
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_precision_loss)]
+    fn next_f32(&mut self) -> f32 {
+        (self.next_u64() >> 40) as f32 / (1_u32 << 24) as f32
+    }
+}
+
+fn normalize(color: &RGBA) -> [f32; 4] {
+    let mut rgba = color.get_as_normalized_f32();
+    rgba[3] = rgba[3].clamp(0.0, 1.0);
+    rgba
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_emitter(seed: u64) -> Emitter {
+        Builder::new()
+            .with_spawn_rate(10.0)
+            .with_lifetime(1.0, 2.0)
+            .with_velocity(Vector3::new(-1.0, 1.0, 0.0), Vector3::new(1.0, 2.0, 0.0))
+            .with_gravity(Vector3::new(0.0, -10.0, 0.0))
+            .with_seed(seed)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_emitter_spawn_rate_and_lifetime() {
+        let mut emitter = create_emitter(1);
+
+        emitter.update(0.25);
+        assert_eq!(emitter.len(), 2);
+        emitter.update(0.5);
+        assert_eq!(emitter.len(), 7);
+
+        //every particle is dead after the longest lifetime
+        emitter.set_emitting(false);
+        emitter.update(2.0);
+        assert!(emitter.is_empty());
+        assert!(emitter.get_vertices().is_empty());
+    }
+
+    #[test]
+    fn test_emitter_same_seed_same_particles() {
+        let mut first = create_emitter(42);
+        let mut second = create_emitter(42);
+        let mut other = create_emitter(7);
+        for _ in 0..10 {
+            first.update(0.1);
+            second.update(0.1);
+            other.update(0.1);
+        }
+
+        assert_eq!(first.get_vertices(), second.get_vertices());
+        assert_ne!(first.get_vertices(), other.get_vertices());
+    }
+
+    #[test]
+    fn test_emitter_velocity_and_gravity() {
+        let mut emitter = create_emitter(3);
+        emitter.update(0.1);
+        let spawned: Vec<_> = emitter.positions().collect();
+        assert_eq!(spawned, [Vector3::new(0.0, 0.0, 0.0)]);
+
+        emitter.set_emitting(false);
+        emitter.update(0.1);
+        let position = emitter.positions().next().unwrap();
+        //the velocity is inside its range after gravity slowed it down during one step
+        assert!((-0.1..=0.1).contains(&position.x));
+        assert!((0.0..=0.1).contains(&position.y));
+    }
+
+    #[test]
+    fn test_emitter_quads_over_lifetime() {
+        let mut emitter = Builder::new()
+            .with_spawn_rate(1.0)
+            .with_lifetime(2.0, 2.0)
+            .with_colors(&RGBA::new(255, 0, 0, 1.0), &RGBA::new(0, 0, 255, 0.0))
+            .with_sizes(1.0, 3.0)
+            .with_seed(5)
+            .build()
+            .unwrap();
+        emitter.update(1.0);
+        emitter.set_emitting(false);
+        emitter.update(1.0);
+
+        assert_eq!(emitter.get_vertices().len(), 12);
+        assert_eq!(emitter.get_indices().unwrap(), [0, 1, 2, 0, 2, 3]);
+        //halfway through the lifetime
+        assert_eq!(emitter.get_colors().unwrap()[..4], [0.5, 0.0, 0.5, 0.5]);
+        assert_eq!(emitter.get_normals().unwrap()[..3], [-1.0, -1.0, 0.0]);
+        assert_eq!(emitter.get_texture_coords().unwrap()[4..6], [1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_emitter_points() {
+        let mut emitter = Builder::new()
+            .with_shape(ParticleShape::Points)
+            .with_sizes(4.0, 4.0)
+            .with_seed(5)
+            .build()
+            .unwrap();
+        emitter.update(0.5);
+
+        assert_eq!(emitter.len(), 5);
+        assert_eq!(emitter.get_vertices().len(), 15);
+        assert!(emitter.get_indices().is_none());
+        assert_eq!(emitter.get_normals().unwrap()[..3], [0.0, 0.0, 4.0]);
+        assert!(matches!(emitter.get_fill_mode(), FillMode::Points { .. }));
+    }
+
+    #[test]
+    fn test_emitter_max_particles() {
+        let mut emitter = Builder::new()
+            .with_spawn_rate(100.0)
+            .with_max_particles(5)
+            .with_lifetime(10.0, 10.0)
+            .with_seed(1)
+            .build()
+            .unwrap();
+        emitter.update(1.0);
+        assert_eq!(emitter.len(), 5);
+    }
+
+    #[test]
+    fn test_emitter_invalid_get_error() {
+        assert!(Builder::new().with_lifetime(0.0, 1.0).build().is_err());
+        assert!(Builder::new().with_lifetime(2.0, 1.0).build().is_err());
+        assert!(Builder::new().with_spawn_rate(-1.0).build().is_err());
+        assert!(Builder::new().with_sizes(-1.0, 1.0).build().is_err());
+    }
+}
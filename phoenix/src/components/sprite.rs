@@ -0,0 +1,610 @@
+use std::rc::Rc;
+
+use super::texture::Texture;
+use super::{Error, Result, Shape, ShapeType};
+
+/// Rectangle of an atlas image in pixels, counted from its top left corner.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Region {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Texture holding many sprite frames, addressed by their index.
+/// The image is expected as returned by `texture::load`, which flips it upside down for OpenGL.
+pub struct Atlas {
+    texture: Texture,
+    regions: Vec<Region>,
+}
+
+/// Order in which the animation goes through its frames.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Playback {
+    /// Stops at the last frame.
+    Once,
+    /// Starts again from the first frame after the last one.
+    #[default]
+    Loop,
+    /// Goes to the last frame and back to the first one.
+    PingPong,
+}
+
+/// Sprite-sheet animation moved forward by the frame time.
+#[derive(Clone, Debug)]
+pub struct Animation {
+    /// Indices of the atlas regions in the order they are shown.
+    frames: Vec<usize>,
+    frame_duration: f32,
+    playback: Playback,
+    elapsed: f32,
+    current: usize,
+    backwards: bool,
+    playing: bool,
+}
+
+/// Textured quad showing one region of an atlas, drawn with the atlas texture.
+pub struct Sprite {
+    atlas: Rc<Atlas>,
+    frame: usize,
+    size: [f32; 2],
+    pivot: [f32; 2],
+    flip: [bool; 2],
+    animation: Option<Animation>,
+    changed: bool,
+    vertices: [f32; 12],
+    texture_coords: [f32; 8],
+}
+
+pub struct Builder {
+    atlas: Rc<Atlas>,
+    frame: usize,
+    size: Option<[f32; 2]>,
+    pivot: [f32; 2],
+    flip: [bool; 2],
+    animation: Option<Animation>,
+}
+
+const NORMALS: [f32; 12] = [0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+const INDICES: [u32; 6] = [0, 1, 2, 0, 2, 3];
+
+impl Atlas {
+    /// # Errors
+    ///
+    /// Will return `Err` if a region reaches out of the texture.
+    pub fn new(texture: Texture, regions: Vec<Region>) -> Result<Self> {
+        let (width, height) = (texture.get_width(), texture.get_height());
+        if let Some(region) = regions
+            .iter()
+            .find(|region| region.x + region.width > width || region.y + region.height > height)
+        {
+            return Err(Error::SpriteError(format!(
+                "region {region:?} is out of the {width}x{height} texture"
+            )));
+        }
+
+        Ok(Self { texture, regions })
+    }
+
+    /// Sprite sheet of equally sized frames, indexed row by row from the top left corner.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the frame size is zero or bigger than the texture.
+    pub fn from_grid(texture: Texture, frame_width: u32, frame_height: u32) -> Result<Self> {
+        if frame_width == 0 || frame_height == 0 {
+            return Err(Error::SpriteError(
+                "frame size must not be zero".to_string(),
+            ));
+        }
+        let columns = texture.get_width() / frame_width;
+        let rows = texture.get_height() / frame_height;
+        if columns == 0 || rows == 0 {
+            return Err(Error::SpriteError(format!(
+                "{frame_width}x{frame_height} frame is bigger than the texture"
+            )));
+        }
+
+        let regions = (0..rows)
+            .flat_map(|row| {
+                (0..columns).map(move |column| Region {
+                    x: column * frame_width,
+                    y: row * frame_height,
+                    width: frame_width,
+                    height: frame_height,
+                })
+            })
+            .collect();
+        Ok(Self { texture, regions })
+    }
+
+    #[must_use]
+    pub fn get_texture(&self) -> &Texture {
+        &self.texture
+    }
+
+    #[must_use]
+    pub fn get_region(&self, frame: usize) -> Option<&Region> {
+        self.regions.get(frame)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    /// Texture coordinates of the region corners: left, bottom, right and top.
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn get_texture_rect(&self, frame: usize) -> [f32; 4] {
+        let region = self.regions[frame];
+        let width = self.texture.get_width() as f32;
+        let height = self.texture.get_height() as f32;
+        //the rows of the loaded image are flipped, the top of the region has the bigger coordinate
+        [
+            region.x as f32 / width,
+            1.0 - (region.y + region.height) as f32 / height,
+            (region.x + region.width) as f32 / width,
+            1.0 - region.y as f32 / height,
+        ]
+    }
+}
+
+impl Animation {
+    /// Plays the atlas frames in the given order, starting with the first one.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the frames per second are not a positive number.
+    pub fn new(frames: Vec<usize>, frames_per_second: f32, playback: Playback) -> Result<Self> {
+        if !frames_per_second.is_finite() || frames_per_second <= 0.0 {
+            return Err(Error::SpriteError(format!(
+                "{frames_per_second} frames per second is not positive"
+            )));
+        }
+        Ok(Self {
+            frames,
+            frame_duration: 1.0 / frames_per_second,
+            playback,
+            elapsed: 0.0,
+            current: 0,
+            backwards: false,
+            playing: true,
+        })
+    }
+
+    /// Returns whether the shown frame changed.
+    pub fn update(&mut self, delta_time: f32) -> bool {
+        if !self.playing || self.frames.len() < 2 {
+            return false;
+        }
+
+        let previous = self.current;
+        self.elapsed += delta_time;
+        while self.playing && self.elapsed >= self.frame_duration {
+            self.elapsed -= self.frame_duration;
+            self.step();
+        }
+        previous != self.current
+    }
+
+    fn step(&mut self) {
+        let last = self.frames.len() - 1;
+        match self.playback {
+            Playback::Once if self.current == last => {
+                self.playing = false;
+                self.elapsed = 0.0;
+            }
+            Playback::Once => self.current += 1,
+            Playback::Loop => self.current = (self.current + 1) % self.frames.len(),
+            Playback::PingPong => {
+                if self.current == last {
+                    self.backwards = true;
+                } else if self.current == 0 {
+                    self.backwards = false;
+                }
+                if self.backwards {
+                    self.current -= 1;
+                } else {
+                    self.current += 1;
+                }
+            }
+        }
+    }
+
+    pub fn play(&mut self) {
+        self.playing = true;
+    }
+
+    pub fn pause(&mut self) {
+        self.playing = false;
+    }
+
+    /// Goes back to the first frame and plays again.
+    pub fn restart(&mut self) {
+        self.current = 0;
+        self.elapsed = 0.0;
+        self.backwards = false;
+        self.playing = true;
+    }
+
+    #[must_use]
+    pub fn is_playing(&self) -> bool {
+        self.playing
+    }
+
+    /// Atlas index of the shown frame.
+    #[must_use]
+    pub fn get_frame(&self) -> usize {
+        self.frames.get(self.current).copied().unwrap_or_default()
+    }
+
+    #[must_use]
+    pub fn get_frames(&self) -> &[usize] {
+        &self.frames
+    }
+}
+
+impl Builder {
+    #[must_use]
+    pub fn new(atlas: Rc<Atlas>) -> Self {
+        Self {
+            atlas,
+            frame: 0,
+            size: None,
+            pivot: [0.5, 0.5],
+            flip: [false, false],
+            animation: None,
+        }
+    }
+
+    #[must_use]
+    pub fn with_frame(mut self, frame: usize) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Size of the quad in world units, one unit high with the aspect ratio of the first frame by default.
+    #[must_use]
+    pub fn with_size(mut self, width: f32, height: f32) -> Self {
+        self.size = Some([width, height]);
+        self
+    }
+
+    /// Point of the quad placed at the origin, from (0, 0) in the bottom left to (1, 1) in the top right corner.
+    /// The transformer rotates and scales the sprite around it.
+    #[must_use]
+    pub fn with_pivot(mut self, x: f32, y: f32) -> Self {
+        self.pivot = [x, y];
+        self
+    }
+
+    /// Mirrors the frame horizontally and vertically.
+    #[must_use]
+    pub fn with_flip(mut self, horizontal: bool, vertical: bool) -> Self {
+        self.flip = [horizontal, vertical];
+        self
+    }
+
+    /// The animation decides the shown frame, `with_frame` is ignored.
+    #[must_use]
+    pub fn with_animation(mut self, animation: Animation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the frame or a frame of the animation is not in the atlas.
+    pub fn build(self) -> Result<Sprite> {
+        let frame = self
+            .animation
+            .as_ref()
+            .map_or(self.frame, Animation::get_frame);
+        let frames = self
+            .animation
+            .as_ref()
+            .map_or(&[][..], Animation::get_frames);
+        if let Some(missing) = std::iter::once(&frame)
+            .chain(frames)
+            .find(|frame| **frame >= self.atlas.len())
+        {
+            return Err(Error::SpriteError(format!(
+                "frame {missing} is not in the atlas of {} frames",
+                self.atlas.len()
+            )));
+        }
+
+        let size = self.size.unwrap_or_else(|| {
+            let region = self.atlas.regions[frame];
+            #[allow(clippy::cast_precision_loss)]
+            let aspect = region.width as f32 / region.height as f32;
+            [aspect, 1.0]
+        });
+        let mut sprite = Sprite {
+            atlas: self.atlas,
+            frame,
+            size,
+            pivot: self.pivot,
+            flip: self.flip,
+            animation: self.animation,
+            changed: false,
+            vertices: [0.0; 12],
+            texture_coords: [0.0; 8],
+        };
+        sprite.update_vertices();
+        sprite.update_texture_coords();
+        Ok(sprite)
+    }
+}
+
+impl Sprite {
+    /// Moves the animation forward, the renderer picks the new frame up in the next frame.
+    pub fn update(&mut self, delta_time: f32) {
+        let Some(animation) = self.animation.as_mut() else {
+            return;
+        };
+        if animation.update(delta_time) {
+            let frame = animation.get_frame();
+            self.show_frame(frame);
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the frame is not in the atlas.
+    pub fn set_frame(&mut self, frame: usize) -> Result<()> {
+        if frame >= self.atlas.len() {
+            return Err(Error::SpriteError(format!(
+                "frame {frame} is not in the atlas of {} frames",
+                self.atlas.len()
+            )));
+        }
+        self.show_frame(frame);
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn get_frame(&self) -> usize {
+        self.frame
+    }
+
+    pub fn set_flip(&mut self, horizontal: bool, vertical: bool) {
+        if self.flip != [horizontal, vertical] {
+            self.flip = [horizontal, vertical];
+            self.update_texture_coords();
+            self.changed = true;
+        }
+    }
+
+    #[must_use]
+    pub fn get_flip(&self) -> [bool; 2] {
+        self.flip
+    }
+
+    #[must_use]
+    pub fn get_size(&self) -> [f32; 2] {
+        self.size
+    }
+
+    #[must_use]
+    pub fn get_pivot(&self) -> [f32; 2] {
+        self.pivot
+    }
+
+    #[must_use]
+    pub fn get_atlas(&self) -> &Rc<Atlas> {
+        &self.atlas
+    }
+
+    #[must_use]
+    pub fn get_animation(&self) -> Option<&Animation> {
+        self.animation.as_ref()
+    }
+
+    pub fn get_animation_mut(&mut self) -> Option<&mut Animation> {
+        self.animation.as_mut()
+    }
+
+    /// Whether the shown frame or the flip changed since the last `take_changed`, without resetting it.
+    pub(crate) fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Whether the shown frame or the flip changed since the last call.
+    pub(crate) fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    fn show_frame(&mut self, frame: usize) {
+        if self.frame != frame {
+            self.frame = frame;
+            self.update_texture_coords();
+            self.changed = true;
+        }
+    }
+
+    fn update_vertices(&mut self) {
+        let [width, height] = self.size;
+        let left = -self.pivot[0] * width;
+        let bottom = -self.pivot[1] * height;
+        let (right, top) = (left + width, bottom + height);
+        self.vertices = [
+            left, bottom, 0.0, right, bottom, 0.0, right, top, 0.0, left, top, 0.0,
+        ];
+    }
+
+    fn update_texture_coords(&mut self) {
+        let [mut left, mut bottom, mut right, mut top] = self.atlas.get_texture_rect(self.frame);
+        if self.flip[0] {
+            std::mem::swap(&mut left, &mut right);
+        }
+        if self.flip[1] {
+            std::mem::swap(&mut bottom, &mut top);
+        }
+        self.texture_coords = [left, bottom, right, bottom, right, top, left, top];
+    }
+}
+
+impl Shape for Sprite {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&INDICES)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&NORMALS)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Sprite
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{DynamicImage, RgbaImage};
+
+    fn create_atlas() -> Rc<Atlas> {
+        //four frames of 2x2 pixels in two rows
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(4, 4));
+        let texture = Texture::new(Rc::new(image), Texture::default().get_config().clone());
+        Rc::new(Atlas::from_grid(texture, 2, 2).unwrap())
+    }
+
+    #[test]
+    fn test_atlas_from_grid() {
+        let atlas = create_atlas();
+
+        assert_eq!(atlas.len(), 4);
+        assert_eq!(
+            atlas.get_region(1),
+            Some(&Region {
+                x: 2,
+                y: 0,
+                width: 2,
+                height: 2
+            })
+        );
+        assert_eq!(atlas.get_texture_rect(1)[..], [0.5, 0.5, 1.0, 1.0]);
+        assert_eq!(atlas.get_texture_rect(2)[..], [0.0, 0.0, 0.5, 0.5]);
+
+        let texture = atlas.get_texture().clone();
+        assert!(Atlas::from_grid(texture.clone(), 8, 2).is_err());
+        assert!(Atlas::from_grid(texture.clone(), 0, 2).is_err());
+        let outside = Region {
+            x: 3,
+            y: 0,
+            width: 2,
+            height: 2,
+        };
+        assert!(Atlas::new(texture, vec![outside]).is_err());
+    }
+
+    #[test]
+    fn test_sprite_pivot_and_flip() {
+        let mut sprite = Builder::new(create_atlas())
+            .with_frame(1)
+            .with_size(2.0, 1.0)
+            .with_pivot(0.0, 0.0)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            sprite.get_vertices(),
+            [0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 2.0, 1.0, 0.0, 0.0, 1.0, 0.0]
+        );
+        assert_eq!(
+            sprite.get_texture_coords().unwrap(),
+            [0.5, 0.5, 1.0, 0.5, 1.0, 1.0, 0.5, 1.0]
+        );
+
+        sprite.set_flip(true, false);
+        assert!(sprite.take_changed());
+        assert_eq!(
+            sprite.get_texture_coords().unwrap(),
+            [1.0, 0.5, 0.5, 0.5, 0.5, 1.0, 1.0, 1.0]
+        );
+    }
+
+    #[test]
+    fn test_sprite_default_size_and_frames() {
+        let mut sprite = Builder::new(create_atlas()).build().unwrap();
+
+        assert_eq!(sprite.get_size()[..], [1.0, 1.0]);
+        assert_eq!(
+            sprite.get_vertices(),
+            [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.5, 0.5, 0.0, -0.5, 0.5, 0.0]
+        );
+        assert!(sprite.set_frame(3).is_ok());
+        assert!(sprite.take_changed());
+        assert!(sprite.set_frame(4).is_err());
+        assert!(Builder::new(create_atlas()).with_frame(4).build().is_err());
+        let animation = Animation::new(vec![0, 5], 10.0, Playback::Loop).unwrap();
+        assert!(Builder::new(create_atlas())
+            .with_animation(animation)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_animation_playback() {
+        let frames_after = |playback, steps| {
+            let mut animation = Animation::new(vec![0, 1, 2], 10.0, playback).unwrap();
+            (0..steps)
+                .map(|_| {
+                    animation.update(0.1);
+                    animation.get_frame()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(frames_after(Playback::Loop, 5), [1, 2, 0, 1, 2]);
+        assert_eq!(frames_after(Playback::Once, 5), [1, 2, 2, 2, 2]);
+        assert_eq!(frames_after(Playback::PingPong, 6), [1, 2, 1, 0, 1, 2]);
+
+        let mut once = Animation::new(vec![0, 1], 10.0, Playback::Once).unwrap();
+        once.update(0.5);
+        assert!(!once.is_playing());
+        once.restart();
+        assert_eq!(once.get_frame(), 0);
+        assert!(once.is_playing());
+
+        for frames_per_second in [0.0, -5.0, f32::NAN, f32::INFINITY] {
+            assert!(Animation::new(vec![0, 1], frames_per_second, Playback::Loop).is_err());
+        }
+    }
+
+    #[test]
+    fn test_sprite_animation_updates_frame() {
+        let animation = Animation::new(vec![3, 2], 4.0, Playback::Loop).unwrap();
+        let mut sprite = Builder::new(create_atlas())
+            .with_animation(animation)
+            .build()
+            .unwrap();
+        assert_eq!(sprite.get_frame(), 3);
+
+        sprite.update(0.1);
+        assert!(!sprite.take_changed());
+        sprite.update(0.2);
+        assert_eq!(sprite.get_frame(), 2);
+        assert!(sprite.take_changed());
+
+        sprite.get_animation_mut().unwrap().pause();
+        sprite.update(1.0);
+        assert_eq!(sprite.get_frame(), 2);
+    }
+}
@@ -0,0 +1,635 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::rc::Rc;
+
+use ab_glyph::{Font as _, FontVec, GlyphId, OutlinedGlyph, PxScale, ScaleFont};
+use image::{DynamicImage, Rgba, RgbaImage};
+
+use super::texture::{ColorSpace, Config, Filtering, MinFiltering, Texture, Wrapping};
+use super::{Error, Result, Shape, ShapeType};
+
+/// Grows when a glyph of a large font does not fit in.
+const ATLAS_WIDTH: u32 = 512;
+/// Empty pixels around every glyph, so the linear filtering does not bleed the neighbours in.
+const ATLAS_PADDING: u32 = 1;
+/// Drawn and advanced for characters missing in the atlas.
+const REPLACEMENT: char = '?';
+
+/// Printable ASCII and Latin-1 characters, rasterized by `Font::load`.
+fn default_characters() -> impl Iterator<Item = char> {
+    (' '..='~').chain('\u{a0}'..='\u{ff}')
+}
+
+/// TTF or OTF font rasterized at one pixel height into a glyph atlas texture.
+pub struct Font {
+    face: FontVec,
+    scale: PxScale,
+    atlas: Rc<DynamicImage>,
+    /// Every rasterized character, the ones without a bitmap like the space have `None`.
+    glyphs: HashMap<char, Option<AtlasGlyph>>,
+}
+
+/// Place of a glyph bitmap in the atlas. The offset of its top left corner is measured
+/// from the pen position on the baseline, in pixels growing to the right and down.
+#[derive(Clone, Copy, Debug)]
+struct AtlasGlyph {
+    offset: [f32; 2],
+    size: [f32; 2],
+    texture_min: [f32; 2],
+    texture_max: [f32; 2],
+}
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// Where the text is drawn.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Space {
+    /// Over the scene, in window pixels from the top left corner. The transformer moves it in pixels.
+    Screen,
+    /// In the scene like any other shape, one line is `line_height` world units tall.
+    /// The text starts at the origin and goes to the right and down.
+    World { line_height: f32 },
+}
+
+/// Laid out string drawn with the atlas of its font, colored by the `Color` component of the entity.
+pub struct Text {
+    font: Rc<Font>,
+    content: String,
+    alignment: Alignment,
+    space: Space,
+    max_width: Option<f32>,
+    line_spacing: f32,
+    size: [f32; 2],
+    changed: bool,
+    vertices: Vec<f32>,
+    normals: Vec<f32>,
+    texture_coords: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+pub struct Builder {
+    font: Rc<Font>,
+    content: String,
+    alignment: Alignment,
+    space: Space,
+    max_width: Option<f32>,
+    line_spacing: f32,
+}
+
+impl Font {
+    /// Rasterizes the printable ASCII and Latin-1 characters.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the file could not be read, it is not a valid font or the pixel height is not positive.
+    pub fn load(path: &Path, pixel_height: f32) -> Result<Rc<Self>> {
+        Self::load_with_characters(path, pixel_height, default_characters())
+    }
+
+    /// Rasterizes the printable ASCII and Latin-1 characters and the given ones.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the file could not be read, it is not a valid font or the pixel height is not positive.
+    pub fn load_with_characters(
+        path: &Path,
+        pixel_height: f32,
+        characters: impl IntoIterator<Item = char>,
+    ) -> Result<Rc<Self>> {
+        if !pixel_height.is_finite() || pixel_height <= 0.0 {
+            return Err(Error::FontError(format!(
+                "pixel height {pixel_height} is not positive"
+            )));
+        }
+        let data = std::fs::read(path)
+            .map_err(|_| Error::SourceFileError(path.to_string_lossy().to_string()))?;
+        let font = FontVec::try_from_vec(data)
+            .map_err(|err| Error::FontError(format!("{}: {err}", path.to_string_lossy())))?;
+        Ok(Rc::new(Self::new(font, pixel_height, characters)))
+    }
+
+    fn new(face: FontVec, pixel_height: f32, characters: impl IntoIterator<Item = char>) -> Self {
+        let scale = PxScale::from(pixel_height);
+        let mut characters: Vec<char> = default_characters().chain(characters).collect();
+        characters.sort_unstable();
+        characters.dedup();
+
+        let outlines: Vec<(char, OutlinedGlyph)> = characters
+            .iter()
+            .filter_map(|character| {
+                let glyph = face.glyph_id(*character).with_scale(scale);
+                Some((*character, face.outline_glyph(glyph)?))
+            })
+            .collect();
+        let (atlas, packed) = pack_atlas(&outlines);
+        let mut glyphs: HashMap<char, Option<AtlasGlyph>> = characters
+            .into_iter()
+            .map(|character| (character, None))
+            .collect();
+        glyphs.extend(
+            packed
+                .into_iter()
+                .map(|(character, glyph)| (character, Some(glyph))),
+        );
+
+        Self {
+            face,
+            scale,
+            atlas: Rc::new(DynamicImage::ImageRgba8(atlas)),
+            glyphs,
+        }
+    }
+
+    #[must_use]
+    pub fn get_pixel_height(&self) -> f32 {
+        self.scale.y
+    }
+
+    /// Distance between the baselines of two lines, in pixels.
+    #[must_use]
+    pub fn get_line_height(&self) -> f32 {
+        let scaled = self.face.as_scaled(self.scale);
+        scaled.height() + scaled.line_gap()
+    }
+
+    /// White atlas with the glyph coverage in the alpha channel.
+    #[must_use]
+    pub fn get_texture(&self) -> Texture {
+        Texture::new(
+            self.atlas.clone(),
+            Config {
+                wrapping_horizontal: Wrapping::ClampToEdge,
+                wrapping_vertical: Wrapping::ClampToEdge,
+                min_filtering: MinFiltering::Filtering(Filtering::Linear),
+                max_filtering: Filtering::Linear,
+                //the coverage is a mask, not a color
+                color_space: ColorSpace::Linear,
+            },
+        )
+    }
+
+    /// Width of one line in pixels, with the kerning between the characters.
+    #[must_use]
+    pub fn measure(&self, line: &str) -> f32 {
+        let scaled = self.face.as_scaled(self.scale);
+        let mut previous: Option<GlyphId> = None;
+        line.chars()
+            .map(|character| {
+                let id = scaled.glyph_id(self.resolve(character));
+                let kerning = previous.map_or(0.0, |previous| scaled.kern(previous, id));
+                previous = Some(id);
+                kerning + scaled.h_advance(id)
+            })
+            .sum()
+    }
+
+    /// The character itself when it was rasterized, the replacement otherwise.
+    fn resolve(&self, character: char) -> char {
+        if self.glyphs.contains_key(&character) {
+            character
+        } else {
+            REPLACEMENT
+        }
+    }
+
+    /// Lines after the explicit line breaks and, with `max_width`, the wrapping between words.
+    /// A single word longer than `max_width` stays on its own line.
+    fn break_lines(&self, content: &str, max_width: Option<f32>) -> Vec<String> {
+        let mut lines = Vec::new();
+        for paragraph in content.lines() {
+            let Some(max_width) = max_width else {
+                lines.push(paragraph.to_string());
+                continue;
+            };
+
+            let mut line = String::new();
+            for word in paragraph.split(' ') {
+                let candidate = if line.is_empty() {
+                    word.to_string()
+                } else {
+                    format!("{line} {word}")
+                };
+                if self.measure(&candidate) > max_width && !line.is_empty() {
+                    lines.push(std::mem::replace(&mut line, word.to_string()));
+                } else {
+                    line = candidate;
+                }
+            }
+            lines.push(line);
+        }
+        lines
+    }
+}
+
+impl Builder {
+    #[must_use]
+    pub fn new(font: Rc<Font>, content: &str) -> Self {
+        Self {
+            font,
+            content: content.to_string(),
+            alignment: Alignment::default(),
+            space: Space::Screen,
+            max_width: None,
+            line_spacing: 1.0,
+        }
+    }
+
+    /// Alignment of the lines inside the text block, as wide as its longest line or `max_width`.
+    #[must_use]
+    pub fn with_alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    #[must_use]
+    pub fn with_space(mut self, space: Space) -> Self {
+        self.space = space;
+        self
+    }
+
+    /// Lines longer than `max_width` are broken between words. The width is in the units of the space.
+    #[must_use]
+    pub fn with_max_width(mut self, max_width: f32) -> Self {
+        self.max_width = Some(max_width);
+        self
+    }
+
+    /// Multiplies the distance between the lines given by the font.
+    #[must_use]
+    pub fn with_line_spacing(mut self, line_spacing: f32) -> Self {
+        self.line_spacing = line_spacing;
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Text {
+        let mut text = Text {
+            font: self.font,
+            content: self.content,
+            alignment: self.alignment,
+            space: self.space,
+            max_width: self.max_width,
+            line_spacing: self.line_spacing,
+            size: [0.0, 0.0],
+            changed: false,
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            texture_coords: Vec::new(),
+            indices: Vec::new(),
+        };
+        text.layout();
+        text
+    }
+}
+
+impl Text {
+    /// Lays the text out again, the renderer picks the new geometry up in the next frame.
+    pub fn set_content(&mut self, content: &str) {
+        if self.content != content {
+            content.clone_into(&mut self.content);
+            self.layout();
+            self.changed = true;
+        }
+    }
+
+    #[must_use]
+    pub fn get_content(&self) -> &str {
+        &self.content
+    }
+
+    #[must_use]
+    pub fn get_space(&self) -> Space {
+        self.space
+    }
+
+    #[must_use]
+    pub fn is_screen_space(&self) -> bool {
+        self.space == Space::Screen
+    }
+
+    /// Width and height of the text block in the units of the space.
+    #[must_use]
+    pub fn get_size(&self) -> [f32; 2] {
+        self.size
+    }
+
+    #[must_use]
+    pub fn get_font(&self) -> &Rc<Font> {
+        &self.font
+    }
+
+    /// Whether the content changed since the last `take_changed`, without resetting it.
+    pub(crate) fn is_changed(&self) -> bool {
+        self.changed
+    }
+
+    /// Whether the content changed since the last call.
+    pub(crate) fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Builds one quad per visible glyph, in pixels first and then scaled to the space.
+    #[allow(clippy::cast_precision_loss)]
+    fn layout(&mut self) {
+        let font = self.font.clone();
+        let scaled = font.face.as_scaled(font.scale);
+        let (unit, flip) = match self.space {
+            Space::Screen => (1.0, 1.0),
+            Space::World { line_height } => (line_height / font.get_line_height(), -1.0),
+        };
+        let lines = font.break_lines(&self.content, self.max_width.map(|width| width / unit));
+        let line_height = font.get_line_height() * self.line_spacing;
+        let widths: Vec<f32> = lines.iter().map(|line| font.measure(line)).collect();
+        let block_width = self.max_width.map_or_else(
+            || widths.iter().copied().fold(0.0, f32::max),
+            |width| width / unit,
+        );
+        let factor = match self.alignment {
+            Alignment::Left => 0.0,
+            Alignment::Center => 0.5,
+            Alignment::Right => 1.0,
+        };
+
+        self.vertices.clear();
+        self.normals.clear();
+        self.texture_coords.clear();
+        self.indices.clear();
+        let mut first_index = 0;
+        let mut baseline = scaled.ascent();
+        for (line, width) in lines.iter().zip(&widths) {
+            let mut pen = (block_width - width) * factor;
+            let mut previous: Option<GlyphId> = None;
+            for character in line.chars().map(|character| font.resolve(character)) {
+                let id = scaled.glyph_id(character);
+                pen += previous.map_or(0.0, |previous| scaled.kern(previous, id));
+                previous = Some(id);
+
+                if let Some(glyph) = font.glyphs.get(&character).copied().flatten() {
+                    //whole pixels keep the screen text sharp
+                    let mut left = pen + glyph.offset[0];
+                    let mut top = baseline + glyph.offset[1];
+                    if self.space == Space::Screen {
+                        left = left.round();
+                        top = top.round();
+                    }
+                    self.push_quad(&glyph, [left, top], unit, flip, first_index);
+                    first_index += 4;
+                }
+                pen += scaled.h_advance(id);
+            }
+            baseline += line_height;
+        }
+
+        let height = line_height * (lines.len().max(1) - 1) as f32 + scaled.height();
+        self.size = [block_width * unit, height * unit];
+    }
+
+    fn push_quad(
+        &mut self,
+        glyph: &AtlasGlyph,
+        top_left: [f32; 2],
+        unit: f32,
+        flip: f32,
+        first: u32,
+    ) {
+        let [left, top] = top_left;
+        let [right, bottom] = [left + glyph.size[0], top + glyph.size[1]];
+        let [texture_left, texture_top] = glyph.texture_min;
+        let [texture_right, texture_bottom] = glyph.texture_max;
+        for ([x, y], [u, v]) in [
+            ([left, bottom], [texture_left, texture_bottom]),
+            ([right, bottom], [texture_right, texture_bottom]),
+            ([right, top], [texture_right, texture_top]),
+            ([left, top], [texture_left, texture_top]),
+        ] {
+            self.vertices.extend([x * unit, y * unit * flip, 0.0]);
+            self.normals.extend([0.0, 0.0, 1.0]);
+            self.texture_coords.extend([u, v]);
+        }
+        self.indices
+            .extend([0, 1, 2, 0, 2, 3].map(|index| first + index));
+    }
+}
+
+impl Shape for Text {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Text
+    }
+}
+
+/// Places the glyphs in rows from the top of the atlas, the height grows to a power of two.
+/// The atlas is wider than `ATLAS_WIDTH` when the widest glyph does not fit in.
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+#[allow(clippy::cast_precision_loss)]
+fn pack_atlas(outlines: &[(char, OutlinedGlyph)]) -> (RgbaImage, HashMap<char, AtlasGlyph>) {
+    let sizes: Vec<(u32, u32)> = outlines
+        .iter()
+        .map(|(_, outline)| {
+            let bounds = outline.px_bounds();
+            (bounds.width() as u32, bounds.height() as u32)
+        })
+        .collect();
+    let atlas_width = sizes
+        .iter()
+        .map(|(width, _)| width + 2 * ATLAS_PADDING)
+        .fold(ATLAS_WIDTH, u32::max);
+
+    let mut places = Vec::with_capacity(outlines.len());
+    let (mut x, mut y, mut row_height) = (ATLAS_PADDING, ATLAS_PADDING, 0);
+    for (width, height) in sizes {
+        if x + width + ATLAS_PADDING > atlas_width {
+            x = ATLAS_PADDING;
+            y += row_height + ATLAS_PADDING;
+            row_height = 0;
+        }
+        places.push((x, y, width, height));
+        x += width + ATLAS_PADDING;
+        row_height = row_height.max(height);
+    }
+    let atlas_height = (y + row_height + ATLAS_PADDING).next_power_of_two();
+
+    let mut atlas = RgbaImage::from_pixel(atlas_width, atlas_height, Rgba([255, 255, 255, 0]));
+    let mut glyphs = HashMap::with_capacity(outlines.len());
+    let (atlas_width, atlas_height_f32) = (atlas_width as f32, atlas_height as f32);
+    for ((character, outline), (x, y, width, height)) in outlines.iter().zip(places) {
+        outline.draw(|pixel_x, pixel_y, coverage| {
+            if pixel_x < width && pixel_y < height {
+                let alpha = (coverage.clamp(0.0, 1.0) * 255.0).round() as u8;
+                atlas.put_pixel(x + pixel_x, y + pixel_y, Rgba([255, 255, 255, alpha]));
+            }
+        });
+        let bounds = outline.px_bounds();
+        glyphs.insert(
+            *character,
+            AtlasGlyph {
+                offset: [bounds.min.x, bounds.min.y],
+                size: [width as f32, height as f32],
+                texture_min: [x as f32 / atlas_width, y as f32 / atlas_height_f32],
+                texture_max: [
+                    (x + width) as f32 / atlas_width,
+                    (y + height) as f32 / atlas_height_f32,
+                ],
+            },
+        );
+    }
+
+    (atlas, glyphs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_font() -> Rc<Font> {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/fonts/DejaVuSans.ttf");
+        Font::load(&path, 32.0).unwrap()
+    }
+
+    #[test]
+    fn test_font_atlas() {
+        let font = load_font();
+        let texture = font.get_texture();
+
+        assert_eq!(texture.get_width(), ATLAS_WIDTH);
+        assert!(texture.get_height().is_power_of_two());
+        assert!(texture.is_alpha_channel());
+        //the space has no bitmap, the letters have
+        assert!(font.glyphs[&' '].is_none());
+        let glyph = font.glyphs[&'A'].unwrap();
+        assert!(glyph.size[0] > 0.0 && glyph.texture_max[0] > glyph.texture_min[0]);
+
+        assert!(Font::load(Path::new("/nonExistedPath/font.ttf"), 32.0).is_err());
+        let not_font = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/models/triangles.gltf");
+        assert!(Font::load(&not_font, 32.0).is_err());
+    }
+
+    #[test]
+    fn test_font_large_glyph_and_invalid_height() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/fonts/DejaVuSans.ttf");
+        let face = FontVec::try_from_vec(std::fs::read(&path).unwrap()).unwrap();
+        let glyph = face.glyph_id('W').with_scale(PxScale::from(700.0));
+        let outline = face.outline_glyph(glyph).unwrap();
+        let width = f64::from(outline.px_bounds().width());
+        assert!(width > f64::from(ATLAS_WIDTH));
+
+        //the atlas grows to the widest glyph
+        let (atlas, glyphs) = pack_atlas(&[('W', outline)]);
+        assert!(f64::from(atlas.width()) >= width + 2.0);
+        assert!(glyphs[&'W'].texture_max[0] <= 1.0);
+
+        for height in [0.0, -12.0, f32::NAN, f32::INFINITY] {
+            assert!(Font::load(&path, height).is_err());
+        }
+    }
+
+    #[test]
+    fn test_text_replacement_for_characters_missing_in_atlas() {
+        let font = load_font();
+        //the font has the letter, but it is not rasterized by default
+        assert!(!font.glyphs.contains_key(&'Ł'));
+        assert!((font.measure("Ł") - font.measure("?")).abs() < 1e-6);
+
+        let replaced = Builder::new(font.clone(), "aŁb").build();
+        let expected = Builder::new(font.clone(), "a?b").build();
+        assert_eq!(replaced.get_vertices(), expected.get_vertices());
+        assert_eq!(replaced.get_texture_coords(), expected.get_texture_coords());
+
+        let loaded = Font::load_with_characters(
+            &Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/fonts/DejaVuSans.ttf"),
+            32.0,
+            ['Ł'],
+        )
+        .unwrap();
+        assert!(loaded.glyphs[&'Ł'].is_some());
+    }
+
+    #[test]
+    fn test_text_kerning() {
+        let font = load_font();
+        let separate = font.measure("A") + font.measure("V");
+
+        assert!(font.measure("AV") < separate);
+        assert!(font.measure("").abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_text_line_breaks_and_wrapping() {
+        let font = load_font();
+        let text = Builder::new(font.clone(), "one\ntwo three").build();
+        //the space is not drawn
+        assert_eq!(text.get_vertices().len(), 11 * 4 * 3);
+        assert_eq!(text.get_indices().unwrap().len(), 11 * 6);
+
+        let width = font.measure("two three") - 1.0;
+        let lines = font.break_lines("one two three", Some(width));
+        assert_eq!(lines, ["one two", "three"]);
+        let wrapped = Builder::new(font.clone(), "one two three")
+            .with_max_width(width)
+            .build();
+        assert!((wrapped.get_size()[0] - width).abs() < 1e-3);
+        assert!(wrapped.get_size()[1] > font.get_line_height());
+    }
+
+    #[test]
+    fn test_text_alignment() {
+        let font = load_font();
+        let left_edge = |alignment| {
+            let text = Builder::new(font.clone(), "wide line\nI")
+                .with_alignment(alignment)
+                .build();
+            //the last quad is the single letter of the second line
+            let vertices = text.get_vertices();
+            vertices[vertices.len() - 3]
+        };
+
+        let width = font.measure("wide line");
+        let letter = font.measure("I");
+        let left = left_edge(Alignment::Left);
+        assert!((left_edge(Alignment::Center) - left - (width - letter) / 2.0).abs() <= 1.0);
+        assert!((left_edge(Alignment::Right) - left - (width - letter)).abs() <= 1.0);
+    }
+
+    #[test]
+    fn test_text_world_space_and_content() {
+        let font = load_font();
+        let mut text = Builder::new(font.clone(), "Hi")
+            .with_space(Space::World { line_height: 1.0 })
+            .build();
+
+        //the text goes down from the origin
+        let vertices = text.get_vertices();
+        assert!(vertices.chunks(3).all(|vertex| vertex[1] <= 0.0));
+        assert!(vertices.chunks(3).any(|vertex| vertex[1] < -0.5));
+        assert!(text.get_size()[1] < 1.5);
+        assert!(!text.take_changed());
+
+        text.set_content("Hi!");
+        assert_eq!(text.get_content(), "Hi!");
+        assert_eq!(text.get_vertices().len(), 3 * 4 * 3);
+        assert!(text.take_changed());
+        assert!(!text.take_changed());
+    }
+}
@@ -1,7 +1,10 @@
+use super::geometry::solid::CUBE_TEXTURE_COORDS;
 use super::Result;
 use image::{self, DynamicImage};
 use std::{path::Path, rc::Rc};
 
+pub mod projection;
+
 pub type TexID = u32;
 
 #[derive(Clone)]
@@ -17,6 +20,7 @@ pub struct Config {
     pub wrapping_vertical: Wrapping,
     pub min_filtering: MinFiltering,
     pub max_filtering: Filtering,
+    pub color_space: ColorSpace,
 }
 
 #[derive(Copy, Clone, PartialEq, Debug)]
@@ -41,6 +45,15 @@ pub enum Mipmaps {
     LinearMipmapLinear,
 }
 
+/// How the sampled texels are decoded before the shader gets them.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub enum ColorSpace {
+    /// Color images, decoded from sRGB to linear light when the renderer output is gamma correct.
+    Srgb,
+    /// Data like specular or normal maps and masks, sampled as they are stored.
+    Linear,
+}
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum MinMag {
     Minifying,
@@ -53,14 +66,10 @@ pub enum MinFiltering {
     Filtering(Filtering),
 }
 
+/// Texture coordinates of `Cube`, one image on every face.
 #[must_use]
 pub fn generate_default_vertices_for_cube() -> Vec<f32> {
-    vec![
-        0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0,
-        1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0,
-        1.0, 0.0, 1.0, 1.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0,
-        1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
-    ]
+    CUBE_TEXTURE_COORDS.to_vec()
 }
 
 /// # Errors
@@ -72,6 +81,7 @@ pub fn load(path: &Path) -> Result<Rc<DynamicImage>> {
 }
 
 impl Texture {
+    /// The texture is mapped with the texture coordinates of the shape it is attached to.
     #[must_use]
     pub fn new(data: Rc<DynamicImage>, config: Config) -> Self {
         Self {
@@ -81,6 +91,7 @@ impl Texture {
         }
     }
 
+    /// The given texture coordinates override the ones provided by the shape.
     #[must_use]
     pub fn new_with_vertices(data: Rc<DynamicImage>, config: Config, vertices: Vec<f32>) -> Self {
         Self {
@@ -127,7 +138,7 @@ impl Texture {
     #[must_use]
     pub fn unpack_vertices(texture: Option<&Vec<Texture>>) -> Option<&[f32]> {
         if let Some(val) = texture {
-            if val.is_empty() {
+            if val.is_empty() || val[0].get_vertices().is_empty() {
                 return None;
             }
 
@@ -147,8 +158,9 @@ impl Default for Texture {
                 wrapping_vertical: Wrapping::Repeat,
                 min_filtering: MinFiltering::Filtering(Filtering::Linear),
                 max_filtering: Filtering::Linear,
+                color_space: ColorSpace::Srgb,
             },
-            vertices: vec![0.0, 0.0, 1.0, 0.0, 0.5, 1.0],
+            vertices: Vec::new(),
         }
     }
 }
@@ -168,12 +180,14 @@ mod tests {
             wrapping_vertical: Wrapping::Repeat,
             min_filtering: MinFiltering::Filtering(Filtering::Linear),
             max_filtering: Filtering::Linear,
+            color_space: ColorSpace::Srgb,
         };
         let texture = Texture::new(Rc::new(img), config.clone());
 
         assert_eq!(texture.get_width(), 2);
         assert_eq!(texture.get_height(), 2);
         assert_eq!(texture.get_config(), &config);
+        assert!(texture.get_vertices().is_empty());
     }
 
     #[test]
@@ -185,6 +199,7 @@ mod tests {
             wrapping_vertical: Wrapping::Repeat,
             min_filtering: MinFiltering::Filtering(Filtering::Linear),
             max_filtering: Filtering::Linear,
+            color_space: ColorSpace::Srgb,
         };
         let vertices = vec![0.0, 0.0, 1.0, 0.0, 0.5, 1.0];
         let texture = Texture::new_with_vertices(Rc::new(img), config.clone(), vertices.clone());
@@ -204,12 +219,14 @@ mod tests {
             wrapping_vertical: Wrapping::Repeat,
             min_filtering: MinFiltering::Filtering(Filtering::Linear),
             max_filtering: Filtering::Linear,
+            color_space: ColorSpace::Srgb,
         };
         let config2 = Config {
             wrapping_horizontal: Wrapping::ClampToEdge,
             wrapping_vertical: Wrapping::ClampToEdge,
             min_filtering: MinFiltering::Mipmap(Mipmaps::LinearMipmapLinear),
             max_filtering: Filtering::Nearest,
+            color_space: ColorSpace::Linear,
         };
 
         let mut texture = Texture::new(Rc::new(img), config1.clone());
@@ -241,6 +258,23 @@ mod tests {
             default_texture.get_config().max_filtering,
             Filtering::Linear
         );
+        assert_eq!(default_texture.get_config().color_space, ColorSpace::Srgb);
+    }
+
+    #[test]
+    fn test_unpack_vertices() {
+        let with_vertices = Texture::new_with_vertices(
+            Rc::new(DynamicImage::default()),
+            Texture::default().get_config().clone(),
+            vec![0.0, 0.0, 1.0, 0.0, 0.5, 1.0],
+        );
+        assert_eq!(
+            Texture::unpack_vertices(Some(&vec![with_vertices])).unwrap(),
+            &[0.0, 0.0, 1.0, 0.0, 0.5, 1.0]
+        );
+        assert!(Texture::unpack_vertices(Some(&vec![Texture::default()])).is_none());
+        assert!(Texture::unpack_vertices(Some(&vec![])).is_none());
+        assert!(Texture::unpack_vertices(None).is_none());
     }
 
     #[test]
@@ -252,6 +286,7 @@ mod tests {
             wrapping_vertical: Wrapping::Repeat,
             min_filtering: MinFiltering::Filtering(Filtering::Linear),
             max_filtering: Filtering::Linear,
+            color_space: ColorSpace::Srgb,
         };
         let texture = Texture::new(Rc::new(img), config);
 
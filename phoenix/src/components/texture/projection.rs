@@ -0,0 +1,206 @@
+use std::f32::consts::PI;
+
+/// Axis along which a projection is done.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    /// Indices of the u, v and projection axis coordinates.
+    fn coordinates(self) -> (usize, usize, usize) {
+        match self {
+            Axis::X => (1, 2, 0),
+            Axis::Y => (0, 2, 1),
+            Axis::Z => (0, 1, 2),
+        }
+    }
+}
+
+struct Bounds {
+    min: [f32; 3],
+    max: [f32; 3],
+}
+
+impl Bounds {
+    fn new(vertices: &[f32]) -> Self {
+        let mut bounds = Self {
+            min: [f32::MAX; 3],
+            max: [f32::MIN; 3],
+        };
+        for vertex in vertices.chunks_exact(3) {
+            for (coordinate, value) in vertex.iter().enumerate() {
+                bounds.min[coordinate] = bounds.min[coordinate].min(*value);
+                bounds.max[coordinate] = bounds.max[coordinate].max(*value);
+            }
+        }
+        bounds
+    }
+
+    /// Maps the value into 0.0 - 1.0, flat dimensions map into the middle.
+    fn normalize(&self, coordinate: usize, value: f32) -> f32 {
+        let extent = self.max[coordinate] - self.min[coordinate];
+        if extent > f32::EPSILON {
+            (value - self.min[coordinate]) / extent
+        } else {
+            0.5
+        }
+    }
+
+    fn center(&self, coordinate: usize) -> f32 {
+        f32::midpoint(self.min[coordinate], self.max[coordinate])
+    }
+}
+
+/// Projects the vertices along `axis`, for `Axis::Z` u follows x and v follows y.
+/// Like every projection here, the texture is stretched over the vertices bounding box.
+#[must_use]
+pub fn planar(vertices: &[f32], axis: Axis) -> Vec<f32> {
+    let bounds = Bounds::new(vertices);
+    let (u, v, _) = axis.coordinates();
+    vertices
+        .chunks_exact(3)
+        .flat_map(|vertex| {
+            [
+                bounds.normalize(u, vertex[u]),
+                bounds.normalize(v, vertex[v]),
+            ]
+        })
+        .collect()
+}
+
+/// Box projection, every vertex is projected along the axis its normal points the most.
+/// `normals` must hold one normal per vertex.
+#[must_use]
+pub fn cubic(vertices: &[f32], normals: &[f32]) -> Vec<f32> {
+    let bounds = Bounds::new(vertices);
+    vertices
+        .chunks_exact(3)
+        .zip(normals.chunks_exact(3))
+        .flat_map(|(vertex, normal)| {
+            let axis = if normal[0].abs() >= normal[1].abs() && normal[0].abs() >= normal[2].abs() {
+                Axis::X
+            } else if normal[1].abs() >= normal[2].abs() {
+                Axis::Y
+            } else {
+                Axis::Z
+            };
+            let (u, v, _) = axis.coordinates();
+            [
+                bounds.normalize(u, vertex[u]),
+                bounds.normalize(v, vertex[v]),
+            ]
+        })
+        .collect()
+}
+
+/// Wraps the texture around `axis`, u follows the angle and v the position along the axis.
+/// Vertices on the seam are not split, so the triangles crossing it get a squeezed texture.
+#[must_use]
+pub fn cylindrical(vertices: &[f32], axis: Axis) -> Vec<f32> {
+    let bounds = Bounds::new(vertices);
+    let (first, second, height) = axis.coordinates();
+    vertices
+        .chunks_exact(3)
+        .flat_map(|vertex| {
+            let angle = f32::atan2(
+                vertex[second] - bounds.center(second),
+                vertex[first] - bounds.center(first),
+            );
+            [
+                angle / (2.0 * PI) + 0.5,
+                bounds.normalize(height, vertex[height]),
+            ]
+        })
+        .collect()
+}
+
+/// Wraps the texture around the bounding box center with the poles on `axis`,
+/// u follows the longitude and v the latitude. The seam is handled like in `cylindrical`.
+#[must_use]
+pub fn spherical(vertices: &[f32], axis: Axis) -> Vec<f32> {
+    let bounds = Bounds::new(vertices);
+    let (first, second, pole) = axis.coordinates();
+    vertices
+        .chunks_exact(3)
+        .flat_map(|vertex| {
+            let x = vertex[first] - bounds.center(first);
+            let y = vertex[second] - bounds.center(second);
+            let z = vertex[pole] - bounds.center(pole);
+            let length = (x * x + y * y + z * z).sqrt();
+            let latitude = if length > f32::EPSILON {
+                (z / length).clamp(-1.0, 1.0).asin()
+            } else {
+                0.0
+            };
+            [f32::atan2(y, x) / (2.0 * PI) + 0.5, latitude / PI + 0.5]
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUAD: [f32; 12] = [
+        -1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 1.0, 1.0, 0.0, -1.0, 1.0, 0.0,
+    ];
+
+    fn assert_near(result: &[f32], expected: &[f32]) {
+        assert_eq!(result.len(), expected.len());
+        for (value, expected) in result.iter().zip(expected) {
+            assert!(
+                (value - expected).abs() < 1e-5,
+                "{result:?} != {expected:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_planar_projection() {
+        assert_near(
+            &planar(&QUAD, Axis::Z),
+            &[0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0, 1.0],
+        );
+        //the quad is flat along y, v falls into the middle
+        assert_near(
+            &planar(&QUAD, Axis::Y),
+            &[0.0, 0.5, 1.0, 0.5, 1.0, 0.5, 0.0, 0.5],
+        );
+    }
+
+    #[test]
+    fn test_cubic_projection() {
+        let vertices = [0.0, 0.0, 0.0, 2.0, 0.0, 1.0, 2.0, 4.0, 1.0];
+        let normals = [0.0, 0.0, 1.0, 0.0, -1.0, 0.0, 1.0, 0.2, 0.0];
+        assert_near(&cubic(&vertices, &normals), &[0.0, 0.0, 1.0, 1.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_cylindrical_projection() {
+        let vertices = [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0, 2.0, 0.0, -1.0, 2.0];
+        assert_near(
+            &cylindrical(&vertices, Axis::Z),
+            &[0.5, 0.0, 0.75, 0.0, 1.0, 1.0, 0.25, 1.0],
+        );
+    }
+
+    #[test]
+    fn test_spherical_projection() {
+        let vertices = [
+            0.0, 0.0, 1.0, 0.0, 0.0, -1.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, -1.0, 0.0, 0.0, 0.0, -1.0,
+            0.0,
+        ];
+        let result = spherical(&vertices, Axis::Z);
+        assert_near(&result[..4], &[0.5, 1.0, 0.5, 0.0]);
+        assert_near(&result[4..], &[0.5, 0.5, 0.75, 0.5, 1.0, 0.5, 0.25, 0.5]);
+    }
+
+    #[test]
+    fn test_projection_of_empty_vertices() {
+        assert!(planar(&[], Axis::Z).is_empty());
+        assert!(spherical(&[], Axis::Y).is_empty());
+    }
+}
@@ -0,0 +1,387 @@
+use std::ops::Range;
+use std::rc::Rc;
+
+use super::sprite::Atlas;
+use super::{Error, Result, Shape, ShapeType};
+
+/// Grid of tileset frames, `None` leaves the cell empty.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Layer {
+    width: usize,
+    height: usize,
+    tiles: Vec<Option<usize>>,
+}
+
+/// Layers of tiles drawn as one mesh, every cell keeps its own quad, so editing a tile
+/// rewrites only its vertices. The map starts at the origin and goes to the right and down,
+/// the later layers are drawn over the earlier ones.
+pub struct Tilemap {
+    tileset: Rc<Atlas>,
+    tile_size: [f32; 2],
+    width: usize,
+    height: usize,
+    layers: Vec<Layer>,
+    changed: Vec<usize>,
+    vertices: Vec<f32>,
+    normals: Vec<f32>,
+    texture_coords: Vec<f32>,
+    indices: Vec<u32>,
+}
+
+impl Layer {
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![None; width * height],
+        }
+    }
+
+    /// The first row is the top one.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the rows differ in length.
+    pub fn from_rows(rows: &[Vec<Option<usize>>]) -> Result<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(Error::TilemapError(
+                "all rows of a layer must have the same length".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height: rows.len(),
+            tiles: rows.concat(),
+        })
+    }
+
+    #[must_use]
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn get_tile(&self, x: usize, y: usize) -> Option<usize> {
+        if x < self.width {
+            self.tiles.get(y * self.width + x).copied().flatten()
+        } else {
+            None
+        }
+    }
+}
+
+impl Tilemap {
+    /// Tiles are `tile_width` by `tile_height` world units, the frames of the tileset are their images.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the layers differ in size or a tile is not in the tileset.
+    pub fn new(
+        tileset: Rc<Atlas>,
+        tile_width: f32,
+        tile_height: f32,
+        layers: Vec<Layer>,
+    ) -> Result<Self> {
+        let (width, height) = layers
+            .first()
+            .map_or((0, 0), |layer| (layer.width, layer.height));
+        if layers
+            .iter()
+            .any(|layer| layer.width != width || layer.height != height)
+        {
+            return Err(Error::TilemapError(
+                "all layers must have the same size".to_string(),
+            ));
+        }
+        if let Some(tile) = layers
+            .iter()
+            .flat_map(|layer| layer.tiles.iter().flatten())
+            .find(|tile| **tile >= tileset.len())
+        {
+            return Err(Error::TilemapError(format!(
+                "tile {tile} is not in the tileset of {} frames",
+                tileset.len()
+            )));
+        }
+
+        let mut tilemap = Self {
+            tileset,
+            tile_size: [tile_width, tile_height],
+            width,
+            height,
+            layers,
+            changed: Vec::new(),
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            texture_coords: Vec::new(),
+            indices: Vec::new(),
+        };
+        tilemap.build_mesh();
+        Ok(tilemap)
+    }
+
+    /// Changes one cell, the renderer rewrites only its vertices in the next frame.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the cell is out of the map or the tile is not in the tileset.
+    pub fn set_tile(
+        &mut self,
+        layer: usize,
+        x: usize,
+        y: usize,
+        tile: Option<usize>,
+    ) -> Result<()> {
+        if layer >= self.layers.len() || x >= self.width || y >= self.height {
+            return Err(Error::TilemapError(format!(
+                "cell ({x}, {y}) of layer {layer} is out of the map"
+            )));
+        }
+        if let Some(tile) = tile.filter(|tile| *tile >= self.tileset.len()) {
+            return Err(Error::TilemapError(format!(
+                "tile {tile} is not in the tileset of {} frames",
+                self.tileset.len()
+            )));
+        }
+
+        let cell = y * self.width + x;
+        if self.layers[layer].tiles[cell] != tile {
+            self.layers[layer].tiles[cell] = tile;
+            let slot = layer * self.width * self.height + cell;
+            self.write_slot(slot);
+            self.changed.push(slot);
+        }
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn get_tile(&self, layer: usize, x: usize, y: usize) -> Option<usize> {
+        self.layers.get(layer)?.get_tile(x, y)
+    }
+
+    #[must_use]
+    pub fn get_layers(&self) -> &[Layer] {
+        &self.layers
+    }
+
+    #[must_use]
+    pub fn get_tileset(&self) -> &Rc<Atlas> {
+        &self.tileset
+    }
+
+    /// Width and height of the map in tiles.
+    #[must_use]
+    pub fn get_size(&self) -> [usize; 2] {
+        [self.width, self.height]
+    }
+
+    #[must_use]
+    pub fn get_tile_size(&self) -> [f32; 2] {
+        self.tile_size
+    }
+
+    /// Cell under a point in the map's own space, `None` outside the map.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn get_cell_at(&self, x: f32, y: f32) -> Option<(usize, usize)> {
+        let column = (x / self.tile_size[0]).floor();
+        let row = (-y / self.tile_size[1]).floor();
+        if column < 0.0 || row < 0.0 {
+            return None;
+        }
+        let (column, row) = (column as usize, row as usize);
+        (column < self.width && row < self.height).then_some((column, row))
+    }
+
+    /// Whether a tile changed since the last `take_changed_vertices`.
+    pub(crate) fn is_changed(&self) -> bool {
+        !self.changed.is_empty()
+    }
+
+    /// Vertices rewritten since the last call, neighbouring cells merged into one range.
+    pub(crate) fn take_changed_vertices(&mut self) -> Vec<Range<usize>> {
+        let mut slots = std::mem::take(&mut self.changed);
+        slots.sort_unstable();
+        slots.dedup();
+
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for slot in slots {
+            let vertices = slot * 4..slot * 4 + 4;
+            match ranges.last_mut() {
+                Some(last) if last.end == vertices.start => last.end = vertices.end,
+                _ => ranges.push(vertices),
+            }
+        }
+        ranges
+    }
+
+    fn build_mesh(&mut self) {
+        let slots = self.layers.len() * self.width * self.height;
+        self.vertices = vec![0.0; slots * 12];
+        self.texture_coords = vec![0.0; slots * 8];
+        self.normals = [0.0, 0.0, 1.0].repeat(slots * 4);
+        self.indices = (0..slots)
+            .flat_map(|slot| {
+                [0, 1, 2, 0, 2, 3].map(|index| u32::try_from(slot * 4 + index).unwrap_or(u32::MAX))
+            })
+            .collect();
+        (0..slots).for_each(|slot| self.write_slot(slot));
+    }
+
+    /// An empty cell keeps its quad folded into one point, so it covers nothing.
+    #[allow(clippy::cast_precision_loss)]
+    fn write_slot(&mut self, slot: usize) {
+        let cells = self.width * self.height;
+        let (layer, cell) = (slot / cells, slot % cells);
+        let (x, y) = (cell % self.width, cell / self.width);
+        let [tile_width, tile_height] = self.tile_size;
+        let left = x as f32 * tile_width;
+        let top = -(y as f32) * tile_height;
+
+        let (vertices, texture_coords) = match self.layers[layer].tiles[cell] {
+            Some(tile) => {
+                let (right, bottom) = (left + tile_width, top - tile_height);
+                let [u_left, v_bottom, u_right, v_top] = self.tileset.get_texture_rect(tile);
+                (
+                    [
+                        left, bottom, 0.0, right, bottom, 0.0, right, top, 0.0, left, top, 0.0,
+                    ],
+                    [
+                        u_left, v_bottom, u_right, v_bottom, u_right, v_top, u_left, v_top,
+                    ],
+                )
+            }
+            None => (
+                [
+                    left, top, 0.0, left, top, 0.0, left, top, 0.0, left, top, 0.0,
+                ],
+                [0.0; 8],
+            ),
+        };
+        self.vertices[slot * 12..slot * 12 + 12].copy_from_slice(&vertices);
+        self.texture_coords[slot * 8..slot * 8 + 8].copy_from_slice(&texture_coords);
+    }
+}
+
+impl Shape for Tilemap {
+    fn get_vertices(&self) -> &[f32] {
+        &self.vertices
+    }
+
+    fn get_indices(&self) -> Option<&[u32]> {
+        Some(&self.indices)
+    }
+
+    fn get_normals(&self) -> Option<&[f32]> {
+        Some(&self.normals)
+    }
+
+    fn get_texture_coords(&self) -> Option<&[f32]> {
+        Some(&self.texture_coords)
+    }
+
+    fn get_type(&self) -> ShapeType {
+        ShapeType::Tilemap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::texture::Texture;
+    use image::{DynamicImage, RgbaImage};
+
+    fn create_tileset() -> Rc<Atlas> {
+        //two tiles of 2x2 pixels next to each other
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(4, 2));
+        let texture = Texture::new(Rc::new(image), Texture::default().get_config().clone());
+        Rc::new(Atlas::from_grid(texture, 2, 2).unwrap())
+    }
+
+    fn create_tilemap() -> Tilemap {
+        let ground = Layer::from_rows(&[vec![Some(0), Some(0)], vec![Some(1), None]]).unwrap();
+        Tilemap::new(create_tileset(), 1.0, 1.0, vec![ground, Layer::new(2, 2)]).unwrap()
+    }
+
+    #[test]
+    fn test_tilemap_mesh() {
+        let tilemap = create_tilemap();
+
+        //one quad per cell and layer
+        assert_eq!(tilemap.get_vertices().len(), 2 * 4 * 4 * 3);
+        assert_eq!(tilemap.get_indices().unwrap().len(), 2 * 4 * 6);
+        //the second row goes down
+        assert_eq!(
+            tilemap.get_vertices()[24..36],
+            [0.0, -2.0, 0.0, 1.0, -2.0, 0.0, 1.0, -1.0, 0.0, 0.0, -1.0, 0.0]
+        );
+        assert_eq!(
+            tilemap.get_texture_coords().unwrap()[16..24],
+            [0.5, 0.0, 1.0, 0.0, 1.0, 1.0, 0.5, 1.0]
+        );
+        //the empty cell has no area
+        assert_eq!(tilemap.get_vertices()[36..48], [1.0, -1.0, 0.0].repeat(4));
+        assert_eq!(tilemap.get_tile(0, 0, 1), Some(1));
+        assert_eq!(tilemap.get_tile(1, 0, 1), None);
+    }
+
+    #[test]
+    fn test_tilemap_set_tile_changes_only_its_vertices() {
+        let mut tilemap = create_tilemap();
+        let before = tilemap.get_vertices().to_vec();
+
+        tilemap.set_tile(1, 1, 0, Some(1)).unwrap();
+        tilemap.set_tile(1, 1, 1, Some(0)).unwrap();
+        //the same tile again is not a change
+        tilemap.set_tile(0, 0, 0, Some(0)).unwrap();
+        assert_eq!(tilemap.take_changed_vertices(), [20..24, 28..32]);
+        assert!(tilemap.take_changed_vertices().is_empty());
+
+        tilemap.set_tile(1, 0, 1, Some(0)).unwrap();
+        tilemap.set_tile(1, 1, 1, None).unwrap();
+        //neighbouring cells are written at once
+        let ranges = tilemap.take_changed_vertices();
+        assert_eq!(ranges.len(), 1);
+        assert_eq!(ranges[0], 24..32);
+        assert_eq!(tilemap.get_vertices()[..60], before[..60]);
+        assert_eq!(tilemap.get_tile(1, 1, 0), Some(1));
+    }
+
+    #[test]
+    fn test_tilemap_errors() {
+        let mut tilemap = create_tilemap();
+
+        assert!(tilemap.set_tile(2, 0, 0, Some(0)).is_err());
+        assert!(tilemap.set_tile(0, 2, 0, Some(0)).is_err());
+        assert!(tilemap.set_tile(0, 0, 0, Some(2)).is_err());
+        assert!(Layer::from_rows(&[vec![None], vec![None, None]]).is_err());
+        assert!(Tilemap::new(
+            create_tileset(),
+            1.0,
+            1.0,
+            vec![Layer::new(2, 2), Layer::new(1, 2)]
+        )
+        .is_err());
+        let unknown = Layer::from_rows(&[vec![Some(5)]]).unwrap();
+        assert!(Tilemap::new(create_tileset(), 1.0, 1.0, vec![unknown]).is_err());
+    }
+
+    #[test]
+    fn test_tilemap_cell_at() {
+        let tilemap = create_tilemap();
+
+        assert_eq!(tilemap.get_cell_at(1.5, -0.5), Some((1, 0)));
+        assert_eq!(tilemap.get_cell_at(0.5, -1.5), Some((0, 1)));
+        assert_eq!(tilemap.get_cell_at(0.5, 0.5), None);
+        assert_eq!(tilemap.get_cell_at(2.5, -0.5), None);
+    }
+}
@@ -85,6 +85,11 @@ impl Transformer {
         Self { matrix }
     }
 
+    #[must_use]
+    pub fn new_from_matrix(matrix: Matrix4<f32>) -> Self {
+        Self { matrix }
+    }
+
     #[must_use]
     pub fn new_translate(translation: Vector3<f32>) -> Self {
         Self {
@@ -1,12 +1,22 @@
 use std::mem;
+use std::ops::Range;
 use std::{collections::HashMap, rc::Rc};
 
+use cgmath::{Matrix4, SquareMatrix};
+
 use crate::common::IdGarbageCollector;
 use crate::components::color::Color;
+use crate::components::geometry::bounds::Bounds;
+use crate::components::instances::Instances;
 use crate::components::light::Light;
+use crate::components::lod::Lod;
 use crate::components::material::Material;
+use crate::components::particles::Emitter;
 use crate::components::shaders::ShaderBase;
+use crate::components::sprite::Sprite;
+use crate::components::text::Text;
 use crate::components::texture::Texture;
+use crate::components::tilemap::Tilemap;
 use crate::components::transformer::Transformer;
 use crate::components::{shaders::ShaderSource, Component, Shape};
 pub type ID = u32;
@@ -28,6 +38,13 @@ pub struct Manager {
     transformers: HashMap<ID, Transformer>,
     lights: HashMap<ID, Light>,
     materials: HashMap<ID, Material>,
+    lods: HashMap<ID, Lod>,
+    instances: HashMap<ID, Instances>,
+    particles: HashMap<ID, Box<Emitter>>,
+    texts: HashMap<ID, Box<Text>>,
+    sprites: HashMap<ID, Box<Sprite>>,
+    tilemaps: HashMap<ID, Box<Tilemap>>,
+    bounds: HashMap<ID, Bounds>,
     id_gc: IdGarbageCollector,
     shader_base: ShaderBase,
 }
@@ -42,6 +59,11 @@ pub struct View<'a> {
     pub transformer: Option<&'a Transformer>,
     pub light: Option<&'a Light>,
     pub material: Option<&'a Material>,
+    pub instances: Option<&'a Instances>,
+    pub particles: Option<&'a Emitter>,
+    pub text: Option<&'a Text>,
+    pub sprite: Option<&'a Sprite>,
+    pub tilemap: Option<&'a Tilemap>,
 }
 
 impl Entity {
@@ -88,6 +110,34 @@ impl Entity {
             .any(|component| matches!(component, Component::Material(_)))
     }
 
+    #[must_use]
+    pub fn has_particles(&self) -> bool {
+        self.components
+            .iter()
+            .any(|component| matches!(component, Component::Particles(_)))
+    }
+
+    #[must_use]
+    pub fn has_text(&self) -> bool {
+        self.components
+            .iter()
+            .any(|component| matches!(component, Component::Text(_)))
+    }
+
+    #[must_use]
+    pub fn has_sprite(&self) -> bool {
+        self.components
+            .iter()
+            .any(|component| matches!(component, Component::Sprite(_)))
+    }
+
+    #[must_use]
+    pub fn has_tilemap(&self) -> bool {
+        self.components
+            .iter()
+            .any(|component| matches!(component, Component::Tilemap(_)))
+    }
+
     #[must_use]
     pub fn get_light(&self) -> Option<&Light> {
         self.components
@@ -138,8 +188,43 @@ impl Manager {
                 Component::Material(material) => {
                     self.materials.insert(id, material);
                 }
+
+                Component::Lod(mut lod) => {
+                    lod.assign_render_ids(|| self.id_gc.create_id());
+                    self.lods.insert(id, lod);
+                }
+
+                Component::Instances(instances) => {
+                    self.instances.insert(id, instances);
+                }
+
+                Component::Particles(emitter) => {
+                    self.particles.insert(id, emitter);
+                }
+
+                //the glyphs are drawn from the font atlas
+                Component::Text(text) => {
+                    let atlas = text.get_font().get_texture();
+                    self.textures.entry(id).or_default().push(atlas);
+                    self.texts.insert(id, text);
+                }
+
+                //the frames are drawn from the atlas texture
+                Component::Sprite(sprite) => {
+                    let atlas = sprite.get_atlas().get_texture().clone();
+                    self.textures.entry(id).or_default().push(atlas);
+                    self.sprites.insert(id, sprite);
+                }
+
+                //the tiles are drawn from the tileset texture
+                Component::Tilemap(tilemap) => {
+                    let tileset = tilemap.get_tileset().get_texture().clone();
+                    self.textures.entry(id).or_default().push(tileset);
+                    self.tilemaps.insert(id, tilemap);
+                }
             }
         }
+        self.refresh_bounds(id);
 
         id
     }
@@ -163,28 +248,70 @@ impl Manager {
         self.colors.remove(&id);
         self.shapes.remove(&id);
         self.shaders_source.remove(&id);
+        self.instances.remove(&id);
+        self.particles.remove(&id);
+        self.texts.remove(&id);
+        self.sprites.remove(&id);
+        self.tilemaps.remove(&id);
+        self.bounds.remove(&id);
+        if let Some(lod) = self.lods.remove(&id) {
+            lod.get_render_ids()
+                .for_each(|render_id| self.id_gc.remove_id(render_id));
+        }
         self.id_gc.remove_id(id);
     }
 
     #[must_use]
     pub fn get_keys(&self) -> Vec<ID> {
-        self.shapes.keys().copied().collect()
+        self.shapes
+            .keys()
+            .chain(
+                self.particles
+                    .keys()
+                    .filter(|key| !self.shapes.contains_key(key)),
+            )
+            .chain(
+                self.texts
+                    .keys()
+                    .filter(|key| !self.shapes.contains_key(key)),
+            )
+            .chain(
+                self.sprites
+                    .keys()
+                    .filter(|key| !self.shapes.contains_key(key)),
+            )
+            .chain(
+                self.tilemaps
+                    .keys()
+                    .filter(|key| !self.shapes.contains_key(key)),
+            )
+            .copied()
+            .collect()
+    }
+
+    /// Keys in the order the entities are drawn. Blended entities are mixed with what is behind them,
+    /// so they come after the opaque ones, and the screen space texts are drawn over everything.
+    #[must_use]
+    pub fn get_keys_in_draw_order(&self) -> Vec<ID> {
+        let mut keys = self.get_keys();
+        keys.sort_by_key(|key| (self.is_blended(*key), self.is_screen_text(*key)));
+        keys
     }
 
+    /// With a `Lod` component the view holds the geometry of the current level
+    /// and the ID under which the renderer keeps it. A particle emitter, a text, a sprite or a tilemap is the geometry of its entity.
     #[must_use]
     pub fn as_ref_entity(&self, key: ID) -> View {
-        let mut shape = None;
-        if let Some(value) = self.shapes.get(&key) {
-            shape = Some(value.as_ref());
-        }
+        let shape = self.get_drawn_shape(key);
+        let render_id = self.get_render_id(key);
 
         let mut shader = None;
         if let Some(value) = self.shaders_source.get(&key) {
             shader = Some(value.clone());
         }
 
-        View::new(
-            key,
+        let mut view = View::new(
+            render_id,
             self.colors.get(&key),
             shape,
             shader,
@@ -192,13 +319,234 @@ impl Manager {
             self.transformers.get(&key),
             self.lights.get(&key),
             self.materials.get(&key),
-        )
+        );
+        view.instances = self.instances.get(&key);
+        view.particles = self.particles.get(&key).map(AsRef::as_ref);
+        view.text = self.texts.get(&key).map(AsRef::as_ref);
+        view.sprite = self.sprites.get(&key).map(AsRef::as_ref);
+        view.tilemap = self.tilemaps.get(&key).map(AsRef::as_ref);
+        view
     }
 
     #[must_use]
     pub fn get_shape(&self, key: ID) -> Option<&dyn Shape> {
         self.shapes.get(&key).map(std::convert::AsRef::as_ref)
     }
+
+    /// Geometry the entity is drawn with: the current level of detail, a tilemap, a sprite,
+    /// a text or a particle emitter before the `Geometry` component.
+    #[must_use]
+    pub fn get_drawn_shape(&self, key: ID) -> Option<&dyn Shape> {
+        if let Some((level_shape, _)) = self.lods.get(&key).and_then(Lod::get_current) {
+            return Some(level_shape);
+        }
+        if let Some(tilemap) = self.tilemaps.get(&key) {
+            return Some(tilemap.as_ref());
+        }
+        if let Some(sprite) = self.sprites.get(&key) {
+            return Some(sprite.as_ref());
+        }
+        if let Some(text) = self.texts.get(&key) {
+            return Some(text.as_ref());
+        }
+        if let Some(emitter) = self.particles.get(&key) {
+            return Some(emitter.as_ref());
+        }
+        self.get_shape(key)
+    }
+
+    /// Moves every particle emitter forward by the time step.
+    pub fn update_particles(&mut self, delta_time: f32) {
+        self.particles
+            .values_mut()
+            .for_each(|emitter| emitter.update(delta_time));
+        let keys: Vec<ID> = self.particles.keys().copied().collect();
+        for key in keys {
+            self.refresh_bounds(key);
+        }
+    }
+
+    #[must_use]
+    pub fn has_particles(&self, key: ID) -> bool {
+        self.particles.contains_key(&key)
+    }
+
+    #[must_use]
+    pub fn has_text(&self, key: ID) -> bool {
+        self.texts.contains_key(&key)
+    }
+
+    #[must_use]
+    pub fn is_screen_text(&self, key: ID) -> bool {
+        self.texts
+            .get(&key)
+            .is_some_and(|text| text.is_screen_space())
+    }
+
+    #[must_use]
+    pub fn get_text_mut(&mut self, key: ID) -> Option<&mut Text> {
+        self.texts.get_mut(&key).map(AsMut::as_mut)
+    }
+
+    /// Whether the text content changed since the last call, the renderer then rebuilds its geometry.
+    pub fn take_text_changed(&mut self, key: ID) -> bool {
+        self.texts
+            .get_mut(&key)
+            .is_some_and(|text| text.take_changed())
+    }
+
+    /// Moves every sprite animation forward by the time step.
+    pub fn update_sprites(&mut self, delta_time: f32) {
+        self.sprites
+            .values_mut()
+            .for_each(|sprite| sprite.update(delta_time));
+    }
+
+    #[must_use]
+    pub fn has_sprite(&self, key: ID) -> bool {
+        self.sprites.contains_key(&key)
+    }
+
+    #[must_use]
+    pub fn get_sprite_mut(&mut self, key: ID) -> Option<&mut Sprite> {
+        self.sprites.get_mut(&key).map(AsMut::as_mut)
+    }
+
+    /// Whether the sprite frame or flip changed since the last call, the renderer then rebuilds its geometry.
+    pub fn take_sprite_changed(&mut self, key: ID) -> bool {
+        self.sprites
+            .get_mut(&key)
+            .is_some_and(|sprite| sprite.take_changed())
+    }
+
+    #[must_use]
+    pub fn has_tilemap(&self, key: ID) -> bool {
+        self.tilemaps.contains_key(&key)
+    }
+
+    #[must_use]
+    pub fn get_tilemap_mut(&mut self, key: ID) -> Option<&mut Tilemap> {
+        self.tilemaps.get_mut(&key).map(AsMut::as_mut)
+    }
+
+    /// Vertices of the edited tiles since the last call, the renderer rewrites only them.
+    pub fn take_tilemap_changes(&mut self, key: ID) -> Vec<Range<usize>> {
+        self.tilemaps
+            .get_mut(&key)
+            .map(|tilemap| tilemap.take_changed_vertices())
+            .unwrap_or_default()
+    }
+
+    /// Whether the entity is mixed with what is behind it, so it has to be drawn after the opaque ones.
+    #[must_use]
+    pub fn is_blended(&self, key: ID) -> bool {
+        self.particles.contains_key(&key)
+            || self.texts.contains_key(&key)
+            || self.sprites.contains_key(&key)
+            || self.tilemaps.contains_key(&key)
+    }
+
+    /// Chooses the level of detail for the distance to the camera, nothing happens without a `Lod` component.
+    pub fn update_lod(&mut self, key: ID, distance: f32) {
+        if let Some(lod) = self.lods.get_mut(&key) {
+            lod.update(distance);
+        }
+    }
+
+    /// ID under which the renderer keeps the current level of detail, the entity ID without a `Lod`.
+    #[must_use]
+    pub fn get_render_id(&self, key: ID) -> ID {
+        self.lods
+            .get(&key)
+            .and_then(Lod::get_current)
+            .map_or(key, |(_, render_id)| render_id)
+    }
+
+    /// Recomputes the bounds of the texts, sprites and tilemaps whose geometry changed since they were last drawn.
+    pub fn update_bounds(&mut self) {
+        let changed: Vec<ID> = self
+            .texts
+            .iter()
+            .filter(|(_, text)| text.is_changed())
+            .map(|(key, _)| *key)
+            .chain(
+                self.sprites
+                    .iter()
+                    .filter(|(_, sprite)| sprite.is_changed())
+                    .map(|(key, _)| *key),
+            )
+            .chain(
+                self.tilemaps
+                    .iter()
+                    .filter(|(_, tilemap)| tilemap.is_changed())
+                    .map(|(key, _)| *key),
+            )
+            .collect();
+        for key in changed {
+            self.refresh_bounds(key);
+        }
+    }
+
+    /// Bounds of the entity's shape before its transformation is applied.
+    /// They are kept from the last `update_particles` or `update_bounds`, not computed on every call.
+    #[must_use]
+    pub fn get_local_bounds(&self, key: ID) -> Option<Bounds> {
+        self.bounds.get(&key).copied()
+    }
+
+    fn refresh_bounds(&mut self, key: ID) {
+        match self.compute_local_bounds(key) {
+            Some(bounds) => self.bounds.insert(key, bounds),
+            None => self.bounds.remove(&key),
+        };
+    }
+
+    fn compute_local_bounds(&self, key: ID) -> Option<Bounds> {
+        if let Some(text) = self.texts.get(&key) {
+            return text.get_bounds();
+        }
+        if let Some(sprite) = self.sprites.get(&key) {
+            return sprite.get_bounds();
+        }
+        if let Some(tilemap) = self.tilemaps.get(&key) {
+            return tilemap.get_bounds();
+        }
+        match self.particles.get(&key) {
+            Some(emitter) => emitter.get_bounds(),
+            None => self.shapes.get(&key).and_then(|shape| shape.get_bounds()),
+        }
+    }
+
+    /// Bounds of the entity's shape moved by its transformer, the same as the local ones without it.
+    /// With an `Instances` component they enclose all instances. A screen space text has none.
+    #[must_use]
+    pub fn get_world_bounds(&self, key: ID) -> Option<Bounds> {
+        if self.is_screen_text(key) {
+            return None;
+        }
+        let bounds = self.get_local_bounds(key)?;
+        self.get_model_matrices(key)
+            .iter()
+            .map(|matrix| bounds.transform(matrix))
+            .reduce(|union, bounds| union.union(&bounds))
+    }
+
+    /// Matrix placing the entity's shape in the world, one for every instance with an `Instances` component.
+    #[must_use]
+    pub fn get_model_matrices(&self, key: ID) -> Vec<Matrix4<f32>> {
+        let model = self
+            .transformers
+            .get(&key)
+            .map_or_else(Matrix4::identity, Transformer::get_matrix);
+        match self.instances.get(&key) {
+            Some(instances) => instances
+                .get_transformers()
+                .iter()
+                .map(|transformer| model * transformer.get_matrix())
+                .collect(),
+            None => vec![model],
+        }
+    }
 }
 
 impl<'a> View<'a> {
@@ -223,6 +571,11 @@ impl<'a> View<'a> {
             transformer,
             light,
             material,
+            instances: None,
+            particles: None,
+            text: None,
+            sprite: None,
+            tilemap: None,
         }
     }
 }
@@ -231,6 +584,8 @@ impl<'a> View<'a> {
 mod tests {
     use super::*;
     use crate::components::geometry::plane::Triangle;
+    use crate::components::{lod, particles, sprite, text, tilemap, ShapeType};
+    use cgmath::Vector3;
 
     #[test]
     fn test_new_manager() {
@@ -432,4 +787,252 @@ mod tests {
         entity.add_component(Component::Material(Material::default()));
         assert!(entity.has_material());
     }
+
+    #[test]
+    fn test_entity_bounds() {
+        let mut entity_manager = Manager::default();
+        let vertices: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
+        let still = entity_manager.add_entity(Entity::new(vec![Component::Geometry(Box::new(
+            Triangle::new(vertices),
+        ))]));
+        let moved = entity_manager.add_entity(Entity::new(vec![
+            Component::Geometry(Box::new(Triangle::new(vertices))),
+            Component::Transformer(Transformer::new_translate(Vector3::new(2.0, 0.0, -1.0))),
+        ]));
+        let no_shape = entity_manager.add_entity(Entity::new(vec![Component::Color(Color::new(
+            255, 0, 0, 255_f32,
+        ))]));
+
+        let local = entity_manager.get_local_bounds(still).unwrap();
+        assert_eq!(local.aabb.min, Vector3::new(-0.5, -0.5, 0.0));
+        assert_eq!(local.aabb.max, Vector3::new(0.5, 0.5, 0.0));
+        assert_eq!(entity_manager.get_world_bounds(still), Some(local));
+
+        let world = entity_manager.get_world_bounds(moved).unwrap();
+        assert_eq!(entity_manager.get_local_bounds(moved), Some(local));
+        assert_eq!(world.aabb.min, Vector3::new(1.5, -0.5, -1.0));
+        assert_eq!(world.aabb.max, Vector3::new(2.5, 0.5, -1.0));
+        assert_eq!(world.sphere.center, Vector3::new(2.0, 0.0, -1.0));
+
+        assert!(entity_manager.get_world_bounds(no_shape).is_none());
+    }
+
+    #[test]
+    fn test_entity_particles() {
+        let mut entity_manager = Manager::default();
+        let emitter = particles::Builder::new()
+            .with_spawn_rate(10.0)
+            .with_seed(1)
+            .build()
+            .unwrap();
+        let id =
+            entity_manager.add_entity(Entity::new(vec![Component::Particles(Box::new(emitter))]));
+
+        assert_eq!(entity_manager.get_keys(), [id]);
+        assert!(entity_manager.has_particles(id));
+        assert!(entity_manager.get_local_bounds(id).is_none());
+
+        entity_manager.update_particles(0.5);
+        let view = entity_manager.as_ref_entity(id);
+        assert_eq!(view.particles.unwrap().len(), 5);
+        assert_eq!(view.shape.unwrap().get_type(), ShapeType::Particles);
+        assert!(entity_manager.get_world_bounds(id).is_some());
+
+        entity_manager.remove_entity(id);
+        assert!(entity_manager.get_keys().is_empty());
+    }
+
+    #[test]
+    fn test_entity_text() {
+        let mut entity_manager = Manager::default();
+        let path =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/fonts/DejaVuSans.ttf");
+        let font = text::Font::load(&path, 16.0).unwrap();
+        let screen = entity_manager.add_entity(Entity::new(vec![Component::Text(Box::new(
+            text::Builder::new(font.clone(), "FPS").build(),
+        ))]));
+        let world = entity_manager.add_entity(Entity::new(vec![Component::Text(Box::new(
+            text::Builder::new(font, "Label")
+                .with_space(text::Space::World { line_height: 1.0 })
+                .build(),
+        ))]));
+
+        let view = entity_manager.as_ref_entity(screen);
+        assert_eq!(view.shape.unwrap().get_type(), ShapeType::Text);
+        assert_eq!(view.texture.unwrap().len(), 1);
+        assert!(entity_manager.is_screen_text(screen));
+        assert!(entity_manager.get_world_bounds(screen).is_none());
+        assert!(entity_manager.get_world_bounds(world).is_some());
+
+        assert!(!entity_manager.take_text_changed(screen));
+        entity_manager
+            .get_text_mut(screen)
+            .unwrap()
+            .set_content("FPS: 60");
+        assert!(entity_manager.take_text_changed(screen));
+
+        let label = entity_manager.get_local_bounds(world).unwrap();
+        entity_manager
+            .get_text_mut(world)
+            .unwrap()
+            .set_content("Longer label");
+        assert_eq!(entity_manager.get_local_bounds(world), Some(label));
+        entity_manager.update_bounds();
+        let longer = entity_manager.get_local_bounds(world).unwrap();
+        assert!(longer.aabb.max.x > label.aabb.max.x);
+        assert!(entity_manager.take_text_changed(world));
+
+        entity_manager.remove_entity(screen);
+        assert_eq!(entity_manager.get_keys(), [world]);
+    }
+
+    #[test]
+    fn test_entity_sprite() {
+        let mut entity_manager = Manager::default();
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::new(4, 2));
+        let texture = Texture::new(Rc::new(image), Texture::default().get_config().clone());
+        let atlas = Rc::new(sprite::Atlas::from_grid(texture, 2, 2).unwrap());
+        let animation = sprite::Animation::new(vec![0, 1], 10.0, sprite::Playback::Loop).unwrap();
+        let walk = sprite::Builder::new(atlas)
+            .with_animation(animation)
+            .build()
+            .unwrap();
+        let id = entity_manager.add_entity(Entity::new(vec![Component::Sprite(Box::new(walk))]));
+
+        let view = entity_manager.as_ref_entity(id);
+        assert_eq!(view.shape.unwrap().get_type(), ShapeType::Sprite);
+        assert_eq!(view.texture.unwrap().len(), 1);
+        assert!(entity_manager.get_world_bounds(id).is_some());
+
+        entity_manager.update_sprites(0.1);
+        assert!(entity_manager.take_sprite_changed(id));
+        assert_eq!(
+            entity_manager.as_ref_entity(id).sprite.unwrap().get_frame(),
+            1
+        );
+
+        entity_manager.remove_entity(id);
+        assert!(entity_manager.get_keys().is_empty());
+    }
+
+    #[test]
+    fn test_entity_draw_order() {
+        let mut entity_manager = Manager::default();
+        let path =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/fonts/DejaVuSans.ttf");
+        let font = text::Font::load(&path, 16.0).unwrap();
+        let screen = entity_manager.add_entity(Entity::new(vec![Component::Text(Box::new(
+            text::Builder::new(font, "FPS").build(),
+        ))]));
+        let emitter = particles::Builder::new().build().unwrap();
+        let blended =
+            entity_manager.add_entity(Entity::new(vec![Component::Particles(Box::new(emitter))]));
+        let vertices: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
+        let opaque = entity_manager.add_entity(Entity::new(vec![Component::Geometry(Box::new(
+            Triangle::new(vertices),
+        ))]));
+
+        assert_eq!(
+            entity_manager.get_keys_in_draw_order(),
+            [opaque, blended, screen]
+        );
+    }
+
+    #[test]
+    fn test_entity_tilemap() {
+        let mut entity_manager = Manager::default();
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::new(4, 2));
+        let texture = Texture::new(Rc::new(image), Texture::default().get_config().clone());
+        let tileset = Rc::new(sprite::Atlas::from_grid(texture, 2, 2).unwrap());
+        let layer = tilemap::Layer::from_rows(&[vec![Some(0), None]]).unwrap();
+        let map = tilemap::Tilemap::new(tileset, 1.0, 1.0, vec![layer]).unwrap();
+        let id = entity_manager.add_entity(Entity::new(vec![Component::Tilemap(Box::new(map))]));
+
+        let view = entity_manager.as_ref_entity(id);
+        assert_eq!(view.shape.unwrap().get_type(), ShapeType::Tilemap);
+        assert_eq!(view.texture.unwrap().len(), 1);
+        assert!(entity_manager.is_blended(id));
+
+        let tilemap = entity_manager.get_tilemap_mut(id).unwrap();
+        tilemap.set_tile(0, 1, 0, Some(1)).unwrap();
+        let changes = entity_manager.take_tilemap_changes(id);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0], 4..8);
+        assert!(entity_manager.take_tilemap_changes(id).is_empty());
+
+        entity_manager.remove_entity(id);
+        assert!(entity_manager.get_keys().is_empty());
+    }
+
+    #[test]
+    fn test_entity_instances() {
+        let mut entity_manager = Manager::default();
+        let vertices: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
+        let instances = Instances::new(vec![
+            Transformer::new_translate(Vector3::new(-2.0, 0.0, 0.0)),
+            Transformer::new_translate(Vector3::new(2.0, 0.0, 0.0)),
+        ]);
+        let id = entity_manager.add_entity(Entity::new(vec![
+            Component::Geometry(Box::new(Triangle::new(vertices))),
+            Component::Transformer(Transformer::new_translate(Vector3::new(0.0, 0.0, -1.0))),
+            Component::Instances(instances),
+        ]));
+
+        let matrices = entity_manager.get_model_matrices(id);
+        assert_eq!(matrices.len(), 2);
+        assert_eq!(matrices[1].w.truncate(), Vector3::new(2.0, 0.0, -1.0));
+        assert_eq!(entity_manager.as_ref_entity(id).instances.unwrap().len(), 2);
+
+        let world = entity_manager.get_world_bounds(id).unwrap();
+        assert_eq!(world.aabb.min, Vector3::new(-2.5, -0.5, -1.0));
+        assert_eq!(world.aabb.max, Vector3::new(2.5, 0.5, -1.0));
+
+        entity_manager.remove_entity(id);
+        assert!(entity_manager.instances.is_empty());
+    }
+
+    #[test]
+    fn test_entity_lod() {
+        let mut entity_manager = Manager::default();
+        let vertices: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
+        let coarse: [f32; 9] = [-1.0, -1.0, 0.0, 1.0, -1.0, 0.0, 0.0, 1.0, 0.0];
+        let lod = lod::Builder::new()
+            .with_level(Box::new(Triangle::new(coarse)), 10.0)
+            .build()
+            .unwrap();
+        let id = entity_manager.add_entity(Entity::new(vec![
+            Component::Geometry(Box::new(Triangle::new(vertices))),
+            Component::Lod(lod),
+        ]));
+        let next_id = entity_manager.add_entity(Entity::new(vec![Component::Geometry(Box::new(
+            Triangle::new(vertices),
+        ))]));
+        //the level got its own ID
+        assert_eq!(next_id, id + 2);
+        assert_eq!(entity_manager.get_keys().len(), 2);
+
+        assert_eq!(entity_manager.get_render_id(id), id);
+        assert_eq!(
+            entity_manager
+                .as_ref_entity(id)
+                .shape
+                .unwrap()
+                .get_vertices(),
+            &vertices
+        );
+
+        entity_manager.update_lod(id, 20.0);
+        let view = entity_manager.as_ref_entity(id);
+        assert_eq!(view.entity_id, id + 1);
+        assert_eq!(view.shape.unwrap().get_vertices(), &coarse);
+        assert_eq!(entity_manager.get_render_id(id), id + 1);
+        //bounds and picking use the most detailed geometry
+        assert_eq!(
+            entity_manager.get_shape(id).unwrap().get_vertices(),
+            &vertices
+        );
+
+        entity_manager.update_lod(next_id, 20.0);
+        assert_eq!(entity_manager.get_render_id(next_id), next_id);
+    }
 }
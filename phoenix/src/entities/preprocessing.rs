@@ -6,7 +6,8 @@ use crate::{
         Component,
     },
     renderer::shaders::{
-        BASIC_SHAPES_FRAG, BASIC_SHAPES_VERT, LIGHT_SOURCE_FRAG, LIGHT_SOURCE_VERT,
+        BASIC_SHAPES_FRAG, BASIC_SHAPES_VERT, LIGHT_SOURCE_FRAG, LIGHT_SOURCE_VERT, PARTICLES_VERT,
+        TEXT_VERT,
     },
 };
 
@@ -27,6 +28,10 @@ pub fn preprocessing(mut entity: Entity) -> Entity {
 
     if is_light_source(&entity) {
         insert_light_source_shader(&mut entity);
+    } else if is_particles(&entity) {
+        insert_particles_shader(&mut entity);
+    } else if is_text(&entity) {
+        insert_text_shader(&mut entity);
     } else {
         insert_basic_shapes_shader(&mut entity);
     }
@@ -50,12 +55,28 @@ fn is_light_source(entity: &Entity) -> bool {
     entity.contains_component(&Component::Light(Light::default()))
 }
 
+fn is_particles(entity: &Entity) -> bool {
+    entity.has_particles()
+}
+
+fn is_text(entity: &Entity) -> bool {
+    entity.has_text()
+}
+
 fn insert_light_source_shader(entity: &mut Entity) {
     entity.add_component(Component::ShaderProgram(
         create_default_light_source_shader(),
     ));
 }
 
+fn insert_particles_shader(entity: &mut Entity) {
+    entity.add_component(Component::ShaderProgram(create_default_particles_shader()));
+}
+
+fn insert_text_shader(entity: &mut Entity) {
+    entity.add_component(Component::ShaderProgram(create_default_text_shader()));
+}
+
 fn insert_basic_shapes_shader(entity: &mut Entity) {
     entity.add_component(Component::ShaderProgram(create_default_basic_shader()));
 }
@@ -64,6 +85,14 @@ fn create_default_light_source_shader() -> ShaderSource {
     ShaderSource::new(LIGHT_SOURCE_VERT, LIGHT_SOURCE_FRAG)
 }
 
+fn create_default_particles_shader() -> ShaderSource {
+    ShaderSource::new(PARTICLES_VERT, BASIC_SHAPES_FRAG)
+}
+
+fn create_default_text_shader() -> ShaderSource {
+    ShaderSource::new(TEXT_VERT, BASIC_SHAPES_FRAG)
+}
+
 fn create_default_basic_shader() -> ShaderSource {
     ShaderSource::new(BASIC_SHAPES_VERT, BASIC_SHAPES_FRAG)
 }
@@ -73,8 +102,8 @@ mod tests {
     use super::preprocessing;
     use crate::{
         components::{
-            color::Color, geometry::plane::Triangle, light::Light, shaders::ShaderSource,
-            texture::Texture, Component,
+            color::Color, geometry::plane::Triangle, light::Light, particles,
+            shaders::ShaderSource, text, texture::Texture, Component,
         },
         entities::entity::Entity,
     };
@@ -110,6 +139,30 @@ mod tests {
         assert_eq!(3, result.len());
     }
 
+    #[test]
+    fn test_add_particles_shader_component() {
+        let emitter = particles::Builder::new().with_seed(1).build().unwrap();
+        let entity = Entity::new(vec![Component::Particles(Box::new(emitter))]);
+
+        let result = preprocessing(entity);
+        assert!(result.contains_component(&Component::ShaderProgram(ShaderSource::default())));
+        assert_eq!(2, result.len());
+    }
+
+    #[test]
+    fn test_add_text_shader_component() {
+        let path =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/fonts/DejaVuSans.ttf");
+        let font = text::Font::load(&path, 16.0).unwrap();
+        let entity = Entity::new(vec![Component::Text(Box::new(
+            text::Builder::new(font, "text").build(),
+        ))]);
+
+        let result = preprocessing(entity);
+        assert!(result.contains_component(&Component::ShaderProgram(ShaderSource::default())));
+        assert_eq!(2, result.len());
+    }
+
     #[test]
     fn test_add_default_shader_component() {
         let vertices: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
@@ -43,6 +43,7 @@ pub struct Event {
 pub struct Manager {
     control_binding: ControlBinding,
     world_events: WorldEvents,
+    pending_actions: Vec<Action>,
 }
 
 impl Manager {
@@ -51,6 +52,7 @@ impl Manager {
         Self {
             control_binding: ControlBinding::new(Box::new(GlfwUserInputHandler { window })),
             world_events: WorldEvents::default(),
+            pending_actions: Vec::new(),
         }
     }
 
@@ -70,8 +72,14 @@ impl Manager {
         self.world_events.add_high_priority(event);
     }
 
+    /// The action is processed together with the user input in the next `process_events` call.
+    pub fn push_action(&mut self, action: Action) {
+        self.pending_actions.push(action);
+    }
+
     pub fn process_events(&mut self) -> Vec<Action> {
-        let mut actions = self.process_user_input_callbacks();
+        let mut actions = std::mem::take(&mut self.pending_actions);
+        actions.append(&mut self.process_user_input_callbacks());
         self.process_world_events(&mut actions)
     }
 
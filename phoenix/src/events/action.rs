@@ -1,4 +1,5 @@
 use crate::components::color::RGBA;
+use crate::entities::entity::ID;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Action {
@@ -12,4 +13,8 @@ pub enum Action {
     CameraOrientation(f32, f32),
     ChangeBackgroundColor(RGBA),
     PrintFPS(),
+    /// Picks the nearest entity under the cursor position, in window coordinates.
+    PickEntity(f32, f32),
+    /// Sent by the scene after `PickEntity` hits an entity, to be used in event conditions.
+    EntityPicked(ID),
 }
@@ -2,14 +2,16 @@ use crate::window::Window;
 use std::{collections::HashMap, ffi::c_int, rc::Rc, sync::Mutex};
 
 use glfw_sys::glfw_bindings::{
-    glfwGetKey, glfwSetCursorPosCallback, glfwSetFramebufferSizeCallback, glfwSetKeyCallback,
-    glfwSetScrollCallback, GLFWwindow, GLFW_PRESS,
+    glfwGetCursorPos, glfwGetKey, glfwSetCursorPosCallback, glfwSetFramebufferSizeCallback,
+    glfwSetKeyCallback, glfwSetMouseButtonCallback, glfwSetScrollCallback, GLFWwindow,
+    GLFW_MOUSE_BUTTON_LEFT, GLFW_PRESS,
 };
 
 use super::{action::Action, UserInput};
 
 static SCROLL_INPUT: Mutex<Option<f32>> = Mutex::new(None);
 static CURSOR_POS_INPUT: Mutex<Option<(f32, f32)>> = Mutex::new(None);
+static LEFT_CLICK_INPUT: Mutex<Option<(f32, f32)>> = Mutex::new(None);
 
 pub struct GlfwUserInputHandler {
     pub window: Rc<Window>,
@@ -31,6 +33,8 @@ pub enum KeyState {
 pub enum MouseInput {
     Scroll,
     CursorPos,
+    /// Produces `Action::PickEntity` with the cursor position of the click.
+    LeftClick,
 }
 
 pub(super) struct ControlBinding {
@@ -57,6 +61,10 @@ impl UserInput for GlfwUserInputHandler {
             );
             glfwSetCursorPosCallback(self.window.get_raw_mut_window(), Some(cursor_pos_callback));
             glfwSetScrollCallback(self.window.get_raw_mut_window(), Some(scroll_callback));
+            glfwSetMouseButtonCallback(
+                self.window.get_raw_mut_window(),
+                Some(mouse_button_callback),
+            );
         }
     }
 
@@ -72,6 +80,7 @@ impl GlfwUserInputHandler {
             glfwSetFramebufferSizeCallback(window.get_raw_mut_window(), None);
             glfwSetCursorPosCallback(window.get_raw_mut_window(), None);
             glfwSetScrollCallback(window.get_raw_mut_window(), None);
+            glfwSetMouseButtonCallback(window.get_raw_mut_window(), None);
         }
     }
 }
@@ -140,6 +149,9 @@ impl ControlBinding {
         if let Some(action) = self.get_cursor_pos_data() {
             result.push(action);
         }
+        if let Some(action) = self.get_left_click_data() {
+            result.push(action);
+        }
 
         result
     }
@@ -176,6 +188,17 @@ impl ControlBinding {
         }
         None
     }
+
+    fn get_left_click_data(&self) -> Option<Action> {
+        if self.mouse_binding.contains_key(&MouseInput::LeftClick) {
+            if let Ok(mut data) = LEFT_CLICK_INPUT.try_lock() {
+                if let Some(value) = data.take() {
+                    return Some(Action::PickEntity(value.0, value.1));
+                }
+            }
+        }
+        None
+    }
 }
 
 impl Drop for GlfwUserInputHandler {
@@ -200,6 +223,26 @@ extern "C" fn cursor_pos_callback(_window: *mut GLFWwindow, xpos: f64, ypos: f64
     }
 }
 
+#[allow(clippy::cast_possible_truncation)]
+extern "C" fn mouse_button_callback(
+    window: *mut GLFWwindow,
+    button: c_int,
+    action: c_int,
+    _mods: c_int,
+) {
+    if button != GLFW_MOUSE_BUTTON_LEFT || action != GLFW_PRESS {
+        return;
+    }
+
+    let (mut xpos, mut ypos) = (0.0, 0.0);
+    unsafe {
+        glfwGetCursorPos(window, &mut xpos, &mut ypos);
+    }
+    if let Ok(mut data) = LEFT_CLICK_INPUT.lock() {
+        *data = Some((xpos as f32, ypos as f32));
+    }
+}
+
 #[allow(clippy::cast_possible_truncation)]
 extern "C" fn scroll_callback(_window: *mut GLFWwindow, _xoffset: f64, yoffset: f64) {
     if let Ok(mut data) = SCROLL_INPUT.lock() {
@@ -223,7 +266,8 @@ mod tests {
     };
 
     use super::{
-        cursor_pos_callback, ControlBinding, KeyboardInput, CURSOR_POS_INPUT, SCROLL_INPUT,
+        cursor_pos_callback, ControlBinding, KeyboardInput, CURSOR_POS_INPUT, LEFT_CLICK_INPUT,
+        SCROLL_INPUT,
     };
 
     struct TestUserInput {
@@ -252,6 +296,12 @@ mod tests {
         }
     }
 
+    fn test_left_click_input_callback() {
+        if let Ok(mut data) = LEFT_CLICK_INPUT.try_lock() {
+            *data = Some((100.0, 50.0));
+        }
+    }
+
     fn test_cursor_pos_input_callback() {
         if let Ok(mut data) = CURSOR_POS_INPUT.try_lock() {
             *data = Some((2.0, 4.0));
@@ -324,6 +374,22 @@ mod tests {
         assert_eq!(actions, vec![Action::CameraFov(5.0)]);
     }
 
+    #[test]
+    fn test_control_binding_bind_left_click() {
+        let user_input_handler: Box<dyn UserInput> = Box::new(TestUserInput {
+            pressed_keys: vec![],
+        });
+        let mut control_binding = ControlBinding::new(user_input_handler);
+
+        control_binding.bind_mouse(MouseInput::LeftClick, Action::PickEntity(0.0, 0.0));
+
+        test_left_click_input_callback();
+
+        let actions = control_binding.collect_user_actions();
+        assert_eq!(actions, vec![Action::PickEntity(100.0, 50.0)]);
+        assert!(control_binding.collect_user_actions().is_empty());
+    }
+
     #[test]
     fn test_control_binding_bind_keys_and_mouse() {
         let user_input_handler: Box<dyn UserInput> = Box::new(TestUserInput {
@@ -2,6 +2,7 @@ pub mod opengl;
 pub(crate) mod shaders;
 pub mod vulkan;
 
+use std::ops::Range;
 use std::rc::Rc;
 
 use crate::{
@@ -29,6 +30,17 @@ pub enum Error {
     TransformationError(String),
 }
 
+/// Input of the entity ID pass used for picking. `x` and `y` are framebuffer pixels
+/// counted from the bottom left corner, the matrices are identities in scenes without a camera.
+pub struct IdPass<'a> {
+    /// Entities with their model matrices, drawn in this order.
+    pub entities: &'a [(ID, Matrix4<f32>)],
+    pub camera_pos: Matrix4<f32>,
+    pub projection: Matrix4<f32>,
+    pub x: i32,
+    pub y: i32,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Api {
     OpenGL,
@@ -47,6 +59,18 @@ pub trait Render {
     ///
     /// Will return `Err` when shader compilation failed.
     fn init_entity(&mut self, entity: &View) -> Result<ID>;
+    /// Replaces the geometry buffers of an initialized entity, for shapes changing every frame.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` when the new buffers cannot be created.
+    fn update_shape(&mut self, entity: &View) -> Result<()>;
+    /// Rewrites the attributes of the given vertices of an initialized entity, the rest of its buffers stays.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` when the vertices are out of the entity buffers.
+    fn update_shape_range(&mut self, entity: &View, vertices: Range<usize>) -> Result<()>;
     /// # Errors
     ///
     /// Will return `Err` when texture initialization failed.
@@ -97,6 +121,14 @@ pub trait Render {
         camera_position: &Vector3<f32>,
     ) -> Result<()>;
     fn draw_entity(&self, entity_id: ID);
+    /// Draws the entity IDs into an offscreen buffer and reads the pixel of the pass.
+    /// Returns the ID and the depth (from 0 at the near plane to 1 at the far plane)
+    /// of the entity covering the pixel. Only initialized entities are drawn.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` when the offscreen buffer cannot be created or read.
+    fn read_entity_id(&mut self, pass: &IdPass) -> Result<Option<(ID, f32)>>;
     fn enable_3d(&self);
     fn get_last_error_code(&self) -> Option<u32>;
 }
@@ -1,29 +1,34 @@
 use std::collections::HashMap;
 use std::ffi::{c_void, CString};
+use std::ops::Range;
 use std::rc::Rc;
 
 use cgmath::{Matrix4, Vector3};
 use common::{
-    set_uniform_bool, set_uniform_color, set_uniform_float, set_uniform_int, set_uniform_matrix4f,
-    set_uniform_vec3, unset_uniform_bool,
+    set_optional_uniform_bool, set_uniform_bool, set_uniform_color, set_uniform_float,
+    set_uniform_int, set_uniform_matrix4f, set_uniform_uint, set_uniform_vec3,
+    unset_optional_uniform_bool, unset_uniform_bool,
 };
 use glfw_sys::glfw_bindings;
+use id_buffer::IdBuffer;
 
-use super::{Api, Error, Render, ID};
-use crate::common::calculate_normal_vec_for_shape;
+use super::{Api, Error, IdPass, Render, ID};
+use crate::common::{calculate_normal_vec_for_shape, calculate_normals, unindex_attribute};
 use crate::components::color::{Color, RGBA};
+use crate::components::instances::Instances;
 use crate::components::light::Light;
 use crate::components::material::Material;
 use crate::components::shaders::ShaderSource;
 use crate::components::texture::Texture;
 use crate::components::transformer::Transformer;
-use crate::components::{FillMode, Shape, ShapeType};
+use crate::components::{Blending, FillMode, NormalMode, Shape};
 use crate::entities::entity::View;
 use crate::renderer::Result;
 use crate::window::Window;
 
 mod common;
 mod geometry_rendering;
+mod id_buffer;
 mod shader_compiler;
 mod textures;
 
@@ -36,24 +41,58 @@ pub struct OpenGL {
     shaders_id: HashMap<EntityID, ShaderID>,
     compiled_shaders: HashMap<Rc<ShaderSource>, ShaderID>,
     buffers: HashMap<EntityID, Buffers>,
-    shapes_type: HashMap<EntityID, ShapeType>,
     textures: HashMap<EntityID, Vec<TextureID>>,
-    shape_fill_mode: HashMap<EntityID, u32>,
+    shape_fill_mode: HashMap<EntityID, FillMode>,
+    /// Entities drawn with blending and without writing the depth, like particles, texts, sprites and tilemaps.
+    blending: HashMap<EntityID, Blending>,
+    id_buffer: Option<IdBuffer>,
+    /// Colors are decoded to linear light and the framebuffer encodes the output to sRGB.
+    gamma_correct: bool,
 }
 
 #[derive(Clone, Default)]
 struct Buffers {
     pub vertex_array_object: u32,
     pub vertex_buffer_object: u32,
-    pub indices: u16,
+    /// Zero when the shape is drawn without an index list.
+    pub element_buffer_object: u32,
+    pub indices: i32,
+    /// Zero when the shape is not instanced.
+    pub instance_buffer_object: u32,
+    pub instances: i32,
+    /// Bytes allocated for the vertex attributes, a changed shape reuses them while it fits.
+    pub vertex_capacity: usize,
+    /// Bytes allocated for the indices.
+    pub index_capacity: usize,
 }
 
 impl Buffers {
-    fn new(vertex_array_object: u32, vertex_buffer_object: u32, indices: u16) -> Buffers {
+    fn new(vertex_array_object: u32, vertex_buffer_object: u32) -> Buffers {
         Buffers {
             vertex_array_object,
             vertex_buffer_object,
-            indices,
+            ..Default::default()
+        }
+    }
+
+    fn is_indexed(&self) -> bool {
+        self.element_buffer_object != 0
+    }
+
+    fn is_instanced(&self) -> bool {
+        self.instance_buffer_object != 0
+    }
+
+    fn delete(&self) {
+        unsafe {
+            gl::DeleteVertexArrays(1, &self.vertex_array_object);
+            gl::DeleteBuffers(1, &self.vertex_buffer_object);
+            if self.is_indexed() {
+                gl::DeleteBuffers(1, &self.element_buffer_object);
+            }
+            if self.is_instanced() {
+                gl::DeleteBuffers(1, &self.instance_buffer_object);
+            }
         }
     }
 }
@@ -69,7 +108,8 @@ impl Render for OpenGL {
 
     fn set_background_color(&self, color: &RGBA) {
         unsafe {
-            let rgba = color.get_as_normalized_f32();
+            //cleared through the sRGB encoding like the drawn fragments
+            let rgba = self.encode_color(color);
             gl::ClearColor(rgba[0], rgba[1], rgba[2], rgba[3]);
             if gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE {
                 gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT);
@@ -91,14 +131,29 @@ impl Render for OpenGL {
         }
 
         if let Some(shape) = entity.shape {
-            let buffers = OpenGL::handle_shape(shape, entity.color, entity.texture)?;
+            let mut buffers = geometry_rendering::generate_buffers();
+            if let Err(err) =
+                OpenGL::handle_shape(&mut buffers, shape, entity.color, entity.texture)
+            {
+                buffers.delete();
+                return Err(err);
+            }
+            if let Some(instances) = entity.instances {
+                geometry_rendering::init_instances(&mut buffers, instances)?;
+            }
             self.buffers.insert(entity.entity_id, buffers);
 
-            self.shapes_type.insert(entity.entity_id, shape.get_type());
-            self.shape_fill_mode.insert(
-                entity.entity_id,
-                OpenGL::match_fill_mode(shape.get_fill_mode()),
-            );
+            self.shape_fill_mode
+                .insert(entity.entity_id, shape.get_fill_mode());
+        }
+
+        if let Some(emitter) = entity.particles {
+            self.blending
+                .insert(entity.entity_id, emitter.get_blending());
+        }
+
+        if entity.text.is_some() || entity.sprite.is_some() || entity.tilemap.is_some() {
+            self.blending.insert(entity.entity_id, Blending::Alpha);
         }
 
         if let Some(textures) = entity.texture {
@@ -115,6 +170,39 @@ impl Render for OpenGL {
         Ok(entity.entity_id)
     }
 
+    fn update_shape(&mut self, entity: &View) -> Result<()> {
+        let Some(shape) = entity.shape else {
+            return Ok(());
+        };
+        //the vertex array keeps the instance attributes, they do not change with the shape
+        match self.buffers.get_mut(&entity.entity_id) {
+            Some(buffers) => OpenGL::handle_shape(buffers, shape, entity.color, entity.texture),
+            None => self.init_entity(entity).map(|_| ()),
+        }
+    }
+
+    fn update_shape_range(&mut self, entity: &View, vertices: Range<usize>) -> Result<()> {
+        let (Some(shape), Some(buffers)) = (entity.shape, self.buffers.get(&entity.entity_id))
+        else {
+            return Ok(());
+        };
+        //without its own normals the shape may be unindexed when it is sent, so the layout differs
+        let Some(normals) = shape.get_normals() else {
+            return self.update_shape(entity);
+        };
+        let colors = Color::unpack_vertices(entity.color).or(shape.get_colors());
+        let texture_coords =
+            Texture::unpack_vertices(entity.texture).or(shape.get_texture_coords());
+        geometry_rendering::update_shape_range(
+            buffers,
+            shape.get_vertices(),
+            Some(normals),
+            colors,
+            texture_coords,
+            vertices,
+        )
+    }
+
     fn draw_entity(&self, entity_id: ID) {
         unsafe {
             if let Some(shader) = self.shaders_id.get(&entity_id) {
@@ -134,29 +222,51 @@ impl Render for OpenGL {
                     }
                 }
             }
+        }
 
-            if let Some(buffer) = self.buffers.get(&entity_id) {
-                gl::BindVertexArray(buffer.vertex_array_object);
-                if let Some(shape_type) = self.shapes_type.get(&entity_id) {
-                    let mode: u32 = *self
-                        .shape_fill_mode
-                        .get(&entity_id)
-                        .unwrap_or(&gl::TRIANGLES);
-                    match shape_type {
-                        ShapeType::Triangle | ShapeType::Cube => {
-                            gl::DrawArrays(mode, 0, buffer.indices.into());
-                        }
-                        ShapeType::Circle => gl::DrawArrays(mode, 1, i32::from(buffer.indices - 1)),
-
-                        ShapeType::Sphere => gl::DrawArrays(mode, 0, buffer.indices.into()),
-                    };
-                }
+        match self.blending.get(&entity_id) {
+            Some(blending) => {
+                enable_blending(*blending);
+                self.draw_buffers(entity_id);
+                disable_blending();
             }
+            None => self.draw_buffers(entity_id),
         }
     }
 
+    fn read_entity_id(&mut self, pass: &IdPass) -> Result<Option<(ID, f32)>> {
+        let mut viewport = [0; 4];
+        unsafe { gl::GetIntegerv(gl::VIEWPORT, viewport.as_mut_ptr()) };
+        let (width, height) = (viewport[2], viewport[3]);
+        if !(0..width).contains(&pass.x) || !(0..height).contains(&pass.y) {
+            return Ok(None);
+        }
+
+        //the buffer follows the size of the window
+        if self
+            .id_buffer
+            .as_ref()
+            .is_none_or(|id_buffer| id_buffer.get_size() != (width, height))
+        {
+            if let Some(id_buffer) = self.id_buffer.take() {
+                id_buffer.delete();
+            }
+            self.id_buffer = Some(IdBuffer::new(width, height)?);
+        }
+
+        let Some(id_buffer) = self.id_buffer.as_ref() else {
+            return Ok(None);
+        };
+        let depth_state = id_buffer.begin(pass.x, pass.y);
+        let drawn = self.draw_entity_ids(id_buffer.get_shader(), pass);
+        let (id, depth) = id_buffer.end(pass.x, pass.y, &depth_state)?;
+        drawn?;
+
+        Ok((id != 0).then_some((id, depth)))
+    }
+
     fn init_texture(&mut self, texture: &Texture) -> Result<ID> {
-        textures::init_texture(texture)
+        textures::init_texture(texture, self.gamma_correct)
     }
 
     fn perform_transformations(&self, entity_id: ID, transformation: &Transformer) -> Result<()> {
@@ -196,10 +306,10 @@ impl Render for OpenGL {
         if let Some(shader_id) = self.shaders_id.get(&entity.entity_id) {
             if entity.light.is_some() {
                 if let Some(rgba) = Color::unpack_rgba(entity.color) {
-                    return OpenGL::set_uniform_light_shader_variable(rgba, *shader_id);
+                    return set_uniform_color("color", self.encode_color(rgba), *shader_id);
                 }
             }
-            OpenGL::set_uniform_shader_variables(entity, *shader_id)?;
+            self.set_uniform_shader_variables(entity, *shader_id)?;
         }
 
         Ok(())
@@ -275,16 +385,78 @@ impl OpenGL {
     pub fn new(window: &Window) -> Result<Self> {
         window.set_current();
         OpenGL::load_gl_functions()?;
+        //the driver may ignore the request for an sRGB framebuffer, the output would be too dark
+        let gamma_correct = window.is_srgb_requested() && is_framebuffer_srgb();
+        if gamma_correct {
+            unsafe { gl::Enable(gl::FRAMEBUFFER_SRGB) };
+        }
         Ok(OpenGL {
             compiled_shaders: HashMap::new(),
             buffers: HashMap::new(),
             shaders_id: HashMap::new(),
             textures: HashMap::new(),
-            shapes_type: HashMap::new(),
             shape_fill_mode: HashMap::new(),
+            blending: HashMap::new(),
+            id_buffer: None,
+            gamma_correct,
         })
     }
 
+    /// Shaders light and blend in linear light, the framebuffer encodes the output to sRGB.
+    /// Only when the window asked for it with `GlfwConfig::create_srgb_window` and got it.
+    #[must_use]
+    pub fn is_gamma_correct(&self) -> bool {
+        self.gamma_correct
+    }
+
+    fn draw_buffers(&self, entity_id: ID) {
+        if let Some(buffer) = self.buffers.get(&entity_id) {
+            let fill_mode = self
+                .shape_fill_mode
+                .get(&entity_id)
+                .copied()
+                .unwrap_or_default();
+            let mode = OpenGL::match_fill_mode(fill_mode);
+            unsafe {
+                gl::BindVertexArray(buffer.vertex_array_object);
+                if let FillMode::Points { size } = fill_mode {
+                    gl::PointSize(size.max(1.0));
+                }
+
+                match (buffer.is_indexed(), buffer.is_instanced()) {
+                    (true, false) => {
+                        gl::DrawElements(mode, buffer.indices, gl::UNSIGNED_INT, std::ptr::null());
+                    }
+                    (false, false) => gl::DrawArrays(mode, 0, buffer.indices),
+                    (true, true) => gl::DrawElementsInstanced(
+                        mode,
+                        buffer.indices,
+                        gl::UNSIGNED_INT,
+                        std::ptr::null(),
+                        buffer.instances,
+                    ),
+                    (false, true) => {
+                        gl::DrawArraysInstanced(mode, 0, buffer.indices, buffer.instances);
+                    }
+                }
+            }
+        }
+    }
+
+    fn draw_entity_ids(&self, shader: ShaderID, pass: &IdPass) -> Result<()> {
+        set_uniform_matrix4f("camera_pos", &pass.camera_pos, shader)?;
+        set_uniform_matrix4f("projection", &pass.projection, shader)?;
+        for (entity_id, model) in pass.entities {
+            if let Some(buffers) = self.buffers.get(entity_id) {
+                set_uniform_int("is_instanced", i32::from(buffers.is_instanced()), shader)?;
+                set_uniform_matrix4f("model", model, shader)?;
+                set_uniform_uint("entity_id", *entity_id, shader)?;
+                self.draw_buffers(*entity_id);
+            }
+        }
+        Ok(())
+    }
+
     fn handle_shader(&mut self, shader: Rc<ShaderSource>) -> Result<ShaderID> {
         if let Some(val) = self.compiled_shaders.get(&shader) {
             return Ok(*val); //already compiled
@@ -297,41 +469,107 @@ impl OpenGL {
 
     fn match_fill_mode(mode: FillMode) -> u32 {
         match mode {
+            FillMode::Points { .. } => gl::POINTS,
             FillMode::Lines => gl::LINES,
+            FillMode::LineStrip => gl::LINE_STRIP,
+            FillMode::LineLoop => gl::LINE_LOOP,
             FillMode::Solid => gl::TRIANGLES,
+            FillMode::TriangleStrip => gl::TRIANGLE_STRIP,
             FillMode::Fan => gl::TRIANGLE_FAN,
         }
     }
 
     fn handle_shape(
+        buffers: &mut Buffers,
         shape: &dyn Shape,
         color: Option<&Color>,
         texture: Option<&Vec<Texture>>,
-    ) -> Result<Buffers> {
+    ) -> Result<()> {
+        let colors = Color::unpack_vertices(color).or(shape.get_colors());
+        let texture_coords = Texture::unpack_vertices(texture).or(shape.get_texture_coords());
+
+        if let Some(normals) = shape.get_normals() {
+            return geometry_rendering::upload_shape(
+                buffers,
+                shape.get_vertices(),
+                Some(normals),
+                colors,
+                texture_coords,
+                shape.get_indices(),
+            );
+        }
+
         //TODO move it to the data logic in the future
-        let normal_vectors = calculate_normal_vec_for_shape(shape);
-        geometry_rendering::init_shape(
-            shape.get_vertices(),
-            Some(&normal_vectors),
-            Color::unpack_vertices(color),
-            Texture::unpack_vertices(texture),
-        )
+        match (shape.get_indices(), shape.get_normal_mode()) {
+            (Some(indices), NormalMode::Flat | NormalMode::Winding)
+                if shape.get_fill_mode() == FillMode::Solid =>
+            {
+                //every face needs its own vertices to keep one normal per face
+                let vertices =
+                    unindex_attribute(shape.get_vertices(), indices, 3).ok_or_else(|| {
+                        Error::RenderingError("Shape index out of vertices range".to_string())
+                    })?;
+                let normals =
+                    calculate_normals(&vertices, None, FillMode::Solid, shape.get_normal_mode());
+                geometry_rendering::upload_shape(
+                    buffers,
+                    &vertices,
+                    Some(&normals),
+                    colors
+                        .and_then(|colors| unindex_attribute(colors, indices, 4))
+                        .as_deref(),
+                    texture_coords
+                        .and_then(|texture_coords| unindex_attribute(texture_coords, indices, 2))
+                        .as_deref(),
+                    None,
+                )
+            }
+            _ => geometry_rendering::upload_shape(
+                buffers,
+                shape.get_vertices(),
+                Some(&calculate_normal_vec_for_shape(shape)),
+                colors,
+                texture_coords,
+                shape.get_indices(),
+            ),
+        }
     }
 
-    fn set_uniform_light_shader_variable(color: &RGBA, shader_id: u32) -> Result<()> {
-        set_uniform_color("color", color, shader_id)
+    /// Color as the shaders expect it, in linear light when the output is gamma correct.
+    fn encode_color(&self, color: &RGBA) -> [f32; 4] {
+        if self.gamma_correct {
+            color.get_as_linear_f32()
+        } else {
+            color.get_as_normalized_f32()
+        }
     }
 
-    fn set_uniform_shader_variables(entity: &View, shader_id: u32) -> Result<()> {
+    fn set_uniform_shader_variables(&self, entity: &View, shader_id: u32) -> Result<()> {
         Self::reset_uniforms_shader_variables(shader_id)?;
+        if self.gamma_correct {
+            //custom shaders decode their vertex colors on their own
+            set_optional_uniform_bool("is_gamma_correct", shader_id)?;
+        }
         if let Some(color) = entity.color {
             if color.is_vertices() {
                 set_uniform_bool("is_color_vert", shader_id)?;
             } else if let Some(value) = color.as_ref_uniform() {
-                set_uniform_color("color", value, shader_id)?;
+                set_uniform_color("color", self.encode_color(value), shader_id)?;
             }
+        } else if entity
+            .shape
+            .is_some_and(|shape| shape.get_colors().is_some())
+        {
+            set_uniform_bool("is_color_vert", shader_id)?;
         } else {
-            set_uniform_color("color", &RGBA::new_white(), shader_id)?;
+            set_uniform_color("color", self.encode_color(&RGBA::new_white()), shader_id)?;
+        }
+
+        if entity.instances.is_some() {
+            set_uniform_bool("is_instanced", shader_id)?;
+            if entity.instances.and_then(Instances::get_colors).is_some() {
+                set_uniform_bool("is_instance_color", shader_id)?;
+            }
         }
 
         if let Some(textures) = entity.texture {
@@ -349,6 +587,10 @@ impl OpenGL {
         unset_uniform_bool("is_color_vert", shader_id)?;
         unset_uniform_bool("is_texture_vert", shader_id)?;
         unset_uniform_bool("is_multi_texture", shader_id)?;
+        //custom shaders without instancing support leave them out
+        unset_optional_uniform_bool("is_instanced", shader_id)?;
+        unset_optional_uniform_bool("is_instance_color", shader_id)?;
+        unset_optional_uniform_bool("is_gamma_correct", shader_id)?;
         Ok(())
     }
 
@@ -373,11 +615,51 @@ impl OpenGL {
     }
 }
 
+/// Encoding of the default framebuffer the window really got.
+fn is_framebuffer_srgb() -> bool {
+    let mut encoding = 0;
+    unsafe {
+        gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        gl::GetFramebufferAttachmentParameteriv(
+            gl::FRAMEBUFFER,
+            gl::BACK_LEFT,
+            gl::FRAMEBUFFER_ATTACHMENT_COLOR_ENCODING,
+            &mut encoding,
+        );
+    }
+    u32::try_from(encoding).is_ok_and(|encoding| encoding == gl::SRGB)
+}
+
+/// Blended fragments do not write the depth, so they do not hide each other.
+/// Points take their size from the vertex shader.
+fn enable_blending(blending: Blending) {
+    unsafe {
+        gl::Enable(gl::BLEND);
+        match blending {
+            Blending::Alpha => gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA),
+            Blending::Additive => gl::BlendFunc(gl::SRC_ALPHA, gl::ONE),
+        }
+        gl::DepthMask(gl::FALSE);
+        gl::Enable(gl::PROGRAM_POINT_SIZE);
+    }
+}
+
+fn disable_blending() {
+    unsafe {
+        gl::Disable(gl::PROGRAM_POINT_SIZE);
+        gl::DepthMask(gl::TRUE);
+        gl::Disable(gl::BLEND);
+    }
+}
+
 impl Drop for OpenGL {
     fn drop(&mut self) {
         self.shaders_id.iter().for_each(|(_, id)| unsafe {
             gl::DeleteProgram(*id);
         });
+        if let Some(id_buffer) = self.id_buffer.take() {
+            id_buffer.delete();
+        }
     }
 }
 
@@ -385,6 +667,8 @@ impl Drop for OpenGL {
 mod tests {
     use std::rc::Rc;
 
+    use cgmath::{Matrix4, SquareMatrix};
+
     use super::OpenGL;
     use crate::components::color::Color;
     use crate::renderer::shaders::BASIC_SHAPES_FRAG;
@@ -392,10 +676,32 @@ mod tests {
     use crate::{
         components::{geometry::plane::Triangle, shaders::ShaderSource},
         entities::entity::View,
-        renderer::{shaders::BASIC_SHAPES_VERT, Render},
+        renderer::{shaders::BASIC_SHAPES_VERT, IdPass, Render},
     };
     use serial_test::serial;
 
+    #[test]
+    #[serial]
+    fn test_opengl_gamma_correct() {
+        let config = GlfwConfig::create().unwrap();
+        let window = config
+            .create_window("test_opengl_linear", Resolution::default())
+            .unwrap();
+        assert!(!OpenGL::new(&window).unwrap().is_gamma_correct());
+        drop(window);
+
+        let window = config
+            .create_srgb_window("test_opengl_srgb", Resolution::default())
+            .unwrap();
+        let renderer = OpenGL::new(&window).unwrap();
+        //only when the driver gave an sRGB framebuffer
+        assert_eq!(renderer.is_gamma_correct(), super::is_framebuffer_srgb());
+        assert_eq!(
+            renderer.is_gamma_correct(),
+            unsafe { gl::IsEnabled(gl::FRAMEBUFFER_SRGB) } == gl::TRUE
+        );
+    }
+
     #[test]
     #[serial]
     fn test_opengl_init_entity() {
@@ -510,4 +816,48 @@ mod tests {
             renderer.shaders_id.get(&2).unwrap()
         );
     }
+
+    #[test]
+    #[serial]
+    fn test_opengl_read_entity_id() {
+        let config = GlfwConfig::create().unwrap();
+        let window = config
+            .create_window("test_opengl_read_entity_id", Resolution::default())
+            .unwrap();
+        window.set_current();
+
+        let vertices = Triangle::new([0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0]);
+        let shader = Rc::new(ShaderSource::new(BASIC_SHAPES_VERT, BASIC_SHAPES_FRAG));
+        let entity = View::new(
+            7,
+            None,
+            Some(&vertices),
+            Some(shader),
+            None,
+            None,
+            None,
+            None,
+        );
+
+        let mut renderer = OpenGL::new(&window).unwrap();
+        renderer.init_entity(&entity).unwrap();
+        let entities = [(7, Matrix4::identity())];
+        let mut pass = IdPass {
+            entities: &entities,
+            camera_pos: Matrix4::identity(),
+            projection: Matrix4::identity(),
+            //a quarter of the way from the middle to the top right corner
+            x: 500,
+            y: 375,
+        };
+
+        let (id, depth) = renderer.read_entity_id(&pass).unwrap().unwrap();
+        assert_eq!(id, 7);
+        assert!((depth - 0.5).abs() < 1e-3);
+
+        //the bottom left corner is not covered by the triangle
+        pass.x = 10;
+        pass.y = 10;
+        assert!(renderer.read_entity_id(&pass).unwrap().is_none());
+    }
 }
@@ -1,10 +1,7 @@
 use cgmath::{Array, Matrix, Matrix4, Vector3};
 use std::ffi::CString;
 
-use crate::{
-    components::color::RGBA,
-    renderer::{Error, Result},
-};
+use crate::renderer::{Error, Result};
 
 pub fn get_last_error_code(ignore_value_err: bool) -> Option<u32> {
     let error_code = unsafe { gl::GetError() };
@@ -24,6 +21,32 @@ pub fn unset_uniform_bool(variable_name: &str, shader_id: u32) -> Result<()> {
     Ok(())
 }
 
+/// Like `unset_uniform_bool`, but a shader without the variable is not an error.
+pub fn unset_optional_uniform_bool(variable_name: &str, shader_id: u32) -> Result<()> {
+    set_optional_uniform_int(variable_name, 0, shader_id)
+}
+
+/// Like `set_uniform_bool`, but a shader without the variable is not an error.
+pub fn set_optional_uniform_bool(variable_name: &str, shader_id: u32) -> Result<()> {
+    set_optional_uniform_int(variable_name, 1, shader_id)
+}
+
+fn set_optional_uniform_int(variable_name: &str, value: i32, shader_id: u32) -> Result<()> {
+    let name = CString::new(variable_name).map_err(|_| {
+        Error::RenderingError(
+            "Invalid variable name for uniform searching: ".to_string() + variable_name,
+        )
+    })?;
+    let location = unsafe { gl::GetUniformLocation(shader_id, name.as_ptr()) };
+    if location != -1 {
+        unsafe {
+            gl::UseProgram(shader_id);
+            gl::Uniform1i(location, value);
+        };
+    }
+    Ok(())
+}
+
 pub fn set_uniform_bool(variable_name: &str, shader_id: u32) -> Result<()> {
     let location = get_uniform_variable_location(shader_id, variable_name)?;
     unsafe {
@@ -40,6 +63,13 @@ pub fn set_uniform_int(variable_name: &str, value: i32, shader_id: u32) -> Resul
     Ok(())
 }
 
+pub fn set_uniform_uint(variable_name: &str, value: u32, shader_id: u32) -> Result<()> {
+    let location = get_uniform_variable_location(shader_id, variable_name)?;
+    unsafe { gl::UseProgram(shader_id) };
+    unsafe { gl::Uniform1ui(location, value) };
+    Ok(())
+}
+
 pub fn set_uniform_vec3(variable_name: &str, vector: &Vector3<f32>, shader_id: u32) -> Result<()> {
     let location = get_uniform_variable_location(shader_id, variable_name)?;
     unsafe { gl::UseProgram(shader_id) };
@@ -58,10 +88,10 @@ pub fn set_uniform_matrix4f(
     Ok(())
 }
 
-pub fn set_uniform_color(variable_name: &str, rgba: &RGBA, shader_id: u32) -> Result<()> {
+/// The color is already normalized, sRGB encoded or decoded to linear light by the caller.
+pub fn set_uniform_color(variable_name: &str, color: [f32; 4], shader_id: u32) -> Result<()> {
     let color_location = get_uniform_variable_location(shader_id, variable_name)?;
     unsafe { gl::UseProgram(shader_id) };
-    let color = rgba.get_as_normalized_f32();
     unsafe { gl::Uniform4f(color_location, color[0], color[1], color[2], color[3]) };
     Ok(())
 }
@@ -1,3 +1,6 @@
+use std::ops::Range;
+
+use crate::components::instances::Instances;
 use crate::renderer::{Error, Result};
 
 use super::common::get_last_error_code;
@@ -7,6 +10,9 @@ const POSITION_LAYOUT: u32 = 0;
 const NORMAL_LAYOUT: u32 = 1;
 const COLOR_LAYOUT: u32 = 2;
 const TEXTURE_LAYOUT: u32 = 3;
+/// A matrix takes four locations, one per column.
+const INSTANCE_MODEL_LAYOUT: u32 = 4;
+const INSTANCE_COLOR_LAYOUT: u32 = 8;
 
 #[derive(Debug)]
 struct VertexAttrPointerArgs {
@@ -16,18 +22,41 @@ struct VertexAttrPointerArgs {
     pub offset: usize,
 }
 
-pub fn init_shape(
+/// Sends the attributes of the shape into its buffers. The buffers of a changed shape are kept,
+/// reallocated only when the shape outgrows them and orphaned otherwise, so the frame still drawing them does not stall.
+pub fn upload_shape(
+    buffers: &mut Buffers,
     vertices: &[f32],
     normals: Option<&[f32]>,
     color: Option<&[f32]>,
     texture: Option<&[f32]>,
-) -> Result<Buffers> {
-    let buffers = generate_buffers(vertices.len());
-    bind_buffers(&buffers);
-
-    allocate_gpu_buffer(vertices, normals, color, texture);
+    indices: Option<&[u32]>,
+) -> Result<()> {
+    //a shape sent again is expected to change every frame, like the particles
+    let usage = if buffers.vertex_capacity == 0 {
+        gl::STATIC_DRAW
+    } else {
+        gl::DYNAMIC_DRAW
+    };
+    let count = indices.map_or(vertices.len() / 3, <[u32]>::len);
+    buffers.indices = i32::try_from(count).map_err(|_| {
+        Error::RenderingError(format!(
+            "Geometry rendering: too many vertices to draw {count}"
+        ))
+    })?;
+    bind_buffers(buffers);
+
+    buffers.vertex_capacity = buffers
+        .vertex_capacity
+        .max(attributes_size(vertices, normals, color, texture));
+    allocate_gpu_buffer(gl::ARRAY_BUFFER, buffers.vertex_capacity, usage);
     send_data_to_gpu_buffer(vertices, normals, color, texture);
+    send_indices_to_gpu_buffer(buffers, indices, usage);
 
+    //the offsets follow the attribute sizes, and an attribute the shape lost must not be read
+    for layout in [POSITION_LAYOUT, NORMAL_LAYOUT, COLOR_LAYOUT, TEXTURE_LAYOUT] {
+        unsafe { gl::DisableVertexAttribArray(layout) };
+    }
     create_vertex_attribute_pointer_argument_list(vertices, normals, color, texture)
         .into_iter()
         .for_each(|args| set_vertex_attribute_pointer(&args));
@@ -39,7 +68,112 @@ pub fn init_shape(
             "Geometry rendering: OpenGL error code {err_code}"
         )))
     } else {
-        Ok(buffers)
+        Ok(())
+    }
+}
+
+/// Adds a buffer with the per-instance attributes to the vertex array of the shape,
+/// they advance once per instance instead of once per vertex.
+pub fn init_instances(buffers: &mut Buffers, instances: &Instances) -> Result<()> {
+    let matrices = instances.unpack_matrices();
+    let colors = instances.unpack_colors();
+    buffers.instances = i32::try_from(instances.len()).map_err(|_| {
+        Error::RenderingError(format!(
+            "Geometry rendering: too many instances to draw {}",
+            instances.len()
+        ))
+    })?;
+
+    unsafe {
+        gl::BindVertexArray(buffers.vertex_array_object);
+        gl::GenBuffers(1, &mut buffers.instance_buffer_object);
+        gl::BindBuffer(gl::ARRAY_BUFFER, buffers.instance_buffer_object);
+    }
+
+    let colors = colors.as_deref();
+    allocate_gpu_buffer(
+        gl::ARRAY_BUFFER,
+        attributes_size(&matrices, None, colors, None),
+        gl::STATIC_DRAW,
+    );
+    send_data_to_gpu_buffer(&matrices, None, colors, None);
+
+    let mut args: Vec<VertexAttrPointerArgs> = (0..4)
+        .map(|column| VertexAttrPointerArgs {
+            layout: INSTANCE_MODEL_LAYOUT + column,
+            size: 4,
+            stride: 16,
+            offset: column as usize * 4 * std::mem::size_of::<f32>(),
+        })
+        .collect();
+    if colors.is_some() {
+        args.push(VertexAttrPointerArgs {
+            layout: INSTANCE_COLOR_LAYOUT,
+            size: 4,
+            stride: 4,
+            offset: std::mem::size_of_val(matrices.as_slice()),
+        });
+    }
+    for args in &args {
+        set_vertex_attribute_pointer(args);
+        unsafe { gl::VertexAttribDivisor(args.layout, 1) };
+    }
+
+    unbind_buffers();
+
+    if let Some(err_code) = get_last_error_code(true) {
+        Err(Error::RenderingError(format!(
+            "Instances rendering: OpenGL error code {err_code}"
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Rewrites the attributes of the given vertices in place. The attributes have to be the ones
+/// the buffers were created from, only their values may differ.
+pub fn update_shape_range(
+    buffers: &Buffers,
+    vertices: &[f32],
+    normals: Option<&[f32]>,
+    color: Option<&[f32]>,
+    texture: Option<&[f32]>,
+    range: Range<usize>,
+) -> Result<()> {
+    let attributes: Vec<(&[f32], usize)> =
+        [(Some(vertices), 3), (normals, 3), (color, 4), (texture, 2)]
+            .into_iter()
+            .filter_map(|(data, size)| data.map(|data| (data, size)))
+            .collect();
+    if range.start > range.end
+        || attributes
+            .iter()
+            .any(|(data, size)| range.end * size > data.len())
+    {
+        return Err(Error::RenderingError(format!(
+            "Geometry rendering: vertices {range:?} are out of the shape"
+        )));
+    }
+
+    unsafe { gl::BindBuffer(gl::ARRAY_BUFFER, buffers.vertex_buffer_object) };
+    //the attributes lie one after another, as in `send_data_to_gpu_buffer`
+    let mut offset = 0;
+    for (data, size) in attributes {
+        let start = std::mem::size_of_val(&data[..range.start * size]);
+        copy_buffer_to_gpu(
+            &data[range.start * size..range.end * size],
+            (offset + start).try_into().unwrap_or(0),
+        );
+        offset += std::mem::size_of_val(data);
+    }
+    unsafe { gl::BindBuffer(gl::ARRAY_BUFFER, 0) };
+
+    if let Some(err_code) = get_last_error_code(true) {
+        Err(Error::RenderingError(format!(
+            "Geometry rendering: OpenGL error code {err_code}"
+        )))
+    } else {
+        Ok(())
     }
 }
 
@@ -92,7 +226,8 @@ fn create_vertex_attribute_pointer_argument_list(
     result
 }
 
-fn generate_buffers(vertices_count: usize) -> Buffers {
+/// The element buffer is generated with the first index list.
+pub fn generate_buffers() -> Buffers {
     let mut vertex_array_object = 0;
     let mut vertex_buffer_object = 0;
     unsafe {
@@ -100,26 +235,59 @@ fn generate_buffers(vertices_count: usize) -> Buffers {
         gl::GenBuffers(1, &mut vertex_buffer_object);
     }
 
-    Buffers::new(
-        vertex_array_object,
-        vertex_buffer_object,
-        u16::try_from(vertices_count / 3).unwrap_or(0),
-    )
+    Buffers::new(vertex_array_object, vertex_buffer_object)
 }
 
 fn bind_buffers(buffers: &Buffers) {
     unsafe {
         gl::BindVertexArray(buffers.vertex_array_object);
         gl::BindBuffer(gl::ARRAY_BUFFER, buffers.vertex_buffer_object);
+        if buffers.is_indexed() {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, buffers.element_buffer_object);
+        }
     }
 }
 
-fn allocate_gpu_buffer(
+//The vertex array has to be bound, it keeps the element buffer
+fn send_indices_to_gpu_buffer(buffers: &mut Buffers, indices: Option<&[u32]>, usage: u32) {
+    match indices {
+        Some(indices) => unsafe {
+            if !buffers.is_indexed() {
+                gl::GenBuffers(1, &mut buffers.element_buffer_object);
+                gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, buffers.element_buffer_object);
+            }
+            let size = std::mem::size_of_val(indices);
+            buffers.index_capacity = buffers.index_capacity.max(size);
+            allocate_gpu_buffer(gl::ELEMENT_ARRAY_BUFFER, buffers.index_capacity, usage);
+            gl::BufferSubData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                0,
+                size.try_into().unwrap(),
+                indices.as_ptr().cast::<std::ffi::c_void>(),
+            );
+        },
+        None if buffers.is_indexed() => unsafe {
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, 0);
+            gl::DeleteBuffers(1, &buffers.element_buffer_object);
+            buffers.element_buffer_object = 0;
+            buffers.index_capacity = 0;
+        },
+        None => {}
+    }
+}
+
+fn allocate_gpu_buffer(target: u32, size: usize, usage: u32) {
+    unsafe {
+        gl::BufferData(target, size.try_into().unwrap(), std::ptr::null(), usage);
+    }
+}
+
+fn attributes_size(
     positions: &[f32],
     normals: Option<&[f32]>,
     color: Option<&[f32]>,
     texture: Option<&[f32]>,
-) {
+) -> usize {
     let mut size = std::mem::size_of_val(positions);
     if let Some(val) = normals {
         size += std::mem::size_of_val(val);
@@ -133,14 +301,7 @@ fn allocate_gpu_buffer(
         size += std::mem::size_of_val(val);
     }
 
-    unsafe {
-        gl::BufferData(
-            gl::ARRAY_BUFFER,
-            size.try_into().unwrap(),
-            std::ptr::null(),
-            gl::STATIC_DRAW,
-        );
-    }
+    size
 }
 
 fn send_data_to_gpu_buffer(
@@ -192,6 +353,7 @@ fn set_vertex_attribute_pointer(args: &VertexAttrPointerArgs) {
     }
 }
 
+//The element buffer binding is stored in the VAO, so it is left bound
 fn unbind_buffers() {
     unsafe {
         gl::BindBuffer(gl::ARRAY_BUFFER, 0);
@@ -201,9 +363,14 @@ fn unbind_buffers() {
 
 #[cfg(test)]
 mod tests {
+    use super::Buffers;
+    use crate::components::color::RGBA;
     use crate::components::geometry::solid::Cube;
+    use crate::components::instances::Instances;
+    use crate::components::transformer::Transformer;
     use crate::components::Shape;
     use crate::renderer::opengl::OpenGL;
+    use crate::renderer::Result;
     use crate::{
         testing::setup_opengl,
         window::{GlfwConfig, Resolution},
@@ -211,13 +378,25 @@ mod tests {
     use serial_test::serial;
     use std::rc::Rc;
 
+    fn init_shape(
+        vertices: &[f32],
+        normals: Option<&[f32]>,
+        color: Option<&[f32]>,
+        texture: Option<&[f32]>,
+        indices: Option<&[u32]>,
+    ) -> Result<Buffers> {
+        let mut buffers = super::generate_buffers();
+        super::upload_shape(&mut buffers, vertices, normals, color, texture, indices)?;
+        Ok(buffers)
+    }
+
     #[test]
     #[serial]
     fn test_init_shape() {
         setup_opengl!();
 
         let vertices = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
-        let buffers = super::init_shape(&vertices, None, None, None).unwrap();
+        let buffers = init_shape(&vertices, None, None, None, None).unwrap();
         assert_ne!(buffers.vertex_array_object, 0);
         assert_ne!(buffers.vertex_buffer_object, 0);
     }
@@ -229,7 +408,7 @@ mod tests {
 
         let vertices = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
         let texture = vec![0.0, 0.0, 1.0, 0.0, 0.5, 1.0];
-        let buffers = super::init_shape(&vertices, None, Some(&texture), None).unwrap();
+        let buffers = init_shape(&vertices, None, Some(&texture), None, None).unwrap();
         assert_ne!(buffers.vertex_array_object, 0);
         assert_ne!(buffers.vertex_buffer_object, 0);
     }
@@ -245,7 +424,7 @@ mod tests {
             0.5_f32, 0.5_f32, 0.5_f32,
         ];
 
-        let buffers = super::init_shape(&vertices, None, None, Some(&color)).unwrap();
+        let buffers = init_shape(&vertices, None, None, Some(&color), None).unwrap();
         assert_ne!(buffers.vertex_array_object, 0);
         assert_ne!(buffers.vertex_buffer_object, 0);
     }
@@ -258,9 +437,91 @@ mod tests {
         let cube = Cube::new(0.5, [0.0, 0.0, 0.0]);
         let texture = vec![];
 
-        let buffers = super::init_shape(cube.get_vertices(), None, None, Some(&texture)).unwrap();
+        let buffers = init_shape(
+            cube.get_vertices(),
+            None,
+            None,
+            Some(&texture),
+            cube.get_indices(),
+        )
+        .unwrap();
         assert_ne!(buffers.vertex_array_object, 0);
         assert_ne!(buffers.vertex_buffer_object, 0);
+        assert_ne!(buffers.element_buffer_object, 0);
+        assert_eq!(buffers.indices, 36);
+    }
+
+    #[test]
+    #[serial]
+    fn test_init_shape_without_indices_no_element_buffer() {
+        setup_opengl!();
+
+        let vertices = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let buffers = init_shape(&vertices, None, None, None, None).unwrap();
+        assert_eq!(buffers.element_buffer_object, 0);
+        assert_eq!(buffers.indices, 3);
+    }
+
+    #[test]
+    #[serial]
+    fn test_init_instances() {
+        setup_opengl!();
+
+        let vertices = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let instances = Instances::new_with_colors(
+            vec![Transformer::new_identity(), Transformer::new_identity()],
+            vec![RGBA::new_white(), RGBA::new_white()],
+        )
+        .unwrap();
+        let mut buffers = init_shape(&vertices, None, None, None, None).unwrap();
+        super::init_instances(&mut buffers, &instances).unwrap();
+        assert_ne!(buffers.instance_buffer_object, 0);
+        assert_eq!(buffers.instances, 2);
+    }
+
+    #[test]
+    #[serial]
+    fn test_upload_shape_reuses_buffers() {
+        setup_opengl!();
+
+        let triangle = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let mut buffers = init_shape(&triangle, None, None, None, None).unwrap();
+        let (vertex_array, vertex_buffer) =
+            (buffers.vertex_array_object, buffers.vertex_buffer_object);
+        assert_eq!(buffers.vertex_capacity, 36);
+
+        let quad = [triangle.clone(), vec![1.0, 1.0, 0.0]].concat();
+        let indices = [0, 1, 2, 1, 3, 2];
+        super::upload_shape(&mut buffers, &quad, None, None, None, Some(&indices)).unwrap();
+        assert_eq!(buffers.vertex_array_object, vertex_array);
+        assert_eq!(buffers.vertex_buffer_object, vertex_buffer);
+        assert_eq!(buffers.vertex_capacity, 48);
+        assert_ne!(buffers.element_buffer_object, 0);
+        assert_eq!(buffers.indices, 6);
+
+        super::upload_shape(&mut buffers, &triangle, None, None, None, None).unwrap();
+        assert_eq!(buffers.vertex_buffer_object, vertex_buffer);
+        assert_eq!(buffers.vertex_capacity, 48);
+        assert_eq!(buffers.element_buffer_object, 0);
+        assert_eq!(buffers.indices, 3);
+    }
+
+    #[test]
+    #[serial]
+    fn test_update_shape_range() {
+        setup_opengl!();
+
+        let vertices = vec![0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+        let texture = vec![0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+        let buffers = init_shape(&vertices, None, None, Some(&texture), None).unwrap();
+        assert!(
+            super::update_shape_range(&buffers, &vertices, None, None, Some(&texture), 1..3)
+                .is_ok()
+        );
+        assert!(
+            super::update_shape_range(&buffers, &vertices, None, None, Some(&texture), 2..4)
+                .is_err()
+        );
     }
 
     #[test]
@@ -275,7 +536,7 @@ mod tests {
         ];
         let texture = vec![0.0, 0.0, 1.0, 0.0, 0.5, 1.0];
 
-        let buffers = super::init_shape(&vertices, None, Some(&texture), Some(&color)).unwrap();
+        let buffers = init_shape(&vertices, None, Some(&texture), Some(&color), None).unwrap();
         assert_ne!(buffers.vertex_array_object, 0);
         assert_ne!(buffers.vertex_buffer_object, 0);
     }
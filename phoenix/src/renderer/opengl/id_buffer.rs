@@ -0,0 +1,176 @@
+use std::ffi::c_void;
+
+use super::{common::get_last_error_code, shader_compiler, Error, Result};
+use crate::renderer::shaders::{ENTITY_ID_FRAG, ENTITY_ID_VERT};
+
+/// Offscreen framebuffer with the entity IDs in an unsigned integer color attachment
+/// and the depth of the nearest fragment in a depth attachment. Zero means no entity.
+#[derive(Clone, Default)]
+pub struct IdBuffer {
+    framebuffer: u32,
+    color: u32,
+    depth: u32,
+    shader: u32,
+    width: i32,
+    height: i32,
+}
+
+/// Depth test state of the main pass, restored after the ID pass.
+pub struct DepthState {
+    enabled: bool,
+    function: i32,
+}
+
+impl IdBuffer {
+    pub fn new(width: i32, height: i32) -> Result<Self> {
+        let shader = shader_compiler::compile(ENTITY_ID_VERT, ENTITY_ID_FRAG)?;
+        let mut id_buffer = Self {
+            shader,
+            width,
+            height,
+            ..Default::default()
+        };
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut id_buffer.framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, id_buffer.framebuffer);
+            id_buffer.color = create_renderbuffer(gl::R32UI, width, height);
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::RENDERBUFFER,
+                id_buffer.color,
+            );
+            id_buffer.depth = create_renderbuffer(gl::DEPTH_COMPONENT24, width, height);
+            gl::FramebufferRenderbuffer(
+                gl::FRAMEBUFFER,
+                gl::DEPTH_ATTACHMENT,
+                gl::RENDERBUFFER,
+                id_buffer.depth,
+            );
+            let status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+
+            if status != gl::FRAMEBUFFER_COMPLETE {
+                id_buffer.delete();
+                return Err(Error::RenderingError(format!(
+                    "Entity ID framebuffer is incomplete, status {status}"
+                )));
+            }
+        }
+
+        Ok(id_buffer)
+    }
+
+    pub fn get_size(&self) -> (i32, i32) {
+        (self.width, self.height)
+    }
+
+    pub fn get_shader(&self) -> u32 {
+        self.shader
+    }
+
+    /// Binds and clears the framebuffer. Only the pixel under the cursor is rasterized.
+    pub fn begin(&self, x: i32, y: i32) -> DepthState {
+        let depth = DepthState::save();
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, self.framebuffer);
+            gl::Enable(gl::SCISSOR_TEST);
+            gl::Scissor(x, y, 1, 1);
+
+            let no_entity = [0_u32; 4];
+            gl::ClearBufferuiv(gl::COLOR, 0, no_entity.as_ptr());
+            gl::Clear(gl::DEPTH_BUFFER_BIT);
+
+            //without the depth test the last drawn entity is on top, as in the main pass
+            if !depth.enabled {
+                gl::Enable(gl::DEPTH_TEST);
+                gl::DepthFunc(gl::ALWAYS);
+            }
+            gl::UseProgram(self.shader);
+        }
+
+        depth
+    }
+
+    /// Reads the entity ID and the depth of the pixel, then restores the main framebuffer.
+    pub fn end(&self, x: i32, y: i32, depth_state: &DepthState) -> Result<(u32, f32)> {
+        let mut id = 0_u32;
+        let mut depth = 1.0_f32;
+        unsafe {
+            gl::BindFramebuffer(gl::READ_FRAMEBUFFER, self.framebuffer);
+            gl::ReadBuffer(gl::COLOR_ATTACHMENT0);
+            gl::ReadPixels(
+                x,
+                y,
+                1,
+                1,
+                gl::RED_INTEGER,
+                gl::UNSIGNED_INT,
+                (&raw mut id).cast::<c_void>(),
+            );
+            gl::ReadPixels(
+                x,
+                y,
+                1,
+                1,
+                gl::DEPTH_COMPONENT,
+                gl::FLOAT,
+                (&raw mut depth).cast::<c_void>(),
+            );
+
+            gl::Disable(gl::SCISSOR_TEST);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+        depth_state.restore();
+
+        if let Some(err_code) = get_last_error_code(false) {
+            return Err(Error::RenderingError(format!(
+                "Entity ID pass: OpenGL error code {err_code}"
+            )));
+        }
+        Ok((id, depth))
+    }
+
+    pub fn delete(&self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+            gl::DeleteRenderbuffers(1, &self.color);
+            gl::DeleteRenderbuffers(1, &self.depth);
+            gl::DeleteProgram(self.shader);
+        }
+    }
+}
+
+impl DepthState {
+    fn save() -> Self {
+        let mut function = 0;
+        unsafe {
+            gl::GetIntegerv(gl::DEPTH_FUNC, &mut function);
+            Self {
+                enabled: gl::IsEnabled(gl::DEPTH_TEST) == gl::TRUE,
+                function,
+            }
+        }
+    }
+
+    fn restore(&self) {
+        unsafe {
+            gl::DepthFunc(u32::try_from(self.function).unwrap_or(gl::LESS));
+            if !self.enabled {
+                gl::Disable(gl::DEPTH_TEST);
+            }
+        }
+    }
+}
+
+fn create_renderbuffer(format: u32, width: i32, height: i32) -> u32 {
+    let mut renderbuffer = 0;
+    unsafe {
+        gl::GenRenderbuffers(1, &mut renderbuffer);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+        gl::RenderbufferStorage(gl::RENDERBUFFER, format, width, height);
+        gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+    }
+    renderbuffer
+}
@@ -1,7 +1,8 @@
 use super::{common::get_last_error_code, Error, Result};
-use crate::components::texture::{Filtering, MinFiltering, Mipmaps, Texture, Wrapping};
+use crate::components::texture::{ColorSpace, Filtering, MinFiltering, Mipmaps, Texture, Wrapping};
 
-pub fn init_texture(texture: &Texture) -> Result<u32> {
+/// sRGB textures are only decoded when the output is gamma correct, otherwise everything stays encoded.
+pub fn init_texture(texture: &Texture, gamma_correct: bool) -> Result<u32> {
     let id = generate_texture_buffer();
     bind_texture(id);
 
@@ -11,7 +12,7 @@ pub fn init_texture(texture: &Texture) -> Result<u32> {
         texture_config.wrapping_vertical,
     );
     set_filtering(texture_config.min_filtering, texture_config.max_filtering);
-    generate_texture(texture)?;
+    generate_texture(texture, gamma_correct)?;
 
     if is_mipmaps_set(texture_config.min_filtering) {
         generate_mipmaps();
@@ -46,17 +47,24 @@ fn is_mipmaps_set(min_filtering: MinFiltering) -> bool {
     }
 }
 
-fn generate_texture(texture: &Texture) -> Result<()> {
+fn generate_texture(texture: &Texture, gamma_correct: bool) -> Result<()> {
     let encode = if texture.is_alpha_channel() {
         gl::RGBA
     } else {
         gl::RGB
     };
+    let color_space = if gamma_correct {
+        texture.get_config().color_space
+    } else {
+        ColorSpace::Linear
+    };
+    let internal_format = match_internal_format(encode, color_space);
     unsafe {
         gl::TexImage2D(
             gl::TEXTURE_2D,
             0,
-            gl::RGB.try_into().unwrap_or(0),
+            //keeps the alpha channel, the glyph atlas of a font lives in it
+            internal_format.try_into().unwrap_or(0),
             texture.get_width().try_into().unwrap_or(0),
             texture.get_height().try_into().unwrap_or(0),
             0,
@@ -124,6 +132,15 @@ fn match_min_filtering(min_filtering: MinFiltering) -> u32 {
     }
 }
 
+/// sRGB textures are decoded to linear light when sampled, the alpha stays linear.
+fn match_internal_format(encode: u32, color_space: ColorSpace) -> u32 {
+    match (color_space, encode) {
+        (ColorSpace::Srgb, gl::RGBA) => gl::SRGB8_ALPHA8,
+        (ColorSpace::Srgb, _) => gl::SRGB8,
+        (ColorSpace::Linear, _) => encode,
+    }
+}
+
 fn match_wrapping(wrapping: Wrapping) -> u32 {
     match wrapping {
         Wrapping::Repeat => gl::REPEAT,
@@ -163,10 +180,11 @@ mod tests {
             wrapping_vertical: Wrapping::MirroredRepeat,
             min_filtering: MinFiltering::Filtering(Filtering::Linear),
             max_filtering: Filtering::Linear,
+            color_space: ColorSpace::Srgb,
         };
 
         let texture = Texture::new(Rc::new(img.clone()), config);
-        let id = init_texture(&texture).unwrap();
+        let id = init_texture(&texture, false).unwrap();
 
         unsafe {
             //first texture
@@ -184,6 +202,10 @@ mod tests {
 
             gl::GetTexParameteriv(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, &mut param);
             assert_eq!(param as u32, gl::LINEAR);
+
+            //sampled as stored, the output is not gamma correct
+            gl::GetTexLevelParameteriv(gl::TEXTURE_2D, 0, gl::TEXTURE_INTERNAL_FORMAT, &mut param);
+            assert!(matches!(param as u32, gl::RGBA | gl::RGBA8));
         }
     }
 
@@ -201,10 +223,11 @@ mod tests {
             wrapping_vertical: Wrapping::ClampToBorder,
             min_filtering: MinFiltering::Mipmap(Mipmaps::LinearMipmapNearest),
             max_filtering: Filtering::Nearest,
+            color_space: ColorSpace::Linear,
         };
 
         let texture = Texture::new(Rc::new(img.clone()), config);
-        let id = init_texture(&texture).unwrap();
+        let id = init_texture(&texture, false).unwrap();
 
         unsafe {
             //first texture
@@ -222,6 +245,38 @@ mod tests {
 
             gl::GetTexParameteriv(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, &mut param);
             assert_eq!(param as u32, gl::NEAREST);
+
+            gl::GetTexLevelParameteriv(gl::TEXTURE_2D, 0, gl::TEXTURE_INTERNAL_FORMAT, &mut param);
+            //the unsized format may be reported as the sized one
+            assert!(matches!(param as u32, gl::RGBA | gl::RGBA8));
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_init_texture_srgb() {
+        setup_opengl!();
+
+        let img =
+            DynamicImage::ImageRgba8(image::ImageBuffer::from_pixel(2, 2, Rgba([0, 0, 0, 0])));
+        let texture = Texture::new(Rc::new(img), Texture::default().get_config().clone());
+        let id = init_texture(&texture, true).unwrap();
+
+        unsafe {
+            bind_texture(id);
+            let mut param = 0;
+            gl::GetTexLevelParameteriv(gl::TEXTURE_2D, 0, gl::TEXTURE_INTERNAL_FORMAT, &mut param);
+            assert_eq!(param as u32, gl::SRGB8_ALPHA8);
         }
     }
+
+    #[test]
+    fn test_match_internal_format() {
+        assert_eq!(
+            match_internal_format(gl::RGBA, ColorSpace::Srgb),
+            gl::SRGB8_ALPHA8
+        );
+        assert_eq!(match_internal_format(gl::RGB, ColorSpace::Srgb), gl::SRGB8);
+        assert_eq!(match_internal_format(gl::RGB, ColorSpace::Linear), gl::RGB);
+    }
 }
@@ -1,5 +1,11 @@
 pub static BASIC_SHAPES_FRAG: &str = include_str!("shaders/fragment/basic_shapes.frag");
 pub static BASIC_SHAPES_VERT: &str = include_str!("shaders/vertex/basic_shapes.vert");
+pub static POLYLINE_VERT: &str = include_str!("shaders/vertex/polyline.vert");
+pub static PARTICLES_VERT: &str = include_str!("shaders/vertex/particles.vert");
+pub static TEXT_VERT: &str = include_str!("shaders/vertex/text.vert");
+
+pub static ENTITY_ID_VERT: &str = include_str!("shaders/vertex/entity_id.vert");
+pub static ENTITY_ID_FRAG: &str = include_str!("shaders/fragment/entity_id.frag");
 
 pub static LIGHT_SOURCE_VERT: &str = include_str!("shaders/vertex/light_source.vert");
 pub static LIGHT_SOURCE_FRAG: &str = include_str!("shaders/fragment/light_source.frag");
@@ -2,6 +2,7 @@
 
 in vec2 text_coord;
 in vec4 vertex_color;
+in vec4 instance_tint;
 in vec3 normal;
 in vec3 frag_pos;
 
@@ -14,6 +15,7 @@ uniform int is_texture_vert = 0;
 uniform int is_multi_texture = 0;
 uniform int is_color_vert = 0;
 uniform vec4 color = vec4(1.0);
+uniform int is_gamma_correct = 0;
 
 struct Material {
     vec3 ambient;
@@ -37,13 +39,15 @@ uniform Material material;
 uniform Light light;
 
 vec3 calculate_light();
+vec4 decode_color(vec4 srgb);
 
 void main()
 {
     vec4 our_color = color;
     if (is_color_vert == 1) {
-        our_color = vertex_color;
+        our_color = decode_color(vertex_color);
     }
+    our_color *= decode_color(instance_tint);
 
     if (is_texture_vert == 1 && is_light == 0) {
         if (is_multi_texture == 1) {
@@ -94,4 +98,14 @@ vec3 calculate_light() {
     }
 
     return ambient + diffuse + specular;
+}
+
+//vertex colors are sRGB encoded, the uniform color comes already decoded when the output is gamma correct
+vec4 decode_color(vec4 srgb) {
+    if (is_gamma_correct == 0) {
+        return srgb;
+    }
+    vec3 low = srgb.rgb / 12.92;
+    vec3 high = pow((srgb.rgb + 0.055) / 1.055, vec3(2.4));
+    return vec4(mix(high, low, vec3(lessThanEqual(srgb.rgb, vec3(0.04045)))), srgb.a);
 }
\ No newline at end of file
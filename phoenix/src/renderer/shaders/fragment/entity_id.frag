@@ -0,0 +1,10 @@
+#version 330 core
+
+out uint frag_id;
+
+uniform uint entity_id = 0u;
+
+void main()
+{
+    frag_id = entity_id;
+}
\ No newline at end of file
@@ -4,6 +4,9 @@ layout (location = 0) in vec3 position;
 layout (location = 1) in vec3 in_normal;
 layout (location = 2) in vec4 in_color;
 layout (location = 3) in vec2 in_texture_coord;
+//per-instance attributes, the matrix takes the locations from 4 to 7
+layout (location = 4) in mat4 instance_model;
+layout (location = 8) in vec4 instance_color;
 
 uniform mat4 model = mat4(1.0);
 uniform mat4 camera_pos = mat4(1.0);
@@ -11,17 +14,30 @@ uniform mat4 projection = mat4(1.0);
 
 uniform int is_texture_vert = 0;
 uniform int is_color_vert = 0;
+uniform int is_instanced = 0;
+uniform int is_instance_color = 0;
 
 out vec2 text_coord;
 out vec4 vertex_color;
+out vec4 instance_tint;
 out vec3 normal;
 out vec3 frag_pos;
 
 void main()
 {
-    gl_Position = projection * camera_pos * model * vec4(position, 1.0);
-    normal = mat3(transpose(inverse(model))) * in_normal;
-    frag_pos = vec3(model * vec4(position, 1.0));
+    mat4 world = model;
+    if (is_instanced == 1) {
+        world = model * instance_model;
+    }
+
+    gl_Position = projection * camera_pos * world * vec4(position, 1.0);
+    normal = mat3(transpose(inverse(world))) * in_normal;
+    frag_pos = vec3(world * vec4(position, 1.0));
+
+    instance_tint = vec4(1.0);
+    if (is_instance_color == 1) {
+        instance_tint = instance_color;
+    }
 
     if (is_texture_vert == 1) {
         text_coord = in_texture_coord;
@@ -0,0 +1,20 @@
+#version 330 core
+
+layout (location = 0) in vec3 position;
+layout (location = 4) in mat4 instance_model;
+
+uniform mat4 model = mat4(1.0);
+uniform mat4 camera_pos = mat4(1.0);
+uniform mat4 projection = mat4(1.0);
+
+uniform int is_instanced = 0;
+
+void main()
+{
+    mat4 world = model;
+    if (is_instanced == 1) {
+        world = model * instance_model;
+    }
+
+    gl_Position = projection * camera_pos * world * vec4(position, 1.0);
+}
\ No newline at end of file
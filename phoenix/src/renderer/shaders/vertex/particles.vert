@@ -0,0 +1,42 @@
+#version 330 core
+
+//center of the particle
+layout (location = 0) in vec3 position;
+//offset of the quad corner across the view, the size of a point in z
+layout (location = 1) in vec3 in_normal;
+layout (location = 2) in vec4 in_color;
+layout (location = 3) in vec2 in_texture_coord;
+
+uniform mat4 model = mat4(1.0);
+uniform mat4 camera_pos = mat4(1.0);
+uniform mat4 projection = mat4(1.0);
+
+uniform int is_texture_vert = 0;
+uniform int is_color_vert = 0;
+
+out vec2 text_coord;
+out vec4 vertex_color;
+out vec4 instance_tint;
+out vec3 normal;
+out vec3 frag_pos;
+
+void main()
+{
+    vec4 center = camera_pos * model * vec4(position, 1.0);
+
+    //the quad lies in the view plane, so it always faces the camera
+    vec4 view_position = vec4(center.xyz + vec3(in_normal.xy, 0.0), 1.0);
+    gl_Position = projection * view_position;
+    gl_PointSize = in_normal.z;
+    normal = transpose(mat3(camera_pos)) * vec3(0.0, 0.0, 1.0);
+    frag_pos = vec3(inverse(camera_pos) * view_position);
+    instance_tint = vec4(1.0);
+
+    if (is_texture_vert == 1) {
+        text_coord = in_texture_coord;
+    }
+
+    if (is_color_vert == 1) {
+        vertex_color = in_color;
+    }
+}
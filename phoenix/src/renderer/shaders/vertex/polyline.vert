@@ -0,0 +1,64 @@
+#version 330 core
+
+//point on the line
+layout (location = 0) in vec3 position;
+//direction of the line
+layout (location = 1) in vec3 in_normal;
+layout (location = 2) in vec4 in_color;
+//offset along and across the line
+layout (location = 3) in vec2 in_texture_coord;
+//per-instance attributes, the matrix takes the locations from 4 to 7
+layout (location = 4) in mat4 instance_model;
+layout (location = 8) in vec4 instance_color;
+
+uniform mat4 model = mat4(1.0);
+uniform mat4 camera_pos = mat4(1.0);
+uniform mat4 projection = mat4(1.0);
+
+uniform int is_color_vert = 0;
+uniform int is_instanced = 0;
+uniform int is_instance_color = 0;
+
+out vec2 text_coord;
+out vec4 vertex_color;
+out vec4 instance_tint;
+out vec3 normal;
+out vec3 frag_pos;
+
+void main()
+{
+    mat4 model_view = camera_pos * model;
+    if (is_instanced == 1) {
+        model_view = model_view * instance_model;
+    }
+    vec4 center = model_view * vec4(position, 1.0);
+
+    //orthographic projections look along the z axis
+    vec3 to_eye = vec3(0.0, 0.0, 1.0);
+    if (projection[3][3] != 1.0 && length(center.xyz) > 0.0) {
+        to_eye = normalize(-center.xyz);
+    }
+
+    vec3 direction = mat3(model_view) * in_normal;
+    vec3 along = direction - dot(direction, to_eye) * to_eye;
+    if (length(along) < 0.000001) {
+        along = cross(to_eye, abs(to_eye.y) < 0.99 ? vec3(0.0, 1.0, 0.0) : vec3(1.0, 0.0, 0.0));
+    }
+    along = normalize(along);
+    vec3 across = cross(to_eye, along);
+
+    vec4 view_position = vec4(center.xyz + along * in_texture_coord.x + across * in_texture_coord.y, 1.0);
+    gl_Position = projection * view_position;
+    normal = transpose(mat3(camera_pos)) * to_eye;
+    frag_pos = vec3(inverse(camera_pos) * view_position);
+    text_coord = in_texture_coord;
+
+    if (is_color_vert == 1) {
+        vertex_color = in_color;
+    }
+
+    instance_tint = vec4(1.0);
+    if (is_instance_color == 1) {
+        instance_tint = instance_color;
+    }
+}
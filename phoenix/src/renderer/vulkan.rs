@@ -38,6 +38,18 @@ impl Render for Vulkan {
         todo!()
     }
 
+    fn update_shape(&mut self, _entity: &crate::entities::entity::View) -> super::Result<()> {
+        todo!()
+    }
+
+    fn update_shape_range(
+        &mut self,
+        _entity: &crate::entities::entity::View,
+        _vertices: std::ops::Range<usize>,
+    ) -> super::Result<()> {
+        todo!()
+    }
+
     fn init_texture(
         &mut self,
         _texture: &crate::components::texture::Texture,
@@ -105,6 +117,10 @@ impl Render for Vulkan {
         todo!()
     }
 
+    fn read_entity_id(&mut self, _pass: &super::IdPass) -> super::Result<Option<(super::ID, f32)>> {
+        todo!()
+    }
+
     fn enable_3d(&self) {
         todo!()
     }
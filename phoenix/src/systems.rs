@@ -1,6 +1,8 @@
 pub mod camera;
+pub mod culling;
 pub mod lighting;
 pub mod performance;
+pub mod picking;
 pub mod scaler;
 pub mod scene;
 
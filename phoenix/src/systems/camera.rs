@@ -1,3 +1,5 @@
+use super::culling::Frustum;
+use super::picking::Ray;
 use crate::window::Resolution;
 use cgmath::{perspective, Deg, InnerSpace, Matrix4, Point3, Vector3};
 
@@ -150,6 +152,20 @@ impl Camera {
         )
     }
 
+    pub fn get_frustum(&self) -> Frustum {
+        Frustum::from_matrix(&(self.get_projection() * self.get_camera_position()))
+    }
+
+    pub fn get_cursor_ray(&self, xpos: f32, ypos: f32, resolution: &Resolution) -> Option<Ray> {
+        Ray::from_cursor(
+            xpos,
+            ypos,
+            resolution,
+            &self.get_projection(),
+            &self.get_camera_position(),
+        )
+    }
+
     pub fn get_camera_vec_pos(&self) -> Vector3<f32> {
         Vector3::new(
             self.movement.position.x,
@@ -323,6 +339,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_camera_get_cursor_ray() {
+        let camera = create_camera();
+        let resolution = Resolution {
+            width: 800,
+            height: 600,
+        };
+
+        let ray = camera.get_cursor_ray(400.0, 300.0, &resolution).unwrap();
+        assert_relative_eq!(ray.origin, Vector3::new(0.0, 0.0, -0.1), epsilon = 1e-4);
+        assert_relative_eq!(ray.direction, Vector3::new(0.0, 0.0, -1.0), epsilon = 1e-4);
+    }
+
     #[test]
     fn test_camera_get_camera_position() {
         let camera = create_camera();
@@ -0,0 +1,149 @@
+use cgmath::{InnerSpace, Matrix4, Vector3, Vector4};
+
+use crate::components::geometry::bounds::{Aabb, BoundingSphere, Bounds};
+
+/// Volume seen by the camera, bounded by six planes with normals pointing inside.
+#[derive(Clone, Copy, Debug)]
+pub struct Frustum {
+    planes: [Plane; 6],
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Plane {
+    normal: Vector3<f32>,
+    distance: f32,
+}
+
+/// Number of entities handled during the last frame.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct FrameStatistics {
+    pub drawn: u32,
+    pub culled: u32,
+}
+
+impl Frustum {
+    /// Extracts the planes from the `projection * view` matrix.
+    #[must_use]
+    pub fn from_matrix(matrix: &Matrix4<f32>) -> Self {
+        let row = |index: usize| {
+            Vector4::new(
+                matrix.x[index],
+                matrix.y[index],
+                matrix.z[index],
+                matrix.w[index],
+            )
+        };
+        let (x, y, z, w) = (row(0), row(1), row(2), row(3));
+        Self {
+            planes: [w + x, w - x, w + y, w - y, w + z, w - z].map(Plane::new),
+        }
+    }
+
+    #[must_use]
+    pub fn intersects_sphere(&self, sphere: &BoundingSphere) -> bool {
+        self.planes
+            .iter()
+            .all(|plane| plane.signed_distance(sphere.center) >= -sphere.radius)
+    }
+
+    /// Conservative test, a box near a corner of the frustum may pass although it is not visible.
+    #[must_use]
+    pub fn intersects_aabb(&self, aabb: &Aabb) -> bool {
+        self.planes.iter().all(|plane| {
+            //the corner furthest along the plane normal
+            let corner = Vector3::new(
+                if plane.normal.x >= 0.0 {
+                    aabb.max.x
+                } else {
+                    aabb.min.x
+                },
+                if plane.normal.y >= 0.0 {
+                    aabb.max.y
+                } else {
+                    aabb.min.y
+                },
+                if plane.normal.z >= 0.0 {
+                    aabb.max.z
+                } else {
+                    aabb.min.z
+                },
+            );
+            plane.signed_distance(corner) >= 0.0
+        })
+    }
+
+    /// The sphere rejects most of the invisible objects, the box is tighter for elongated ones.
+    #[must_use]
+    pub fn intersects(&self, bounds: &Bounds) -> bool {
+        self.intersects_sphere(&bounds.sphere) && self.intersects_aabb(&bounds.aabb)
+    }
+}
+
+impl Plane {
+    fn new(coefficients: Vector4<f32>) -> Self {
+        let normal = coefficients.truncate();
+        let length = normal.magnitude();
+        Self {
+            normal: normal / length,
+            distance: coefficients.w / length,
+        }
+    }
+
+    fn signed_distance(&self, point: Vector3<f32>) -> f32 {
+        self.normal.dot(point) + self.distance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cgmath::{perspective, Deg, Point3};
+
+    //camera at the origin looking towards negative z, sees from 0.1 to 100
+    fn create_frustum() -> Frustum {
+        let projection = perspective(Deg(90.0), 1.0, 0.1, 100.0);
+        let view = Matrix4::look_at_rh(
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(0.0, 0.0, -1.0),
+            Vector3::unit_y(),
+        );
+        Frustum::from_matrix(&(projection * view))
+    }
+
+    fn sphere(x: f32, y: f32, z: f32, radius: f32) -> BoundingSphere {
+        BoundingSphere {
+            center: Vector3::new(x, y, z),
+            radius,
+        }
+    }
+
+    #[test]
+    fn test_frustum_spheres() {
+        let frustum = create_frustum();
+
+        assert!(frustum.intersects_sphere(&sphere(0.0, 0.0, -10.0, 1.0)));
+        //behind the camera
+        assert!(!frustum.intersects_sphere(&sphere(0.0, 0.0, 10.0, 1.0)));
+        //beyond the far plane, then crossing it
+        assert!(!frustum.intersects_sphere(&sphere(0.0, 0.0, -110.0, 5.0)));
+        assert!(frustum.intersects_sphere(&sphere(0.0, 0.0, -102.0, 5.0)));
+        //the field of vision is 90 degrees, so x = -z is the edge
+        assert!(!frustum.intersects_sphere(&sphere(15.0, 0.0, -10.0, 1.0)));
+        assert!(frustum.intersects_sphere(&sphere(10.5, 0.0, -10.0, 1.0)));
+        assert!(!frustum.intersects_sphere(&sphere(0.0, -15.0, -10.0, 1.0)));
+    }
+
+    #[test]
+    fn test_frustum_bounds() {
+        let frustum = create_frustum();
+        let visible = Bounds::from_vertices(&[-1.0, -1.0, -5.0, 1.0, 1.0, -6.0]).unwrap();
+        let behind = Bounds::from_vertices(&[-1.0, -1.0, 5.0, 1.0, 1.0, 6.0]).unwrap();
+        //a long thin box along the left edge, its sphere reaches inside but the box does not
+        let thin = Bounds::from_vertices(&[-30.0, 0.0, -10.0, -12.0, 0.0, -10.0]).unwrap();
+
+        assert!(frustum.intersects(&visible));
+        assert!(!frustum.intersects(&behind));
+        assert!(frustum.intersects_sphere(&thin.sphere));
+        assert!(!frustum.intersects(&thin));
+    }
+}
@@ -0,0 +1,443 @@
+use cgmath::{InnerSpace, Matrix4, SquareMatrix, Vector3, Vector4};
+
+use crate::common::collect_triangles;
+use crate::components::geometry::bounds::{Aabb, BoundingSphere};
+use crate::entities::entity::{Manager, ID};
+use crate::window::Resolution;
+
+/// How `Action::PickEntity` finds the entity under the cursor.
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum PickingMode {
+    /// Ray tested against the entity bounds and triangles on the CPU.
+    #[default]
+    Ray,
+    /// Entity IDs drawn into an offscreen buffer, exact to a pixel.
+    /// Shapes expanded by their own vertex shader are drawn without the expansion.
+    IdBuffer,
+}
+
+/// Half-line starting at `origin`, `direction` is a unit vector.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Ray {
+    pub origin: Vector3<f32>,
+    pub direction: Vector3<f32>,
+}
+
+/// The nearest entity hit by a ray.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Pick {
+    pub entity_id: ID,
+    /// Hit position in world space.
+    pub point: Vector3<f32>,
+    /// Distance from the ray origin to the hit position.
+    pub distance: f32,
+}
+
+impl Ray {
+    /// Ray going from the near plane through the cursor position, given in window coordinates
+    /// with the origin in the top left corner. `None` when the matrices can not be inverted.
+    #[must_use]
+    pub fn from_cursor(
+        xpos: f32,
+        ypos: f32,
+        resolution: &Resolution,
+        projection: &Matrix4<f32>,
+        view: &Matrix4<f32>,
+    ) -> Option<Self> {
+        let near = unproject(xpos, ypos, 0.0, resolution, projection, view)?;
+        let far = unproject(xpos, ypos, 1.0, resolution, projection, view)?;
+        Some(Self {
+            origin: near,
+            direction: (far - near).normalize(),
+        })
+    }
+
+    #[must_use]
+    pub fn point_at(&self, distance: f32) -> Vector3<f32> {
+        self.origin + self.direction * distance
+    }
+
+    /// Distance to the point where the ray enters the box, zero when it starts inside.
+    #[must_use]
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> Option<f32> {
+        let mut entry = 0.0_f32;
+        let mut exit = f32::INFINITY;
+        for axis in 0..3 {
+            let inverse = 1.0 / self.direction[axis];
+            let first = (aabb.min[axis] - self.origin[axis]) * inverse;
+            let second = (aabb.max[axis] - self.origin[axis]) * inverse;
+            entry = entry.max(first.min(second));
+            exit = exit.min(first.max(second));
+        }
+
+        (entry <= exit).then_some(entry)
+    }
+
+    #[must_use]
+    pub fn intersect_sphere(&self, sphere: &BoundingSphere) -> Option<f32> {
+        let to_center = sphere.center - self.origin;
+        let along = to_center.dot(self.direction);
+        let squared_offset = to_center.magnitude2() - along * along;
+        let squared_half_chord = sphere.radius * sphere.radius - squared_offset;
+        if squared_half_chord < 0.0 {
+            return None;
+        }
+
+        let half_chord = squared_half_chord.sqrt();
+        [along - half_chord, along + half_chord]
+            .into_iter()
+            .find(|distance| *distance >= 0.0)
+    }
+
+    /// Möller–Trumbore intersection, both sides of the triangle are hit.
+    #[must_use]
+    pub fn intersect_triangle(
+        &self,
+        a: Vector3<f32>,
+        b: Vector3<f32>,
+        c: Vector3<f32>,
+    ) -> Option<f32> {
+        let first_edge = b - a;
+        let second_edge = c - a;
+        let perpendicular = self.direction.cross(second_edge);
+        let determinant = first_edge.dot(perpendicular);
+        //the ray is parallel to the triangle
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let inverse = 1.0 / determinant;
+        let to_origin = self.origin - a;
+        let first_weight = to_origin.dot(perpendicular) * inverse;
+        if !(0.0..=1.0).contains(&first_weight) {
+            return None;
+        }
+
+        let cross = to_origin.cross(first_edge);
+        let second_weight = self.direction.dot(cross) * inverse;
+        if second_weight < 0.0 || first_weight + second_weight > 1.0 {
+            return None;
+        }
+
+        let distance = second_edge.dot(cross) * inverse;
+        (distance >= 0.0).then_some(distance)
+    }
+}
+
+/// World position of the cursor position at the depth buffer value `depth`,
+/// from 0 at the near plane to 1 at the far plane.
+#[must_use]
+pub fn unproject(
+    xpos: f32,
+    ypos: f32,
+    depth: f32,
+    resolution: &Resolution,
+    projection: &Matrix4<f32>,
+    view: &Matrix4<f32>,
+) -> Option<Vector3<f32>> {
+    let inverse = (projection * view).invert()?;
+    let point = inverse
+        * Vector4::new(
+            2.0 * xpos / f32::from(resolution.width) - 1.0,
+            1.0 - 2.0 * ypos / f32::from(resolution.height),
+            2.0 * depth - 1.0,
+            1.0,
+        );
+    Some(point.truncate() / point.w)
+}
+
+/// Framebuffer pixel under the cursor position, counted from the bottom left corner.
+/// The framebuffer may be bigger than the window on high density displays.
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_precision_loss)]
+pub(crate) fn window_to_framebuffer(
+    xpos: f32,
+    ypos: f32,
+    resolution: &Resolution,
+    framebuffer_size: (i32, i32),
+) -> (i32, i32) {
+    let (width, height) = framebuffer_size;
+    let x = (xpos * width as f32 / f32::from(resolution.width)).floor() as i32;
+    let y = (ypos * height as f32 / f32::from(resolution.height)).floor() as i32;
+    (x, height - 1 - y)
+}
+
+/// Finds the nearest entity hit by the ray. Entities are first tested against their world bounds,
+/// then against their triangles. Shapes drawn as points or lines are hit by their bounding box.
+#[must_use]
+pub fn pick(manager: &Manager, ray: &Ray) -> Option<Pick> {
+    let mut nearest: Option<Pick> = None;
+    for key in manager.get_keys() {
+        //screen space texts are not in the world the ray goes through
+        if manager.is_screen_text(key) {
+            continue;
+        }
+        let Some(bounds) = manager.get_world_bounds(key) else {
+            continue;
+        };
+        let Some(box_distance) = ray.intersect_aabb(&bounds.aabb) else {
+            continue;
+        };
+        if nearest.is_some_and(|pick| pick.distance <= box_distance) {
+            continue;
+        }
+
+        let distance = intersect_entity(manager, key, ray, box_distance);
+        if let Some(distance) = distance {
+            if nearest.is_none_or(|pick| distance < pick.distance) {
+                nearest = Some(Pick {
+                    entity_id: key,
+                    point: ray.point_at(distance),
+                    distance,
+                });
+            }
+        }
+    }
+
+    nearest
+}
+
+fn intersect_entity(manager: &Manager, key: ID, ray: &Ray, box_distance: f32) -> Option<f32> {
+    let shape = manager.get_drawn_shape(key)?;
+    let vertices = shape.get_vertices();
+    let triangles = collect_triangles(
+        vertices.len() / 3,
+        shape.get_indices(),
+        shape.get_fill_mode(),
+    );
+    if triangles.is_empty() {
+        return Some(box_distance);
+    }
+
+    //every instance is a copy of the shape under its own matrix
+    manager
+        .get_model_matrices(key)
+        .into_iter()
+        .filter_map(|matrix| {
+            let position = |index: u32| {
+                let start = index as usize * 3;
+                let point = vertices.get(start..start + 3)?;
+                Some((matrix * Vector4::new(point[0], point[1], point[2], 1.0)).truncate())
+            };
+            triangles
+                .iter()
+                .filter_map(|&[a, b, c]| {
+                    ray.intersect_triangle(position(a)?, position(b)?, position(c)?)
+                })
+                .min_by(f32::total_cmp)
+        })
+        .min_by(f32::total_cmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::geometry::plane::Triangle;
+    use crate::components::instances::Instances;
+    use crate::components::sprite;
+    use crate::components::texture::Texture;
+    use crate::components::transformer::Transformer;
+    use crate::components::{Component, FillMode, Shape, ShapeType};
+    use crate::entities::entity::Entity;
+    use cgmath::{assert_relative_eq, perspective, Deg, Point3};
+    use std::rc::Rc;
+
+    const TRIANGLE: [f32; 9] = [-0.5, -0.5, 0.0, 0.5, -0.5, 0.0, 0.0, 0.5, 0.0];
+
+    fn forward_ray(x: f32, y: f32) -> Ray {
+        Ray {
+            origin: Vector3::new(x, y, 10.0),
+            direction: Vector3::new(0.0, 0.0, -1.0),
+        }
+    }
+
+    fn add_triangle(manager: &mut Manager, translation: Vector3<f32>) -> ID {
+        manager.add_entity(Entity::new(vec![
+            Component::Geometry(Box::new(Triangle::new(TRIANGLE))),
+            Component::Transformer(Transformer::new_translate(translation)),
+        ]))
+    }
+
+    #[test]
+    fn test_ray_from_cursor() {
+        let resolution = Resolution {
+            width: 800,
+            height: 600,
+        };
+        let projection = perspective(Deg(45.0), 800.0 / 600.0, 0.1, 100.0);
+        let view = Matrix4::look_at_rh(
+            Point3::new(0.0, 0.0, 5.0),
+            Point3::new(0.0, 0.0, 0.0),
+            Vector3::unit_y(),
+        );
+
+        let ray = Ray::from_cursor(400.0, 300.0, &resolution, &projection, &view).unwrap();
+        assert_relative_eq!(ray.origin, Vector3::new(0.0, 0.0, 4.9), epsilon = 1e-4);
+        assert_relative_eq!(ray.direction, Vector3::new(0.0, 0.0, -1.0), epsilon = 1e-4);
+
+        //the top left corner is up and to the left
+        let ray = Ray::from_cursor(0.0, 0.0, &resolution, &projection, &view).unwrap();
+        assert!(ray.direction.x < 0.0 && ray.direction.y > 0.0 && ray.direction.z < 0.0);
+    }
+
+    #[test]
+    fn test_unproject_and_framebuffer_pixel() {
+        let resolution = Resolution {
+            width: 800,
+            height: 600,
+        };
+        let identity = Matrix4::identity();
+
+        //without a camera the window covers the square from -1 to 1
+        let point = unproject(200.0, 150.0, 0.5, &resolution, &identity, &identity).unwrap();
+        assert_relative_eq!(point, Vector3::new(-0.5, 0.5, 0.0));
+
+        assert_eq!(
+            window_to_framebuffer(0.0, 0.0, &resolution, (800, 600)),
+            (0, 599)
+        );
+        assert_eq!(
+            window_to_framebuffer(400.5, 599.5, &resolution, (1600, 1200)),
+            (801, 0)
+        );
+    }
+
+    #[test]
+    fn test_ray_intersections() {
+        let ray = forward_ray(0.0, 0.0);
+        let [a, b, c] = [0, 1, 2].map(|index| {
+            Vector3::new(
+                TRIANGLE[index * 3],
+                TRIANGLE[index * 3 + 1],
+                TRIANGLE[index * 3 + 2],
+            )
+        });
+        assert_relative_eq!(ray.intersect_triangle(a, b, c).unwrap(), 10.0);
+        assert_relative_eq!(ray.intersect_triangle(a, c, b).unwrap(), 10.0);
+        assert!(forward_ray(0.4, 0.4).intersect_triangle(a, b, c).is_none());
+
+        let aabb = Aabb {
+            min: Vector3::new(-1.0, -1.0, -1.0),
+            max: Vector3::new(1.0, 1.0, 1.0),
+        };
+        assert_relative_eq!(ray.intersect_aabb(&aabb).unwrap(), 9.0);
+        assert!(forward_ray(2.0, 0.0).intersect_aabb(&aabb).is_none());
+
+        let sphere = BoundingSphere {
+            center: Vector3::new(0.0, 0.0, 0.0),
+            radius: 2.0,
+        };
+        assert_relative_eq!(ray.intersect_sphere(&sphere).unwrap(), 8.0);
+        assert!(forward_ray(0.0, 3.0).intersect_sphere(&sphere).is_none());
+        //the ray points away from the box
+        let backward = Ray {
+            origin: Vector3::new(0.0, 0.0, 10.0),
+            direction: Vector3::new(0.0, 0.0, 1.0),
+        };
+        assert!(backward.intersect_aabb(&aabb).is_none());
+        assert!(backward.intersect_sphere(&sphere).is_none());
+    }
+
+    #[test]
+    fn test_pick_nearest_entity() {
+        let mut manager = Manager::default();
+        let far = add_triangle(&mut manager, Vector3::new(0.0, 0.0, -5.0));
+        let near = add_triangle(&mut manager, Vector3::new(0.0, 0.0, 2.0));
+        let aside = add_triangle(&mut manager, Vector3::new(3.0, 0.0, 0.0));
+
+        let pick = pick(&manager, &forward_ray(0.0, 0.0)).unwrap();
+        assert_eq!(pick.entity_id, near);
+        assert_relative_eq!(pick.distance, 8.0);
+        assert_relative_eq!(pick.point, Vector3::new(0.0, 0.0, 2.0));
+
+        assert_eq!(
+            super::pick(&manager, &forward_ray(3.0, 0.0))
+                .unwrap()
+                .entity_id,
+            aside
+        );
+        manager.remove_entity(near);
+        assert_eq!(
+            super::pick(&manager, &forward_ray(0.0, 0.0))
+                .unwrap()
+                .entity_id,
+            far
+        );
+    }
+
+    #[test]
+    fn test_pick_instances() {
+        let mut manager = Manager::default();
+        let id = manager.add_entity(Entity::new(vec![
+            Component::Geometry(Box::new(Triangle::new(TRIANGLE))),
+            Component::Instances(Instances::new(vec![
+                Transformer::new_translate(Vector3::new(-3.0, 0.0, 0.0)),
+                Transformer::new_translate(Vector3::new(3.0, 0.0, -1.0)),
+            ])),
+        ]));
+
+        let pick = pick(&manager, &forward_ray(3.0, 0.0)).unwrap();
+        assert_eq!(pick.entity_id, id);
+        assert_relative_eq!(pick.distance, 11.0);
+        //the gap between the instances is inside their bounds
+        assert!(super::pick(&manager, &forward_ray(0.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_pick_misses_triangle_inside_bounds() {
+        let mut manager = Manager::default();
+        add_triangle(&mut manager, Vector3::new(0.0, 0.0, 0.0));
+
+        //inside the bounding box, but outside the triangle
+        assert!(pick(&manager, &forward_ray(0.45, 0.45)).is_none());
+        assert!(pick(&manager, &forward_ray(0.0, 0.0)).is_some());
+    }
+
+    #[test]
+    fn test_pick_sprite() {
+        let mut manager = Manager::default();
+        let image = image::DynamicImage::ImageRgba8(image::RgbaImage::new(4, 2));
+        let texture = Texture::new(Rc::new(image), Texture::default().get_config().clone());
+        let atlas = Rc::new(sprite::Atlas::from_grid(texture, 2, 2).unwrap());
+        let sprite = sprite::Builder::new(atlas)
+            .with_size(2.0, 2.0)
+            .build()
+            .unwrap();
+        let id = manager.add_entity(Entity::new(vec![
+            Component::Sprite(Box::new(sprite)),
+            Component::Transformer(Transformer::new_translate(Vector3::new(0.0, 0.0, 1.0))),
+        ]));
+
+        let pick = pick(&manager, &forward_ray(0.9, 0.9)).unwrap();
+        assert_eq!(pick.entity_id, id);
+        assert_relative_eq!(pick.distance, 9.0);
+        assert!(super::pick(&manager, &forward_ray(1.5, 0.0)).is_none());
+    }
+
+    struct Lines;
+
+    impl Shape for Lines {
+        fn get_vertices(&self) -> &[f32] {
+            &TRIANGLE
+        }
+
+        fn get_type(&self) -> ShapeType {
+            ShapeType::Polyline
+        }
+
+        fn get_fill_mode(&self) -> FillMode {
+            FillMode::LineLoop
+        }
+    }
+
+    #[test]
+    fn test_pick_lines_by_bounds() {
+        let mut manager = Manager::default();
+        let id = manager.add_entity(Entity::new(vec![Component::Geometry(Box::new(Lines))]));
+
+        assert_eq!(
+            pick(&manager, &forward_ray(0.45, 0.45)).unwrap().entity_id,
+            id
+        );
+    }
+}
@@ -1,14 +1,20 @@
 mod event_interpreter;
 use std::rc::Rc;
 
+use cgmath::{ortho, vec3, Matrix4, MetricSpace, SquareMatrix};
+
 use super::camera::{Camera, Config};
+use super::culling::FrameStatistics;
 use super::lighting::calculate_light_pos;
 use super::performance::{FpsCounter, GlfwTimer};
+use super::picking::{self, Pick, PickingMode};
 use crate::components::color::RGBA;
 use crate::components::material::Material;
+use crate::components::text::{self, Font};
 use crate::components::transformer::Transformer;
+use crate::components::Component;
 use crate::entities::entity::{Entity, Manager, View};
-use crate::renderer::{self, Render};
+use crate::renderer::{self, IdPass, Render};
 use crate::window::{WinError, Window};
 use crate::{entities, events};
 
@@ -33,6 +39,11 @@ pub struct Scene {
     camera: Option<Camera>,
     pub event_manager: events::Manager,
     fps_counter: FpsCounter,
+    frustum_culling: bool,
+    frame_statistics: FrameStatistics,
+    picking_mode: PickingMode,
+    last_pick: Option<Pick>,
+    fps_text: Option<ID>,
 }
 
 impl Scene {
@@ -46,6 +57,11 @@ impl Scene {
             camera: None,
             event_manager: events::Manager::new(window),
             fps_counter: FpsCounter::new(Box::new(GlfwTimer::default())),
+            frustum_culling: true,
+            frame_statistics: FrameStatistics::default(),
+            picking_mode: PickingMode::default(),
+            last_pick: None,
+            fps_text: None,
         }
     }
 
@@ -74,6 +90,18 @@ impl Scene {
         self.entity_manager.add_entity(result);
     }
 
+    /// Draws the frames per second in the top left corner of the window with the given font.
+    pub fn show_fps(&mut self, font: Rc<Font>) {
+        if let Some(id) = self.fps_text.take() {
+            self.entity_manager.remove_entity(id);
+        }
+        let entity = entities::preprocessing::preprocessing(Entity::new(vec![
+            Component::Text(Box::new(text::Builder::new(font, "FPS").build())),
+            Component::Transformer(Transformer::new_translate(vec3(10.0, 10.0, 0.0))),
+        ]));
+        self.fps_text = Some(self.entity_manager.add_entity(entity));
+    }
+
     /// # Errors
     ///
     /// Returns Err when the window fails to set itself as the current window.
@@ -111,6 +139,100 @@ impl Scene {
         self.fps_counter.get_delta_time()
     }
 
+    /// Entities outside the camera view are not drawn, enabled by default.
+    /// Works only with a registered camera.
+    pub fn set_frustum_culling(&mut self, enabled: bool) {
+        self.frustum_culling = enabled;
+    }
+
+    #[must_use]
+    pub fn get_frame_statistics(&self) -> FrameStatistics {
+        self.frame_statistics
+    }
+
+    /// Nearest entity under the cursor position, given in window coordinates.
+    /// Returns `None` without a registered camera.
+    #[must_use]
+    pub fn pick(&self, xpos: f32, ypos: f32) -> Option<Pick> {
+        let ray =
+            self.camera
+                .as_ref()?
+                .get_cursor_ray(xpos, ypos, &self.window.get_resolution())?;
+        picking::pick(&self.entity_manager, &ray)
+    }
+
+    /// Nearest entity under the cursor position, read from an offscreen buffer with the entity IDs.
+    /// Works with and without a registered camera, entities are drawn as in the last frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns Err when the offscreen buffer cannot be created or read.
+    pub fn pick_pixel(&mut self, xpos: f32, ypos: f32) -> Result<Option<Pick>> {
+        let resolution = self.window.get_resolution();
+        let (camera_pos, projection) = self.camera.as_ref().map_or_else(
+            || (Matrix4::identity(), Matrix4::identity()),
+            |camera| (camera.get_camera_position(), camera.get_projection()),
+        );
+        //the buffer holds the renderer IDs, which differ from the entity IDs for LOD levels,
+        //and the entities overlap in the same order as they are drawn
+        let mut keys = self.entity_manager.get_keys_in_draw_order();
+        keys.retain(|key| !self.entity_manager.is_screen_text(*key));
+        let entities: Vec<(ID, Matrix4<f32>)> = keys
+            .iter()
+            .map(|key| {
+                let model = self
+                    .entity_manager
+                    .as_ref_transformers(*key)
+                    .map_or_else(Matrix4::identity, Transformer::get_matrix);
+                (self.entity_manager.get_render_id(*key), model)
+            })
+            .collect();
+        let (x, y) = picking::window_to_framebuffer(
+            xpos,
+            ypos,
+            &resolution,
+            self.window.get_framebuffer_size(),
+        );
+
+        let pass = IdPass {
+            entities: &entities,
+            camera_pos,
+            projection,
+            x,
+            y,
+        };
+        let Some((render_id, depth)) = self.renderer.read_entity_id(&pass)? else {
+            return Ok(None);
+        };
+        let Some(entity_id) = entities
+            .iter()
+            .position(|(id, _)| *id == render_id)
+            .map(|index| keys[index])
+        else {
+            return Ok(None);
+        };
+
+        let unproject =
+            |depth| picking::unproject(xpos, ypos, depth, &resolution, &projection, &camera_pos);
+        Ok(unproject(depth)
+            .zip(unproject(0.0))
+            .map(|(point, near)| Pick {
+                entity_id,
+                point,
+                distance: point.distance(near),
+            }))
+    }
+
+    pub fn set_picking_mode(&mut self, mode: PickingMode) {
+        self.picking_mode = mode;
+    }
+
+    /// Result of the last `Action::PickEntity`.
+    #[must_use]
+    pub fn get_last_pick(&self) -> Option<Pick> {
+        self.last_pick
+    }
+
     #[must_use]
     pub fn get_last_error_code(&self) -> Option<u32> {
         self.renderer.get_last_error_code()
@@ -122,10 +244,51 @@ impl Scene {
 
         self.handle_user_input_callbacks();
 
-        let keys = self.entity_manager.get_keys();
-        for key in keys {
+        let frustum = self
+            .camera
+            .as_ref()
+            .filter(|_| self.frustum_culling)
+            .map(Camera::get_frustum);
+        self.frame_statistics = FrameStatistics::default();
+
+        let camera_position = self.camera.as_ref().map(Camera::get_camera_vec_pos);
+        self.entity_manager
+            .update_particles(self.fps_counter.get_delta_time());
+        self.entity_manager
+            .update_sprites(self.fps_counter.get_delta_time());
+        if let Some(text) = self
+            .fps_text
+            .and_then(|id| self.entity_manager.get_text_mut(id))
+        {
+            text.set_content(&format!("FPS: {:.0}", self.fps_counter.get_fps()));
+        }
+        self.entity_manager.update_bounds();
+
+        for key in self.entity_manager.get_keys_in_draw_order() {
+            let bounds = camera_position.and_then(|_| self.entity_manager.get_world_bounds(key));
+            //entities without bounds are always drawn
+            if let (Some(frustum), Some(bounds)) = (&frustum, &bounds) {
+                if !frustum.intersects(bounds) {
+                    self.frame_statistics.culled += 1;
+                    continue;
+                }
+            }
+            if let (Some(position), Some(bounds)) = (camera_position, &bounds) {
+                self.entity_manager
+                    .update_lod(key, bounds.sphere.center.distance(position));
+            }
+
+            let shape_changed = self.entity_manager.take_text_changed(key)
+                || self.entity_manager.take_sprite_changed(key);
+            let tile_changes = self.entity_manager.take_tilemap_changes(key);
             let entity_view = self.entity_manager.as_ref_entity(key);
             let id = self.renderer.init_entity(&entity_view)?;
+            for vertices in tile_changes {
+                self.renderer.update_shape_range(&entity_view, vertices)?;
+            }
+            if entity_view.particles.is_some() || shape_changed {
+                self.renderer.update_shape(&entity_view)?;
+            }
 
             //shader part
             self.handle_entity_transformation(&entity_view)?;
@@ -138,6 +301,7 @@ impl Scene {
 
             //final step to draw the entity
             self.renderer.draw_entity(id);
+            self.frame_statistics.drawn += 1;
         }
 
         self.window.swap_buffers();
@@ -159,7 +323,7 @@ impl Scene {
 
     fn handle_light_source(&self, entity: &View) -> Result<()> {
         if let Some(light_source) = self.entity_manager.get_light_entity() {
-            if entity.light.is_none() {
+            if entity.light.is_none() && !is_unlit(entity) {
                 self.renderer.update_light_uniform_struct(
                     entity.entity_id,
                     light_source.light.unwrap(),
@@ -186,8 +350,24 @@ impl Scene {
         Ok(())
     }
 
+    /// Screen space texts are drawn in window pixels from the top left corner, in front of the scene.
+    /// The texts share their own shader, so without a camera the world space ones reset the matrices.
     fn handle_camera(&self, entity: &View) -> Result<()> {
-        if let Some(cam) = &self.camera {
+        if entity.text.is_some_and(text::Text::is_screen_space) {
+            let resolution = self.window.get_resolution();
+            let projection = ortho(
+                0.0,
+                f32::from(resolution.width),
+                f32::from(resolution.height),
+                0.0,
+                0.0,
+                1.0,
+            );
+            self.renderer
+                .perform_camera_position_transformation(entity.entity_id, &Matrix4::identity())?;
+            self.renderer
+                .perform_camera_projection_transformation(entity.entity_id, &projection)?;
+        } else if let Some(cam) = &self.camera {
             self.renderer.perform_camera_position_transformation(
                 entity.entity_id,
                 &cam.get_camera_position(),
@@ -200,8 +380,18 @@ impl Scene {
                 self.renderer
                     .update_camera_position_vec(entity.entity_id, &cam.get_camera_vec_pos())?;
             }
+        } else if entity.text.is_some() {
+            self.renderer
+                .perform_camera_position_transformation(entity.entity_id, &Matrix4::identity())?;
+            self.renderer
+                .perform_camera_projection_transformation(entity.entity_id, &Matrix4::identity())?;
         }
 
         Ok(())
     }
 }
+
+/// Texts, sprites and tilemaps show their textures as they are, the light is not applied to them.
+fn is_unlit(entity: &View) -> bool {
+    entity.text.is_some() || entity.sprite.is_some() || entity.tilemap.is_some()
+}
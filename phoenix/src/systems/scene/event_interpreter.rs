@@ -1,4 +1,7 @@
-use crate::{events::action::Action, systems::scene::Scene};
+use crate::{
+    events::action::Action,
+    systems::{picking::PickingMode, scene::Scene},
+};
 
 pub fn process_actions(mut actions: Vec<Action>, scene: &mut Scene) -> bool {
     while let Some(action) = actions.pop() {
@@ -18,6 +21,20 @@ pub fn process_actions(mut actions: Vec<Action>, scene: &mut Scene) -> bool {
                 .as_mut()
                 .unwrap()
                 .change_orientation(xpos, ypos),
+            Action::PickEntity(xpos, ypos) => {
+                scene.last_pick = match scene.picking_mode {
+                    PickingMode::Ray => scene.pick(xpos, ypos),
+                    //a failed offscreen pass is treated as a miss
+                    PickingMode::IdBuffer => scene.pick_pixel(xpos, ypos).ok().flatten(),
+                };
+                if let Some(pick) = scene.last_pick {
+                    scene
+                        .event_manager
+                        .push_action(Action::EntityPicked(pick.entity_id));
+                }
+            }
+            //handled by the events conditions
+            Action::EntityPicked(_) => {}
         }
     }
 
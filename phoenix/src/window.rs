@@ -31,6 +31,7 @@ pub struct Window {
     window: *mut glfw_bindings::GLFWwindow,
     name: String,
     resolution: Resolution,
+    srgb: bool,
 }
 
 impl Default for Resolution {
@@ -60,6 +61,25 @@ impl GlfwConfig {
     /// Height or width in resolution is zero.
     /// Invalid `CString` format, see `CString::new`.
     pub fn create_window(&self, name: &str, resolution: Resolution) -> Result<Window> {
+        Self::create_window_with_framebuffer(name, resolution, false)
+    }
+
+    /// Asks for a framebuffer which encodes the written linear light to sRGB, so the renderer
+    /// lights and blends in linear light. The driver may ignore the request, the renderer
+    /// checks the encoding it really got.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` in the same cases as `create_window`.
+    pub fn create_srgb_window(&self, name: &str, resolution: Resolution) -> Result<Window> {
+        Self::create_window_with_framebuffer(name, resolution, true)
+    }
+
+    fn create_window_with_framebuffer(
+        name: &str,
+        resolution: Resolution,
+        srgb: bool,
+    ) -> Result<Window> {
         let name_cstr = CString::new(name)?;
         if resolution.height == 0 || resolution.width == 0 {
             return Err(WinError::CreateWinError(String::from(
@@ -68,6 +88,14 @@ impl GlfwConfig {
         }
 
         let window = unsafe {
+            glfw_bindings::glfwWindowHint(
+                glfw_bindings::GLFW_SRGB_CAPABLE,
+                if srgb {
+                    glfw_bindings::GLFW_TRUE
+                } else {
+                    glfw_bindings::GLFW_FALSE
+                },
+            );
             glfw_bindings::glfwCreateWindow(
                 resolution.width.into(),
                 resolution.height.into(),
@@ -86,6 +114,7 @@ impl GlfwConfig {
             window,
             name: name.to_string(),
             resolution,
+            srgb,
         })
     }
 
@@ -164,6 +193,12 @@ impl Window {
         &self.name
     }
 
+    /// Whether the window was created with `create_srgb_window`.
+    #[must_use]
+    pub fn is_srgb_requested(&self) -> bool {
+        self.srgb
+    }
+
     #[must_use]
     pub fn get_framebuffer_size(&self) -> (i32, i32) {
         let mut width: c_int = 0;
@@ -244,6 +279,17 @@ mod tests {
         let window = config.create_window("test_win_opengl", resolution).unwrap();
         assert_eq!(window.get_resolution(), res_clone);
         assert_eq!(window.get_name(), "test_win_opengl");
+        assert!(!window.is_srgb_requested());
+    }
+
+    #[test]
+    #[serial]
+    fn test_run_srgb_window_glfw() {
+        let config = GlfwConfig::create().unwrap();
+        let window = config
+            .create_srgb_window("test_win_srgb", Resolution::default())
+            .unwrap();
+        assert!(window.is_srgb_requested());
     }
 
     #[test]
@@ -2,11 +2,15 @@ use clap::ValueEnum;
 
 pub mod actions;
 pub mod light;
+pub mod particles;
 pub mod solar_system;
+pub mod text;
 
 #[derive(ValueEnum, Debug, Clone)]
 pub enum DemoList {
     Light,
     Actions,
     SolarSystem,
+    Particles,
+    Text,
 }
@@ -6,7 +6,7 @@ use phoenix::{
             solid::{Cube, Sphere},
             Point,
         },
-        texture::{self, load, Filtering, MinFiltering, Mipmaps, Texture, Wrapping},
+        texture::{self, load, ColorSpace, Filtering, MinFiltering, Mipmaps, Texture, Wrapping},
         transformer::Builder,
         Component, FillMode,
     },
@@ -97,6 +97,7 @@ pub fn start_demo() {
         wrapping_vertical: Wrapping::Repeat,
         min_filtering: MinFiltering::Mipmap(Mipmaps::LinearMipmapLinear),
         max_filtering: Filtering::Nearest,
+        color_space: ColorSpace::Srgb,
     };
 
     let path = "graphic-tests/assets/textures/brickwall.jpg";
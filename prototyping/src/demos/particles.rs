@@ -0,0 +1,126 @@
+use cgmath::vec3;
+use phoenix::{
+    components::{
+        color::RGBA,
+        particles::{Builder, ParticleShape},
+        transformer::Transformer,
+        Blending, Component,
+    },
+    entities::entity::Entity,
+    events::{
+        action::Action,
+        keys_binding::{KEY_A, KEY_D, KEY_E, KEY_Q, KEY_S, KEY_W},
+        user_input::{KeyboardInput, MouseInput},
+    },
+    renderer::opengl::OpenGL,
+    systems::{camera, scene::Scene},
+    window::{GlfwConfig, Resolution},
+};
+use std::rc::Rc;
+
+pub fn start_demo() {
+    let config = GlfwConfig::create().unwrap();
+    let window = Rc::new(
+        config
+            .create_window(
+                "OpenGL",
+                Resolution {
+                    width: 1600,
+                    height: 900,
+                },
+            )
+            .unwrap(),
+    );
+
+    window.set_current();
+    window.set_capture_mouse(true);
+
+    let render = Box::new(OpenGL::new(window.as_ref()).unwrap());
+    let mut scene = Scene::new(window, render);
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_A.into()),
+        Action::CameraUpdateLeft,
+    );
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_D.into()),
+        Action::CameraUpdateRight,
+    );
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_W.into()),
+        Action::CameraUpdateForward,
+    );
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_S.into()),
+        Action::CameraUpdateBackward,
+    );
+    scene
+        .event_manager
+        .bind_key(KeyboardInput::new_key(KEY_Q.into()), Action::CameraUpdateUp);
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_E.into()),
+        Action::CameraUpdateDown,
+    );
+    scene
+        .event_manager
+        .bind_mouse(MouseInput::CursorPos, Action::CameraOrientation(0.0, 0.0));
+
+    let smoke = Builder::new()
+        .with_spawn_rate(40.0)
+        .with_lifetime(2.0, 4.0)
+        .with_velocity(vec3(-0.2, 0.6, -0.2), vec3(0.2, 1.0, 0.2))
+        .with_colors(
+            &RGBA::from_hex(0x80_80_80_A0),
+            &RGBA::from_hex(0x30_30_30_00),
+        )
+        .with_sizes(0.3, 1.2)
+        .with_seed(1)
+        .build()
+        .unwrap();
+
+    let sparks = Builder::new()
+        .with_spawn_rate(200.0)
+        .with_lifetime(0.5, 1.0)
+        .with_velocity(vec3(-2.0, 2.0, -2.0), vec3(2.0, 5.0, 2.0))
+        .with_gravity(vec3(0.0, -9.81, 0.0))
+        .with_colors(
+            &RGBA::from_hex(0xFF_E0_40_FF),
+            &RGBA::from_hex(0xFF_30_00_00),
+        )
+        .with_sizes(0.05, 0.02)
+        .with_blending(Blending::Additive)
+        .with_seed(2)
+        .build()
+        .unwrap();
+
+    let stars = Builder::new()
+        .with_spawn_rate(100.0)
+        .with_max_particles(500)
+        .with_spawn_area(vec3(30.0, 30.0, 30.0))
+        .with_lifetime(5.0, 10.0)
+        .with_velocity(vec3(0.0, 0.0, 0.0), vec3(0.0, 0.0, 0.0))
+        .with_colors(&RGBA::new_white(), &RGBA::from_hex(0xFF_FF_FF_00))
+        .with_sizes(3.0, 1.0)
+        .with_shape(ParticleShape::Points)
+        .with_blending(Blending::Additive)
+        .with_seed(3)
+        .build()
+        .unwrap();
+
+    for (emitter, translation) in [
+        (smoke, vec3(-2.0, -1.0, -6.0)),
+        (sparks, vec3(2.0, -1.0, -6.0)),
+        (stars, vec3(0.0, 0.0, 0.0)),
+    ] {
+        scene.add_entity(Entity::new(vec![
+            Component::Particles(Box::new(emitter)),
+            Component::Transformer(Transformer::new_translate(translation)),
+        ]));
+    }
+
+    scene.set_background_color(RGBA::from_hex(0x00_00_10_FF));
+    scene.register_camera(&camera::Config::default());
+    scene.set_current_window().unwrap();
+    scene.enable_3d();
+
+    scene.start().unwrap();
+}
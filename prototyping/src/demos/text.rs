@@ -0,0 +1,112 @@
+use cgmath::vec3;
+use phoenix::{
+    components::{
+        color::{Color, RGBA},
+        geometry::solid::Cube,
+        text::{Alignment, Builder, Font, Space},
+        transformer::Transformer,
+        Component,
+    },
+    entities::entity::Entity,
+    events::{
+        action::Action,
+        keys_binding::{KEY_A, KEY_D, KEY_E, KEY_Q, KEY_S, KEY_W},
+        user_input::{KeyboardInput, MouseInput},
+    },
+    renderer::opengl::OpenGL,
+    systems::{camera, scene::Scene},
+    window::{GlfwConfig, Resolution},
+};
+use std::{path::Path, rc::Rc};
+
+pub fn start_demo() {
+    let config = GlfwConfig::create().unwrap();
+    let window = Rc::new(
+        config
+            .create_window(
+                "OpenGL",
+                Resolution {
+                    width: 1600,
+                    height: 900,
+                },
+            )
+            .unwrap(),
+    );
+
+    window.set_current();
+    window.set_capture_mouse(true);
+
+    let render = Box::new(OpenGL::new(window.as_ref()).unwrap());
+    let mut scene = Scene::new(window, render);
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_A.into()),
+        Action::CameraUpdateLeft,
+    );
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_D.into()),
+        Action::CameraUpdateRight,
+    );
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_W.into()),
+        Action::CameraUpdateForward,
+    );
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_S.into()),
+        Action::CameraUpdateBackward,
+    );
+    scene
+        .event_manager
+        .bind_key(KeyboardInput::new_key(KEY_Q.into()), Action::CameraUpdateUp);
+    scene.event_manager.bind_key(
+        KeyboardInput::new_key(KEY_E.into()),
+        Action::CameraUpdateDown,
+    );
+    scene
+        .event_manager
+        .bind_mouse(MouseInput::CursorPos, Action::CameraOrientation(0.0, 0.0));
+
+    let path = Path::new("phoenix/assets/fonts/DejaVuSans.ttf");
+    let font = Font::load(path, 32.0).unwrap();
+    let small_font = Font::load(path, 18.0).unwrap();
+
+    let title = Builder::new(font.clone(), "Text rendering\nglyph atlas, kerning: AV To")
+        .with_alignment(Alignment::Center)
+        .build();
+    scene.add_entity(Entity::new(vec![
+        Component::Text(Box::new(title)),
+        Component::Color(Color::from_rgba(RGBA::from_hex(0xFF_E0_40_FF))),
+        Component::Transformer(Transformer::new_translate(vec3(600.0, 40.0, 0.0))),
+    ]));
+
+    let paragraph = Builder::new(
+        small_font.clone(),
+        "Long lines are wrapped between the words to fit the maximal width of the text block.",
+    )
+    .with_max_width(300.0)
+    .with_alignment(Alignment::Right)
+    .build();
+    scene.add_entity(Entity::new(vec![
+        Component::Text(Box::new(paragraph)),
+        Component::Transformer(Transformer::new_translate(vec3(1280.0, 760.0, 0.0))),
+    ]));
+
+    let label = Builder::new(font, "Cube")
+        .with_space(Space::World { line_height: 0.3 })
+        .build();
+    scene.add_entity(Entity::new(vec![
+        Component::Text(Box::new(label)),
+        Component::Transformer(Transformer::new_translate(vec3(-0.4, 0.9, -3.0))),
+    ]));
+    scene.add_entity(Entity::new(vec![
+        Component::Geometry(Box::new(Cube::new(1.0, [0.0, 0.0, -3.0]))),
+        Component::Color(Color::from_rgba(RGBA::from_hex(0x40_80_FF_FF))),
+    ]));
+
+    scene.show_fps(small_font);
+    scene.set_background_color(RGBA::from_hex(0x10_10_20_FF));
+    scene.register_camera(&camera::Config::default());
+    scene.set_current_window().unwrap();
+    scene.enable_3d();
+
+    scene.start().unwrap();
+}
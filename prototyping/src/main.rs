@@ -10,5 +10,7 @@ fn main() {
         DemoList::Light => demos::light::start_demo(),
         DemoList::Actions => demos::actions::start_demo(),
         DemoList::SolarSystem => demos::solar_system::start_demo(),
+        DemoList::Particles => demos::particles::start_demo(),
+        DemoList::Text => demos::text::start_demo(),
     }
 }